@@ -12,6 +12,8 @@ use digest::HashMarker;
 use digest::Output;
 use digest::OutputSizeUser;
 use digest::Update;
+use openssl::hash::Hasher;
+use openssl::hash::MessageDigest;
 use openssl::sha;
 
 #[cfg(feature = "sha1")]
@@ -109,3 +111,54 @@ impl Default for OpenSSLSha1 {
         }
     }
 }
+
+#[cfg(feature = "sha3-256")]
+/// SHA3-256 algorithm
+pub struct Sha3_256 {
+    #[doc(hidden)]
+    _private: (),
+}
+
+/// OpenSSL SHA3-256 implementation.
+///
+/// OpenSSL only offers SHA3-256 through its fallible EVP interface, which
+/// only fails if the algorithm isn't available or OpenSSL is out of memory.
+#[doc(hidden)]
+pub struct OpenSSLSha3_256 {
+    hash: Hasher,
+}
+
+#[cfg(all(feature = "sha3-256", feature = "openssl"))]
+impl_hash_algorithm!(Sha3_256, OpenSSLSha3_256, "sha3-256");
+
+impl Update for OpenSSLSha3_256 {
+    fn update(&mut self, data: &[u8]) {
+        self.hash
+            .update(data)
+            .expect("OpenSSL failed to update SHA3-256 hash");
+    }
+}
+
+impl OutputSizeUser for OpenSSLSha3_256 {
+    type OutputSize = U32;
+}
+
+impl FixedOutput for OpenSSLSha3_256 {
+    fn finalize_into(mut self, out: &mut Output<Self>) {
+        let hash = self
+            .hash
+            .finish()
+            .expect("OpenSSL failed to finish SHA3-256 hash");
+        out.copy_from_slice(&hash);
+    }
+}
+
+impl HashMarker for OpenSSLSha3_256 {}
+
+impl Default for OpenSSLSha3_256 {
+    fn default() -> Self {
+        Self {
+            hash: Hasher::new(MessageDigest::sha3_256()).expect("OpenSSL doesn't support SHA3-256"),
+        }
+    }
+}
@@ -1,4 +1,7 @@
 //! RustCrypto-based cryptography backend.
+//!
+//! BLAKE3 comes from the `blake3` crate rather than RustCrypto, but it
+//! implements the same `digest` traits, so it's provided here too.
 
 use crate::impl_hash_algorithm;
 use crate::sealed::Sealed;
@@ -38,3 +41,23 @@ pub struct Sha256 {
 
 #[cfg(feature = "sha256")]
 impl_hash_algorithm!(Sha256, sha2::Sha256, "sha256");
+
+#[cfg(feature = "sha3-256")]
+/// SHA3-256 algorithm.
+pub struct Sha3_256 {
+    #[doc(hidden)]
+    _private: (),
+}
+
+#[cfg(feature = "sha3-256")]
+impl_hash_algorithm!(Sha3_256, sha3::Sha3_256, "sha3-256");
+
+#[cfg(feature = "blake3")]
+/// BLAKE3 algorithm, with 256-bit output.
+pub struct Blake3 {
+    #[doc(hidden)]
+    _private: (),
+}
+
+#[cfg(feature = "blake3")]
+impl_hash_algorithm!(Blake3, blake3::Hasher, "blake3");
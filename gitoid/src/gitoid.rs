@@ -126,6 +126,26 @@ where
         gitoid_from_async_buffer(H::new(), reader, expected_length).await
     }
 
+    /// Construct a `GitOid` from the raw bytes of an already-computed hash.
+    ///
+    /// This does not hash `bytes`; they must be the hash itself, and their
+    /// length must match the output size of the hash algorithm.
+    pub fn try_from_hash_bytes(bytes: &[u8]) -> Result<GitOid<H, O>> {
+        let expected = <H::Alg as OutputSizeUser>::output_size();
+
+        let hash = GenericArray::from_exact_iter(bytes.iter().copied()).ok_or(
+            Error::UnexpectedHashLength {
+                expected,
+                observed: bytes.len(),
+            },
+        )?;
+
+        Ok(GitOid {
+            _phantom: PhantomData,
+            value: H::array_from_generic(hash),
+        })
+    }
+
     #[cfg(feature = "url")]
     /// Construct a new `GitOid` from a `Url`.
     pub fn try_from_url(url: Url) -> Result<GitOid<H, O>> {
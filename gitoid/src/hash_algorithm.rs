@@ -64,7 +64,7 @@ macro_rules! impl_hash_algorithm {
             }
 
             fn new() -> Self::Alg {
-                Self::Alg::new()
+                <Self::Alg as Digest>::new()
             }
         }
     };
@@ -55,6 +55,8 @@
 //! - `sha1`
 //! - `sha1dc`
 //! - `sha256`
+//! - `sha3-256`
+//! - `blake3`
 //!
 //! `sha1dc` is actually Git's default algorithm, and is equivalent to `sha1`
 //! in _most_ cases. Where it differs is when the hasher detects what it
@@ -105,23 +107,45 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
-#[cfg(not(any(feature = "sha1", feature = "sha1cd", feature = "sha256")))]
+#[cfg(not(any(
+    feature = "sha1",
+    feature = "sha1cd",
+    feature = "sha256",
+    feature = "sha3-256",
+    feature = "blake3"
+)))]
 compile_error!(
-    r#"At least one hash algorithm feature must be active: "sha1", "sha1cd", or "sha256""#
+    r#"At least one hash algorithm feature must be active: "sha1", "sha1cd", "sha256", "sha3-256", or "blake3""#
 );
 
 #[cfg(all(feature = "sha1cd", feature = "boringssl", not(feature = "rustcrypto")))]
 compile_error!(r#"The "boringssl" feature does not support the "sha1cd" algorithm"#);
 
+#[cfg(all(
+    feature = "sha3-256",
+    feature = "boringssl",
+    not(feature = "rustcrypto")
+))]
+compile_error!(r#"The "boringssl" feature does not support the "sha3-256" algorithm"#);
+
 #[cfg(all(feature = "sha1cd", feature = "openssl", not(feature = "rustcrypto")))]
 compile_error!(r#"The "openssl" feature does not support the "sha1cd" algorithm"#);
 
+#[cfg(all(feature = "blake3", not(feature = "rustcrypto")))]
+compile_error!(r#"The "blake3" algorithm requires the "rustcrypto" feature"#);
+
 #[cfg(all(
     feature = "rustcrypto",
-    not(any(feature = "sha1", feature = "sha1cd", feature = "sha256"))
+    not(any(
+        feature = "sha1",
+        feature = "sha1cd",
+        feature = "sha256",
+        feature = "sha3-256",
+        feature = "blake3"
+    ))
 ))]
 compile_error!(
-    r#"The "rustcrypto" feature requires at least one of the following algorithms: "sha1", "sha1cd", or "sha256""#
+    r#"The "rustcrypto" feature requires at least one of the following algorithms: "sha1", "sha1cd", "sha256", "sha3-256", or "blake3""#
 );
 
 #[cfg(not(any(feature = "rustcrypto", feature = "boringssl", feature = "openssl")))]
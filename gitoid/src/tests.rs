@@ -1,10 +1,14 @@
 #![allow(unused_imports)]
 
 use super::*;
+#[cfg(all(feature = "blake3", feature = "rustcrypto"))]
+use crate::rustcrypto::Blake3;
 #[cfg(all(feature = "sha1", feature = "rustcrypto"))]
 use crate::rustcrypto::Sha1;
 #[cfg(all(feature = "sha256", feature = "rustcrypto"))]
 use crate::rustcrypto::Sha256;
+#[cfg(all(feature = "sha3-256", feature = "rustcrypto"))]
+use crate::rustcrypto::Sha3_256;
 #[cfg(feature = "std")]
 use std::fs::File;
 #[cfg(feature = "async")]
@@ -67,6 +71,54 @@ fn generate_sha256_gitoid_from_bytes() {
     );
 }
 
+#[cfg(all(feature = "sha3-256", feature = "rustcrypto", feature = "url"))]
+#[test]
+fn generate_sha3_256_gitoid_from_bytes() -> Result<()> {
+    let result = GitOid::<Sha3_256, Blob>::id_bytes(b"hello world");
+    let url =
+        "gitoid:blob:sha3-256:254d5b29c6d7dc776a6281cc639d94f8762b02c47786ebd59fa2421a2a9599ce";
+
+    assert_eq!(result.to_string(), url);
+    assert_eq!(url.parse::<GitOid<Sha3_256, Blob>>()?, result);
+
+    Ok(())
+}
+
+#[cfg(all(feature = "blake3", feature = "rustcrypto", feature = "url"))]
+#[test]
+fn generate_blake3_gitoid_from_bytes() -> Result<()> {
+    let result = GitOid::<Blake3, Blob>::id_bytes(b"hello world");
+    let expected = blake3::hash(b"blob 11\0hello world");
+
+    assert_eq!(result.as_bytes(), expected.as_bytes());
+    assert_eq!(
+        result.to_string(),
+        format!("gitoid:blob:blake3:{}", expected.to_hex())
+    );
+    assert_eq!(result.to_string().parse::<GitOid<Blake3, Blob>>()?, result);
+
+    Ok(())
+}
+
+#[cfg(all(feature = "sha256", feature = "rustcrypto"))]
+#[test]
+fn sha256_gitoid_from_hash_bytes() {
+    let expected = GitOid::<Sha256, Blob>::id_bytes(b"hello world");
+    let result = GitOid::<Sha256, Blob>::try_from_hash_bytes(expected.as_bytes()).unwrap();
+    assert_eq!(result, expected);
+
+    match GitOid::<Sha256, Blob>::try_from_hash_bytes(&[0; 20]) {
+        Ok(_) => panic!("gitoid construction should fail"),
+        Err(e) => assert!(matches!(
+            e,
+            Error::UnexpectedHashLength {
+                expected: 32,
+                observed: 20
+            }
+        )),
+    }
+}
+
 #[cfg(all(feature = "sha256", feature = "rustcrypto", feature = "std"))]
 #[test]
 fn generate_sha256_gitoid_from_buffer() -> Result<()> {
@@ -0,0 +1,138 @@
+//! Read manifest inputs from compiler build logs.
+//!
+//! Two formats are supported:
+//!
+//! - Makefile-format dependency files (`.d` files), as written by GCC and
+//!   Clang with `-M`, `-MD`, or `-MMD`. Every prerequisite of every rule is
+//!   an input.
+//! - JSON compilation databases (`compile_commands.json`), or a single entry
+//!   from one. The `file` of each entry is an input.
+
+use crate::error::{Error, Result};
+use serde::Deserialize;
+use std::{
+    fs,
+    ops::Not as _,
+    path::{Path, PathBuf},
+};
+
+/// Read the input paths listed in a build log.
+///
+/// Files starting with `[` or `{` are read as a JSON compilation database,
+/// and anything else as a dependency file. Duplicate paths are removed.
+pub fn read_build_log_inputs(path: &Path) -> Result<Vec<PathBuf>> {
+    let contents = fs::read_to_string(path).map_err(|source| Error::BuildLogCouldNotRead {
+        path: path.to_path_buf(),
+        source,
+    })?;
+
+    let inputs = if contents.trim_start().starts_with(['[', '{']) {
+        compile_commands_inputs(&contents).map_err(|source| Error::BuildLogInvalid {
+            path: path.to_path_buf(),
+            source,
+        })?
+    } else {
+        dep_file_inputs(&contents)
+    };
+
+    let mut deduped = Vec::with_capacity(inputs.len());
+
+    for input in inputs {
+        if deduped.contains(&input).not() {
+            deduped.push(input);
+        }
+    }
+
+    Ok(deduped)
+}
+
+/// Get the prerequisites of every rule in a dependency file.
+///
+/// Paths are returned as written, so relative paths are relative to the
+/// directory the compiler ran in.
+fn dep_file_inputs(contents: &str) -> Vec<PathBuf> {
+    let joined = contents.replace("\\\r\n", " ").replace("\\\n", " ");
+    let mut inputs = Vec::new();
+
+    for line in joined.lines() {
+        let Some(prerequisites) = rule_prerequisites(line) else {
+            continue;
+        };
+
+        inputs.extend(split_paths(prerequisites).into_iter().map(PathBuf::from));
+    }
+
+    inputs
+}
+
+/// Get the part of a rule after the separating colon.
+///
+/// The separator is a colon followed by whitespace or the end of the line,
+/// so drive letters in Windows paths like `C:\src` aren't mistaken for it.
+fn rule_prerequisites(line: &str) -> Option<&str> {
+    let bytes = line.as_bytes();
+
+    for (index, byte) in bytes.iter().enumerate() {
+        if *byte == b':' && bytes.get(index + 1).is_none_or(u8::is_ascii_whitespace) {
+            return Some(&line[index + 1..]);
+        }
+    }
+
+    None
+}
+
+/// Split whitespace-separated paths, handling `\ `, `\#`, and `$$` escapes.
+fn split_paths(s: &str) -> Vec<String> {
+    let mut paths = Vec::new();
+    let mut current = String::new();
+    let mut chars = s.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if matches!(chars.peek(), Some(' ' | '#')) => current.extend(chars.next()),
+            '$' if chars.peek() == Some(&'$') => current.extend(chars.next()),
+            c if c.is_whitespace() => {
+                if current.is_empty().not() {
+                    paths.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+
+    if current.is_empty().not() {
+        paths.push(current);
+    }
+
+    paths
+}
+
+/// Get the source file of every entry in a compilation database.
+///
+/// Relative files are resolved against the entry's `directory`.
+fn compile_commands_inputs(contents: &str) -> serde_json::Result<Vec<PathBuf>> {
+    let entries = match serde_json::from_str::<CompileCommands>(contents)? {
+        CompileCommands::Many(entries) => entries,
+        CompileCommands::One(entry) => vec![entry],
+    };
+
+    Ok(entries
+        .into_iter()
+        .map(|entry| entry.directory.join(entry.file))
+        .collect())
+}
+
+/// A compilation database, or a single entry from one.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum CompileCommands {
+    Many(Vec<CompileCommand>),
+    One(CompileCommand),
+}
+
+/// The parts of a compilation database entry needed to find its input.
+#[derive(Debug, Deserialize)]
+struct CompileCommand {
+    directory: PathBuf,
+    file: PathBuf,
+}
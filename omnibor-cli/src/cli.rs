@@ -3,11 +3,18 @@
 use crate::error::Error;
 use clap::{builder::PossibleValue, ValueEnum};
 use clap_verbosity_flag::{InfoLevel, Verbosity};
-use omnibor::{hashes::Sha256, ArtifactId, IntoArtifactId};
+use omnibor::{
+    hashes::Sha256,
+    storage::{merge::OnConflict, FileSystemStorage},
+    ArtifactId, IntoArtifactId,
+};
 use pathbuf::pathbuf;
 use std::{
     default::Default,
+    env::var_os,
     fmt::{Display, Formatter},
+    num::NonZeroUsize,
+    ops::Not,
     path::{Path, PathBuf},
     str::FromStr,
     sync::OnceLock,
@@ -30,8 +37,7 @@ const IMPORTANT: &str = "Important Flags";
     about,
     version,
     propagate_version = true,
-    arg_required_else_help = true,
-    subcommand_required = true
+    arg_required_else_help = true
 )]
 pub struct Args {
     /// Output format
@@ -56,6 +62,22 @@ pub struct Args {
     )]
     console: bool,
 
+    /// Always colorize output, even when not writing to a terminal.
+    #[arg(long = "color", global = true, conflicts_with = "no_color")]
+    color: bool,
+
+    /// Never colorize output. Also set by the 'NO_COLOR' environment variable.
+    #[arg(long = "no-color", global = true)]
+    no_color: bool,
+
+    /// Stop and exit with code 2 if the command runs longer than this.
+    #[arg(long = "timeout", global = true, value_name = "SECONDS")]
+    timeout: Option<u64>,
+
+    /// List the environment variables the CLI reads, with their current values.
+    #[arg(long = "list-env-vars")]
+    list_env_vars: bool,
+
     #[command(flatten)]
     verbosity: Verbosity<InfoLevel>,
 
@@ -79,11 +101,26 @@ impl Args {
         self.console
     }
 
+    /// Get whether to colorize output, if explicitly chosen.
+    ///
+    /// The flags take precedence over the `NO_COLOR` environment variable,
+    /// per <https://no-color.org/>. `None` means colorize only when writing
+    /// to a terminal.
+    pub fn color(&self) -> Option<bool> {
+        if self.color {
+            Some(true)
+        } else if self.no_color || var_os("NO_COLOR").is_some_and(|value| value.is_empty().not()) {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
     /// Get the chosen OmniBOR root directory.
     pub fn dir(&self) -> Option<&Path> {
         self.dir.as_deref().or_else(|| {
             DEFAULT_DIR
-                .get_or_init(|| dirs::data_dir().map(|cache_dir| pathbuf![&cache_dir, "omnibor"]))
+                .get_or_init(|| FileSystemStorage::default_root().ok())
                 .as_deref()
         })
     }
@@ -97,10 +134,44 @@ impl Args {
         })
     }
 
+    /// Get the time limit for running the command, if any.
+    pub fn timeout(&self) -> Option<u64> {
+        self.timeout
+    }
+
+    /// Get whether to list environment variables instead of running a command.
+    pub fn list_env_vars(&self) -> bool {
+        self.list_env_vars
+    }
+
     /// Get the selected subcommand.
+    ///
+    /// A subcommand is always present unless `--list-env-vars` is set; see
+    /// [`Args::parse_checked`].
     pub fn command(&self) -> &Command {
         self.command.as_ref().unwrap()
     }
+
+    /// Parse the arguments, requiring a subcommand unless `--list-env-vars`
+    /// is set.
+    ///
+    /// Clap's own `subcommand_required` can't be relaxed for a single flag,
+    /// so the check is done here instead. Exits with a usage error if the
+    /// subcommand is missing.
+    pub fn parse_checked() -> Self {
+        let args = <Args as clap::Parser>::parse();
+
+        if args.command.is_none() && args.list_env_vars.not() {
+            <Args as clap::CommandFactory>::command()
+                .error(
+                    clap::error::ErrorKind::MissingSubcommand,
+                    "a subcommand is required unless '--list-env-vars' is set",
+                )
+                .exit();
+        }
+
+        args
+    }
 }
 
 #[derive(Debug, clap::Subcommand)]
@@ -148,6 +219,38 @@ pub struct IdArgs {
         help_heading = IMPORTANT
     )]
     hash: Option<SelectedHash>,
+
+    /// Show how long each Artifact ID took to compute, and how many bytes were hashed.
+    #[arg(long = "show-timing")]
+    pub show_timing: bool,
+
+    /// Skip paths matching a glob when identifying a directory (repeatable).
+    #[arg(long = "exclude-pattern", value_name = "GLOB")]
+    pub exclude_patterns: Vec<String>,
+
+    /// Skip paths matching patterns in a `.gitignore`-style file.
+    #[arg(long = "exclude-from", value_name = "FILE")]
+    pub exclude_from: Option<PathBuf>,
+
+    /// Order of output when identifying a directory.
+    #[arg(long = "sort", value_name = "ORDER")]
+    sort: Option<IdSort>,
+
+    /// Print only the hash of each Artifact ID, as hex unless `--binary` is set.
+    #[arg(long = "hash-only")]
+    pub hash_only: bool,
+
+    /// With `--hash-only`, write the raw hash bytes, sorted by path.
+    #[arg(
+        long = "binary",
+        requires = "hash_only",
+        conflicts_with_all = ["hex", "sort", "show_timing"]
+    )]
+    pub binary: bool,
+
+    /// With `--hash-only`, print each hash as hex, like `--format oid`.
+    #[arg(long = "hex", requires = "hash_only")]
+    pub hex: bool,
 }
 
 impl IdArgs {
@@ -155,6 +258,27 @@ impl IdArgs {
     pub fn hash(&self) -> SelectedHash {
         self.hash.unwrap_or_default()
     }
+
+    /// Get the output order to use.
+    ///
+    /// Binary hashes are always sorted by path, since they're concatenated.
+    pub fn sort(&self) -> IdSort {
+        if self.binary {
+            return IdSort::Path;
+        }
+
+        self.sort.unwrap_or_default()
+    }
+
+    /// Get the output format to use, overriding the global format if only
+    /// hashes are requested.
+    pub fn format(&self, global: Format) -> Format {
+        match (self.hash_only, self.binary) {
+            (true, true) => Format::Binary,
+            (true, false) => Format::Oid,
+            (false, _) => global,
+        }
+    }
 }
 
 #[derive(Debug, Clone, clap::Args)]
@@ -180,6 +304,8 @@ pub struct ManifestArgs {
 pub enum ManifestCommand {
     /// Create a new manifest and add it to the store
     Create(ManifestCreateArgs),
+    /// Check a file's embedded manifest against the store
+    VerifyEmbedded(ManifestVerifyEmbeddedArgs),
 }
 
 #[derive(Debug, clap::Args)]
@@ -199,7 +325,28 @@ pub struct ManifestCreateArgs {
 
     /// The target the manifest is describing.
     #[arg(short = 't', long = "target", help_heading = IMPORTANT)]
-    pub target: PathBuf,
+    pub target: Option<PathBuf>,
+
+    /// JSON file specifying inputs and target; command-line flags take precedence.
+    #[arg(long = "json-inputs", value_name = "FILE")]
+    pub json_inputs: Option<PathBuf>,
+
+    /// Compiler dependency file (`.d`) or compilation database whose listed files are inputs.
+    #[cfg(feature = "build-log-parse")]
+    #[arg(long = "inputs-from-build-log", value_name = "FILE")]
+    pub inputs_from_build_log: Option<PathBuf>,
+
+    /// File listing inputs, one path or Artifact ID per line; `#` lines are comments.
+    #[arg(long = "inputs-from-file-list", value_name = "PATH")]
+    pub inputs_from_file_list: Option<PathBuf>,
+
+    /// Separate `--inputs-from-file-list` entries with NUL bytes instead of newlines.
+    #[arg(long = "null-separated", requires = "inputs_from_file_list")]
+    pub null_separated: bool,
+
+    /// Print the JSON schema for `--json-inputs` files and exit.
+    #[arg(long = "print-schema")]
+    pub print_schema: bool,
 
     /// Do not store the manifest in the local store.
     #[arg(long = "no-store", help_heading = IMPORTANT)]
@@ -213,11 +360,51 @@ pub struct ManifestCreateArgs {
     #[arg(short = 'o', long = "output", help_heading = IMPORTANT, value_name = "DIR")]
     pub output: Option<PathBuf>,
 
+    /// File to write manifest out to, instead of a directory.
+    #[arg(long = "output-file", value_name = "FILE", conflicts_with_all = ["output", "no_out"])]
+    pub output_file: Option<PathBuf>,
+
+    /// Format to write the manifest out in [default: inferred from `--output-file`, or omnibor]
+    #[arg(long = "output-format", value_name = "FORMAT")]
+    pub output_format: Option<ManifestFormat>,
+
+    /// Number of inputs to hash at once.
+    #[arg(long = "parallel", value_name = "N", default_value_t = NonZeroUsize::MIN)]
+    pub parallel: NonZeroUsize,
+
     /// Hash algorithm to use for Artifact IDs.
     #[arg(short = 'H', long = "hash", env = "OMNIBOR_HASH", help_heading = IMPORTANT)]
     pub hash: Option<SelectedHash>,
 }
 
+impl ManifestCreateArgs {
+    /// Get the format to write the manifest out in.
+    ///
+    /// If no format is given, a `.json` output file is written as JSON.
+    pub fn output_format(&self) -> ManifestFormat {
+        if let Some(format) = self.output_format {
+            return format;
+        }
+
+        match self
+            .output_file
+            .as_deref()
+            .and_then(|path| path.extension())
+        {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => ManifestFormat::Json,
+            _ => ManifestFormat::default(),
+        }
+    }
+}
+
+#[derive(Debug, clap::Args)]
+#[command(arg_required_else_help = true)]
+pub struct ManifestVerifyEmbeddedArgs {
+    /// Text file with an embedded manifest ID.
+    #[arg(long = "file", value_name = "PATH", help_heading = IMPORTANT)]
+    pub file: PathBuf,
+}
+
 #[derive(Debug, clap::Args)]
 #[command(arg_required_else_help = true)]
 pub struct StoreArgs {
@@ -231,8 +418,22 @@ pub enum StoreCommand {
     Add(StoreAddArgs),
     /// Remove an Input Manifest from the store.
     Remove(StoreRemoveArgs),
+    /// List the Input Manifests in the store.
+    List(StoreListArgs),
     /// Review the log of changes to the store.
     Log(StoreLogArgs),
+    /// Merge the Input Manifests from another store into this one.
+    Merge(StoreMergeArgs),
+    /// Remove Input Manifests not reachable from the given roots.
+    Gc(StoreGcArgs),
+    /// Save or restore checkpoints of the store.
+    Checkpoint(StoreCheckpointArgs),
+    /// Copy the store to a new directory.
+    Backup(StoreBackupArgs),
+    /// Create the store from a backup.
+    Restore(StoreRestoreArgs),
+    /// Rebuild the target index, dropping corrupted entries.
+    Repair(StoreRepairArgs),
 }
 
 #[derive(Debug, clap::Args)]
@@ -241,12 +442,104 @@ pub struct StoreAddArgs {}
 
 #[derive(Debug, clap::Args)]
 #[command(arg_required_else_help = true)]
-pub struct StoreRemoveArgs {}
+pub struct StoreRemoveArgs {
+    /// Remove every manifest from the store.
+    #[arg(long = "all", help_heading = IMPORTANT)]
+    pub all: bool,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct StoreListArgs {
+    /// Only list manifests for this target (repeatable; any may match).
+    #[arg(long = "filter-target", value_name = "AID|PATH")]
+    pub filter_targets: Vec<IdentifiableArg>,
+
+    /// Only list manifests with this input (repeatable; any may match).
+    #[arg(long = "filter-input", value_name = "AID")]
+    pub filter_inputs: Vec<ArtifactId<Sha256>>,
+}
 
 #[derive(Debug, clap::Args)]
 #[command(arg_required_else_help = true)]
 pub struct StoreLogArgs {}
 
+#[derive(Debug, clap::Args)]
+#[command(arg_required_else_help = true)]
+pub struct StoreMergeArgs {
+    /// Root directory of the store to merge from.
+    #[arg(long = "from", value_name = "DIR", help_heading = IMPORTANT)]
+    pub from: PathBuf,
+
+    /// What to do when a manifest has a different target in each store.
+    #[arg(long = "on-conflict", value_name = "ACTION")]
+    on_conflict: Option<MergeConflict>,
+}
+
+impl StoreMergeArgs {
+    /// Get what to do on conflict.
+    pub fn on_conflict(&self) -> MergeConflict {
+        self.on_conflict.unwrap_or_default()
+    }
+}
+
+#[derive(Debug, clap::Args)]
+#[command(arg_required_else_help = true)]
+pub struct StoreGcArgs {
+    /// Artifact or manifest to keep, along with everything it reaches (repeatable).
+    #[arg(long = "root", value_name = "AID", required = true, help_heading = IMPORTANT)]
+    pub roots: Vec<ArtifactId<Sha256>>,
+}
+
+#[derive(Debug, clap::Args)]
+#[command(arg_required_else_help = true)]
+pub struct StoreBackupArgs {
+    /// Directory to write the backup to; must be missing or empty.
+    #[arg(long = "destination", value_name = "DIR", help_heading = IMPORTANT)]
+    pub destination: PathBuf,
+}
+
+#[derive(Debug, clap::Args)]
+#[command(arg_required_else_help = true)]
+pub struct StoreRestoreArgs {
+    /// Directory holding the backup to restore from.
+    #[arg(long = "source", value_name = "DIR", help_heading = IMPORTANT)]
+    pub source: PathBuf,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct StoreRepairArgs {}
+
+#[derive(Debug, clap::Args)]
+#[command(arg_required_else_help = true)]
+pub struct StoreCheckpointArgs {
+    #[clap(subcommand)]
+    pub command: StoreCheckpointCommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum StoreCheckpointCommand {
+    /// Save the current state of the store.
+    Create(StoreCheckpointCreateArgs),
+    /// Roll the store back to the latest checkpoint with a label.
+    Restore(StoreCheckpointRestoreArgs),
+}
+
+#[derive(Debug, clap::Args)]
+#[command(arg_required_else_help = true)]
+pub struct StoreCheckpointCreateArgs {
+    /// Label for the checkpoint.
+    #[arg(value_name = "LABEL")]
+    pub label: String,
+}
+
+#[derive(Debug, clap::Args)]
+#[command(arg_required_else_help = true)]
+pub struct StoreCheckpointRestoreArgs {
+    /// Label of the checkpoint to restore.
+    #[arg(value_name = "LABEL")]
+    pub label: String,
+}
+
 #[derive(Debug, clap::Args)]
 #[command(arg_required_else_help = true)]
 pub struct DebugArgs {
@@ -268,6 +561,10 @@ pub struct DebugPathsArgs {
         help_heading = IMPORTANT
     )]
     pub keys: Vec<String>,
+
+    /// Get every path the CLI uses, including those inside the store.
+    #[arg(short = 'a', long = "all", conflicts_with = "keys", help_heading = IMPORTANT)]
+    pub all: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -329,6 +626,13 @@ pub enum Format {
     Short,
     /// JSON format
     Json,
+    /// Just the hex hash of each Artifact ID, without the `gitoid` prefix
+    Oid,
+    /// Comma-separated values, with a header row (for `store list`)
+    Csv,
+    /// The raw bytes of each Artifact ID's hash, from `artifact id --hash-only --binary`
+    #[value(skip)]
+    Binary,
 }
 
 to_and_from_string!(Format);
@@ -342,6 +646,53 @@ pub enum SelectedHash {
 
 to_and_from_string!(SelectedHash);
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum IdSort {
+    /// Sort by Artifact ID
+    Hash,
+    /// Sort by file path
+    Path,
+    /// Print each file as soon as it's identified
+    #[default]
+    None,
+}
+
+to_and_from_string!(IdSort);
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum ManifestFormat {
+    /// The OmniBOR Input Manifest text format
+    #[default]
+    Omnibor,
+    /// JSON format, with the target and each input's IDs
+    Json,
+}
+
+to_and_from_string!(ManifestFormat);
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum MergeConflict {
+    /// Keep the target already in this store
+    #[default]
+    Keep,
+    /// Replace the target with the one from the other store
+    Overwrite,
+    /// Stop merging with an error
+    Error,
+}
+
+to_and_from_string!(MergeConflict);
+
+impl From<MergeConflict> for OnConflict {
+    fn from(value: MergeConflict) -> Self {
+        match value {
+            MergeConflict::Keep => OnConflict::Keep,
+            MergeConflict::Overwrite => OnConflict::Overwrite,
+            MergeConflict::Error => OnConflict::Error,
+        }
+    }
+}
+
 fn possible_value(value: Option<PossibleValue>) -> String {
     match value {
         Some(value) => value.get_name().to_string(),
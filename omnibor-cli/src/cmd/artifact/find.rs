@@ -27,6 +27,7 @@ pub async fn run(app: &App, args: &FindArgs) -> Result<()> {
         app.print_tx.clone(),
         app.args.format(),
         path.to_path_buf(),
+        None,
     ));
 
     let mut join_set = JoinSet::new();
@@ -4,17 +4,34 @@ use crate::{app::App, cli::IdArgs, error::Result, fs::*};
 
 /// Run the `artifact id` subcommand.
 pub async fn run(app: &App, args: &IdArgs) -> Result<()> {
+    let format = args.format(app.args.format());
     let mut file = open_async_file(&args.path).await?;
 
     if file_is_dir(&file, &args.path).await? {
-        id_directory(app, args.hash(), &app.print_tx, &args.path).await?;
+        let exclude = build_exclusions(
+            &args.path,
+            &args.exclude_patterns,
+            args.exclude_from.as_deref(),
+        )?;
+
+        id_directory(
+            app,
+            format,
+            args.hash(),
+            args.show_timing,
+            exclude,
+            args.sort(),
+            &args.path,
+        )
+        .await?;
     } else {
         id_file(
             &app.print_tx,
             &mut file,
             &args.path,
-            app.args.format(),
+            format,
             args.hash(),
+            args.show_timing,
         )
         .await?;
     }
@@ -1,4 +1,4 @@
-//! The `debug config` command, which helps debug the CLI configuration.
+//! The `debug paths` command, which helps debug the CLI configuration.
 
 use crate::{
     app::App,
@@ -6,34 +6,69 @@ use crate::{
     error::{Error, Result},
     print::{paths::PathsMsg, PrinterCmd},
 };
+use omnibor::storage::FileSystemStorage;
+use pathbuf::pathbuf;
 use std::{collections::HashMap, ops::Not, path::Path};
 
+/// The paths printed when no keys are requested and `--all` isn't set.
+const DEFAULT_KEYS: &[&str] = &["dir", "config"];
+
 /// Run the `debug paths` subcommand.
 pub async fn run(app: &App, args: &DebugPathsArgs) -> Result<()> {
     let root = app.args.dir().ok_or(Error::NoRoot)?.to_path_buf();
 
+    // These mirror the layout of `FileSystemStorage` under the root.
+    let manifests = pathbuf![&root, "manifests"];
+    let targets = pathbuf![&root, "targets"];
+    let targets_temp = pathbuf![&root, "targets.temp"];
+    let lock = pathbuf![&root, "lock"];
+    let checkpoints = pathbuf![&root, "checkpoints"];
+
     let mut to_insert: HashMap<&'static str, Option<&Path>> = HashMap::new();
     to_insert.insert("dir", Some(&root));
     to_insert.insert("config", app.args.config());
+    to_insert.insert("manifests", Some(&manifests));
+    to_insert.insert("targets", Some(&targets));
+    to_insert.insert("targets_temp", Some(&targets_temp));
+    to_insert.insert("lock", Some(&lock));
+    to_insert.insert("checkpoints", Some(&checkpoints));
 
     let mut msg = PathsMsg::new();
 
     to_insert
         .into_iter()
         .filter(|(key, _)| {
-            if args.keys.is_empty().not() {
+            if args.all {
+                true
+            } else if args.keys.is_empty().not() {
                 let key: String = key.to_string();
                 args.keys.contains(&key)
             } else {
-                // Keep everything if there's no filter list.
-                true
+                DEFAULT_KEYS.contains(key)
             }
         })
         .for_each(|(key, path)| msg.insert(key, path));
 
+    // Report whether the root holds a usable store alongside the root itself.
+    if args.all || args.keys.is_empty() || args.keys.iter().any(|key| key == "dir") {
+        msg.set_store_status(store_status(&root));
+    }
+
     app.print_tx
         .send(PrinterCmd::msg(msg, app.args.format()))
         .await?;
 
     Ok(())
 }
+
+/// Check the store at the root, without creating it if it's missing.
+fn store_status(root: &Path) -> &'static str {
+    let storage = FileSystemStorage::new_unchecked(root);
+
+    match storage.is_valid_store() {
+        Ok(true) => "valid",
+        Ok(false) if storage.root_exists().not() => "missing",
+        Ok(false) => "invalid",
+        Err(_) => "inaccessible",
+    }
+}
@@ -0,0 +1,20 @@
+//! The `--list-env-vars` flag, which documents the CLI's environment variables.
+
+use crate::{
+    app::App,
+    config::ENV_VARS,
+    error::Result,
+    print::{env_vars::EnvVarsMsg, PrinterCmd},
+};
+
+/// Print the environment variables the CLI reads, with their current values.
+pub async fn run(app: &App) -> Result<()> {
+    app.print_tx
+        .send(PrinterCmd::msg(
+            EnvVarsMsg::new(ENV_VARS),
+            app.args.format(),
+        ))
+        .await?;
+
+    Ok(())
+}
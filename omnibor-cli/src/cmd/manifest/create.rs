@@ -1,51 +1,276 @@
 //! The `manifest create` command, which creates manifests.
 
+#[cfg(feature = "build-log-parse")]
+use crate::build_log::read_build_log_inputs;
 use crate::{
     app::App,
-    cli::ManifestCreateArgs,
+    cli::{IdentifiableArg, ManifestCreateArgs, ManifestFormat},
     error::{Error, Result},
+    fs::read_input_list,
+    print::{schema::SchemaMsg, PrinterCmd},
 };
+use futures_util::{stream::FuturesUnordered, StreamExt};
 use omnibor::{
     embedding::{EmbeddingMode, NoEmbed},
     hashes::Sha256,
     storage::{FileSystemStorage, Storage},
-    ArtifactId, InputManifestBuilder, IntoArtifactId, ShouldStore,
+    ArtifactId, InputManifest, InputManifestBuilder, IntoArtifactId, ShouldStore,
 };
 use pathbuf::pathbuf;
+use serde::Deserialize;
+use serde_json::json;
 use std::{
     env::current_dir,
-    fs::File,
+    fs::{self, File},
     io::Write,
     ops::Not as _,
     path::{Path, PathBuf},
+    str::FromStr,
 };
+use tokio::task::spawn_blocking;
 use tracing::info;
 
 /// Run the `manifest create` subcommand.
 pub async fn run(app: &App, args: &ManifestCreateArgs) -> Result<()> {
+    if args.print_schema {
+        app.print_tx
+            .send(PrinterCmd::msg(
+                SchemaMsg {
+                    schema: json_inputs_schema(),
+                },
+                app.args.format(),
+            ))
+            .await?;
+        return Ok(());
+    }
+
     if args.no_store && args.no_out {
         return Err(Error::NoStoreAndNoOut);
     }
 
+    let spec = CreateSpec::from_args(args)?;
+
+    if spec.embed {
+        return Err(Error::EmbeddingUnsupported);
+    }
+
     let root = app.args.dir().ok_or(Error::NoRoot)?;
     let storage = FileSystemStorage::new(root).map_err(Error::StorageInitFailed)?;
     let builder = InputManifestBuilder::<Sha256, NoEmbed, _>::with_storage(storage);
-    create_with_builder(args, builder)?;
+    let inputs = identify_inputs(&spec.inputs, args.parallel.get()).await?;
+    create_with_builder(args, &spec, &inputs, builder)?;
     Ok(())
 }
 
+/// The inputs and target for a new manifest, merged from the
+/// command line and an optional JSON inputs file.
+struct CreateSpec {
+    inputs: Vec<IdentifiableArg>,
+    target: PathBuf,
+    embed: bool,
+}
+
+impl CreateSpec {
+    /// Merge the JSON inputs file, if any, with the command-line flags.
+    ///
+    /// Inputs from every source are combined, while a `--target` flag
+    /// overrides any target from the file.
+    fn from_args(args: &ManifestCreateArgs) -> Result<Self> {
+        let json = match &args.json_inputs {
+            Some(path) => Some(JsonInputs::from_path(path)?),
+            None => None,
+        };
+
+        let (mut inputs, json_target, embed) = match json {
+            Some(json) => (json.inputs, json.target, json.embed),
+            None => (Vec::new(), None, false),
+        };
+
+        #[cfg(feature = "build-log-parse")]
+        if let Some(path) = &args.inputs_from_build_log {
+            inputs.extend(
+                read_build_log_inputs(path)?
+                    .into_iter()
+                    .map(IdentifiableArg::Path),
+            );
+        }
+
+        if let Some(path) = &args.inputs_from_file_list {
+            inputs.extend(read_input_list(path, args.null_separated)?);
+        }
+
+        inputs.extend(args.inputs.iter().cloned());
+
+        let target = args.target.clone().or(json_target).ok_or(Error::NoTarget)?;
+
+        Ok(CreateSpec {
+            inputs,
+            target,
+            embed,
+        })
+    }
+}
+
+/// The validated contents of a `--json-inputs` file.
+struct JsonInputs {
+    inputs: Vec<IdentifiableArg>,
+    target: Option<PathBuf>,
+    embed: bool,
+}
+
+impl JsonInputs {
+    /// Read and validate a JSON inputs file.
+    fn from_path(path: &Path) -> Result<Self> {
+        let contents = fs::read(path).map_err(|source| Error::JsonInputsCouldNotRead {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+        let raw: RawJsonInputs =
+            serde_json::from_slice(&contents).map_err(|source| Error::JsonInputsInvalid {
+                path: path.to_path_buf(),
+                source,
+            })?;
+
+        let inputs = raw
+            .inputs
+            .into_iter()
+            .map(|input| match input {
+                RawJsonInput::Path { path } => Ok(IdentifiableArg::Path(path)),
+                RawJsonInput::ArtifactId { artifact_id } => ArtifactId::from_str(&artifact_id)
+                    .map(IdentifiableArg::ArtifactId)
+                    .map_err(|source| Error::JsonInputsInvalidArtifactId {
+                        path: path.to_path_buf(),
+                        source,
+                    }),
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(JsonInputs {
+            inputs,
+            target: raw.target,
+            embed: raw.embed,
+        })
+    }
+}
+
+/// The JSON inputs file, as deserialized before validation.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawJsonInputs {
+    inputs: Vec<RawJsonInput>,
+    #[serde(default)]
+    target: Option<PathBuf>,
+    #[serde(default)]
+    embed: bool,
+}
+
+/// A single input in the JSON inputs file.
+#[derive(Debug, Deserialize)]
+#[serde(untagged, deny_unknown_fields)]
+enum RawJsonInput {
+    Path { path: PathBuf },
+    ArtifactId { artifact_id: String },
+}
+
+/// The JSON schema for `--json-inputs` files.
+fn json_inputs_schema() -> serde_json::Value {
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "omnibor manifest create inputs",
+        "type": "object",
+        "properties": {
+            "inputs": {
+                "description": "Inputs to record in the manifest.",
+                "type": "array",
+                "items": {
+                    "oneOf": [
+                        {
+                            "type": "object",
+                            "properties": {
+                                "path": {
+                                    "description": "Path to a file to identify.",
+                                    "type": "string"
+                                }
+                            },
+                            "required": ["path"],
+                            "additionalProperties": false
+                        },
+                        {
+                            "type": "object",
+                            "properties": {
+                                "artifact_id": {
+                                    "description": "A `gitoid`-scheme Artifact ID URL.",
+                                    "type": "string"
+                                }
+                            },
+                            "required": ["artifact_id"],
+                            "additionalProperties": false
+                        }
+                    ]
+                }
+            },
+            "target": {
+                "description": "The target the manifest is describing. Overridden by --target.",
+                "type": "string"
+            },
+            "embed": {
+                "description": "Whether to embed the manifest ID in the target.",
+                "type": "boolean",
+                "default": false
+            }
+        },
+        "required": ["inputs"],
+        "additionalProperties": false
+    })
+}
+
+/// Identify the inputs, hashing up to `parallel` of them at once.
+async fn identify_inputs(
+    inputs: &[IdentifiableArg],
+    parallel: usize,
+) -> Result<Vec<ArtifactId<Sha256>>> {
+    let mut pending = inputs.iter().cloned();
+    let mut running = FuturesUnordered::new();
+    let mut aids = Vec::with_capacity(inputs.len());
+
+    loop {
+        while running.len() < parallel {
+            let Some(input) = pending.next() else {
+                break;
+            };
+
+            running.push(spawn_blocking(move || input.into_artifact_id()));
+        }
+
+        let Some(result) = running.next().await else {
+            break;
+        };
+
+        let aid = result
+            .map_err(Error::CouldNotJoinWorker)?
+            .map_err(Error::IdFailed)?;
+        aids.push(aid);
+    }
+
+    // Tasks finish in any order, so sort to keep the result deterministic.
+    aids.sort();
+    Ok(aids)
+}
+
 fn create_with_builder<E, S>(
     args: &ManifestCreateArgs,
+    spec: &CreateSpec,
+    inputs: &[ArtifactId<Sha256>],
     mut builder: InputManifestBuilder<Sha256, E, S>,
 ) -> Result<()>
 where
     E: EmbeddingMode,
     S: Storage<Sha256>,
 {
-    for input in &args.inputs {
-        let aid = input.clone().into_artifact_id().map_err(Error::IdFailed)?;
+    for aid in inputs {
         builder
-            .add_relation(aid)
+            .add_relation(*aid)
             .map_err(Error::AddRelationFailed)?;
     }
 
@@ -56,19 +281,27 @@ where
     };
 
     let linked_manifest = builder
-        .finish(&args.target, should_store)
+        .finish(&spec.target, should_store)
         .map_err(Error::ManifestBuildFailed)?;
 
     if args.no_out.not() {
-        let path = manifest_file_path(args.output.as_deref(), linked_manifest.target_aid())?;
+        let format = args.output_format();
+        let path = match &args.output_file {
+            Some(path) => path.clone(),
+            None => {
+                manifest_file_path(args.output.as_deref(), linked_manifest.target_aid(), format)?
+            }
+        };
+        let contents = manifest_contents(
+            linked_manifest.manifest(),
+            linked_manifest.manifest_aid(),
+            format,
+        );
 
         let mut output_file = match File::create_new(&path) {
             Ok(file) => file,
             Err(source) => {
-                let mut existing_file = File::open(&path).unwrap();
-                let existing_file_aid =
-                    ArtifactId::<Sha256>::id_reader(&mut existing_file).unwrap();
-                if existing_file_aid == linked_manifest.manifest_aid() {
+                if fs::read(&path).is_ok_and(|existing| existing == contents) {
                     info!("matching manifest already found at '{}'", path.display());
                     return Ok(());
                 } else {
@@ -81,8 +314,7 @@ where
         };
 
         output_file
-            // SAFETY: We just constructed the manifest, so we know it's fine.
-            .write_all(&linked_manifest.manifest().as_bytes().unwrap())
+            .write_all(&contents)
             .map_err(|source| Error::CantWriteManifest {
                 path: path.to_path_buf(),
                 source,
@@ -98,7 +330,49 @@ where
     Ok(())
 }
 
-fn manifest_file_path(output: Option<&Path>, target_aid: ArtifactId<Sha256>) -> Result<PathBuf> {
+/// Get the contents to write out for a manifest in the given format.
+///
+/// The stored manifest is always in the OmniBOR format; this only affects
+/// the file written by `manifest create`.
+fn manifest_contents(
+    manifest: &InputManifest<Sha256>,
+    manifest_aid: ArtifactId<Sha256>,
+    format: ManifestFormat,
+) -> Vec<u8> {
+    match format {
+        // SAFETY: We just constructed the manifest, so we know it's fine.
+        ManifestFormat::Omnibor => manifest.as_bytes().unwrap(),
+        ManifestFormat::Json => {
+            let relations = manifest
+                .relations()
+                .iter()
+                .map(|relation| {
+                    json!({
+                        "artifact": relation.artifact().to_string(),
+                        "manifest": relation.manifest().map(|aid| aid.to_string()),
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            let json = json!({
+                "manifest": manifest_aid.to_string(),
+                "target": manifest.target().map(|aid| aid.to_string()),
+                "relations": relations,
+            });
+
+            // SAFETY: Serializing a `Value` to a `Vec` can't fail.
+            let mut contents = serde_json::to_vec_pretty(&json).unwrap();
+            contents.push(b'\n');
+            contents
+        }
+    }
+}
+
+fn manifest_file_path(
+    output: Option<&Path>,
+    target_aid: ArtifactId<Sha256>,
+    format: ManifestFormat,
+) -> Result<PathBuf> {
     let dir = match &output {
         Some(dir) => dir.to_path_buf(),
         None => match current_dir() {
@@ -107,5 +381,10 @@ fn manifest_file_path(output: Option<&Path>, target_aid: ArtifactId<Sha256>) ->
         },
     };
 
-    Ok(pathbuf![&dir, &target_aid.as_file_name()])
+    let file_name = match format {
+        ManifestFormat::Omnibor => target_aid.as_file_name(),
+        ManifestFormat::Json => target_aid.as_file_name().with_extension("manifest.json"),
+    };
+
+    Ok(pathbuf![&dir, &file_name])
 }
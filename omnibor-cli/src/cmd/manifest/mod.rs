@@ -1 +1,2 @@
 pub mod create;
+pub mod verify_embedded;
@@ -0,0 +1,75 @@
+//! The `manifest verify-embedded` command, which checks a file's embedded manifest.
+
+use crate::{
+    app::App,
+    cli::ManifestVerifyEmbeddedArgs,
+    error::{Error, Result},
+    print::{verified::VerifiedMsg, PrinterCmd},
+};
+use omnibor::{
+    embedding::{verify_target, EmbedVerifyResult},
+    hashes::Sha256,
+    storage::{FileSystemStorage, Storage},
+    ArtifactId, IntoArtifactId,
+};
+use std::ops::Not as _;
+
+/// Run the `manifest verify-embedded` subcommand.
+pub async fn run(app: &App, args: &ManifestVerifyEmbeddedArgs) -> Result<()> {
+    let root = app.args.dir().ok_or(Error::NoRoot)?;
+    let storage = FileSystemStorage::new(root).map_err(Error::StorageInitFailed)?;
+    let path = &args.file;
+
+    let embedded = verify_target(path).map_err(|source| Error::EmbeddedReadFailed {
+        path: path.clone(),
+        source,
+    })?;
+
+    let manifest_aid: ArtifactId<Sha256> = match embedded {
+        EmbedVerifyResult::Embedded(manifest_aid) => manifest_aid,
+        EmbedVerifyResult::NotEmbedded | EmbedVerifyResult::FormatUnsupported => {
+            return Err(Error::NoEmbeddedManifest { path: path.clone() });
+        }
+    };
+
+    let target_aid = path
+        .as_path()
+        .into_artifact_id()
+        .map_err(|source| Error::FileFailedToId {
+            path: path.clone(),
+            source,
+        })?;
+
+    if storage.contains_manifest(manifest_aid).not() {
+        return Err(Error::EmbeddedManifestNotStored(manifest_aid.to_string()));
+    }
+
+    // The stored target is the ID of the file after embedding, so it should
+    // match the file as it is now.
+    let stored_target_aid = storage
+        .get_target_for_manifest(manifest_aid)
+        .map_err(Error::ListFailed)?;
+
+    if let Some(stored_target_aid) = stored_target_aid {
+        if stored_target_aid != target_aid {
+            return Err(Error::EmbeddedTargetMismatch {
+                path: path.clone(),
+                expected: stored_target_aid.to_string(),
+                found: target_aid.to_string(),
+            });
+        }
+    }
+
+    app.print_tx
+        .send(PrinterCmd::msg(
+            VerifiedMsg {
+                path: path.clone(),
+                manifest_aid,
+                target_aid,
+            },
+            app.args.format(),
+        ))
+        .await?;
+
+    Ok(())
+}
@@ -2,5 +2,6 @@
 
 pub mod artifact;
 pub mod debug;
+pub mod env_vars;
 pub mod manifest;
 pub mod store;
@@ -0,0 +1,34 @@
+//! The `store backup` command, which copies the store to a new directory.
+
+use crate::{
+    app::App,
+    cli::StoreBackupArgs,
+    error::{Error, Result},
+    print::{backup::BackupMsg, PrinterCmd},
+};
+use omnibor::{
+    hashes::Sha256,
+    storage::{FileSystemStorage, Storage},
+};
+
+/// Run the `store backup` subcommand.
+pub async fn run(app: &App, args: &StoreBackupArgs) -> Result<()> {
+    let root = app.args.dir().ok_or(Error::NoRoot)?;
+    let storage = FileSystemStorage::new(root).map_err(Error::StorageInitFailed)?;
+
+    let stats =
+        Storage::<Sha256>::backup_to(&storage, &args.destination).map_err(Error::BackupFailed)?;
+
+    app.print_tx
+        .send(PrinterCmd::msg(
+            BackupMsg {
+                action: "backed up",
+                path: args.destination.clone(),
+                stats: Some(stats),
+            },
+            app.args.format(),
+        ))
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,32 @@
+//! The `store checkpoint create` command, which saves the state of the store.
+
+use crate::{
+    app::App,
+    cli::StoreCheckpointCreateArgs,
+    error::{Error, Result},
+    print::{checkpoint::CheckpointMsg, PrinterCmd},
+};
+use omnibor::storage::FileSystemStorage;
+
+/// Run the `store checkpoint create` subcommand.
+pub async fn run(app: &App, args: &StoreCheckpointCreateArgs) -> Result<()> {
+    let root = app.args.dir().ok_or(Error::NoRoot)?;
+    let storage = FileSystemStorage::new(root).map_err(Error::StorageInitFailed)?;
+
+    let path = storage
+        .checkpoint(&args.label)
+        .map_err(Error::CheckpointFailed)?;
+
+    app.print_tx
+        .send(PrinterCmd::msg(
+            CheckpointMsg {
+                action: "created",
+                label: args.label.clone(),
+                path: Some(path),
+            },
+            app.args.format(),
+        ))
+        .await?;
+
+    Ok(())
+}
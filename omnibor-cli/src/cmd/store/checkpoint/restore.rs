@@ -0,0 +1,32 @@
+//! The `store checkpoint restore` command, which rolls the store back.
+
+use crate::{
+    app::App,
+    cli::StoreCheckpointRestoreArgs,
+    error::{Error, Result},
+    print::{checkpoint::CheckpointMsg, PrinterCmd},
+};
+use omnibor::storage::FileSystemStorage;
+
+/// Run the `store checkpoint restore` subcommand.
+pub async fn run(app: &App, args: &StoreCheckpointRestoreArgs) -> Result<()> {
+    let root = app.args.dir().ok_or(Error::NoRoot)?;
+    let mut storage = FileSystemStorage::new(root).map_err(Error::StorageInitFailed)?;
+
+    storage
+        .restore_checkpoint(&args.label)
+        .map_err(Error::CheckpointFailed)?;
+
+    app.print_tx
+        .send(PrinterCmd::msg(
+            CheckpointMsg {
+                action: "restored",
+                label: args.label.clone(),
+                path: None,
+            },
+            app.args.format(),
+        ))
+        .await?;
+
+    Ok(())
+}
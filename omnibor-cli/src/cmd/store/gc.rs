@@ -0,0 +1,32 @@
+//! The `store gc` command, which removes unreachable manifests from the store.
+
+use crate::{
+    app::App,
+    cli::StoreGcArgs,
+    error::{Error, Result},
+    print::{collected::CollectedMsg, PrinterCmd},
+};
+use omnibor::storage::FileSystemStorage;
+
+/// Run the `store gc` subcommand.
+pub async fn run(app: &App, args: &StoreGcArgs) -> Result<()> {
+    let root = app.args.dir().ok_or(Error::NoRoot)?;
+    let mut storage = FileSystemStorage::new(root).map_err(Error::StorageInitFailed)?;
+
+    let reachable = storage
+        .reachable_manifests(&args.roots)
+        .map_err(Error::GcFailed)?;
+    let stats = storage.gc(&reachable).map_err(Error::GcFailed)?;
+
+    app.print_tx
+        .send(PrinterCmd::msg(
+            CollectedMsg {
+                freed_manifests: stats.freed_manifests,
+                freed_bytes: stats.freed_bytes,
+            },
+            app.args.format(),
+        ))
+        .await?;
+
+    Ok(())
+}
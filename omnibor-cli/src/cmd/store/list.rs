@@ -0,0 +1,84 @@
+//! The `store list` command, which lists manifests in the store.
+
+use crate::{
+    app::App,
+    cli::{Format, StoreListArgs},
+    error::{Error, Result},
+    print::{csv::CsvHeaderMsg, list_manifest::ListManifestMsg, PrinterCmd},
+};
+use omnibor::{
+    hashes::Sha256,
+    storage::{FileSystemStorage, Storage},
+    ArtifactId, InputManifest, IntoArtifactId,
+};
+use std::ops::Not as _;
+
+/// Run the `store list` subcommand.
+pub async fn run(app: &App, args: &StoreListArgs) -> Result<()> {
+    let root = app.args.dir().ok_or(Error::NoRoot)?;
+    let storage = FileSystemStorage::new(root).map_err(Error::StorageInitFailed)?;
+
+    let targets = args
+        .filter_targets
+        .iter()
+        .cloned()
+        .map(|target| target.into_artifact_id().map_err(Error::IdFailed))
+        .collect::<Result<Vec<ArtifactId<Sha256>>>>()?;
+
+    let manifests: Vec<InputManifest<Sha256>> =
+        storage.get_manifests().map_err(Error::ListFailed)?;
+
+    if app.args.format() == Format::Csv {
+        let header = CsvHeaderMsg {
+            columns: ListManifestMsg::CSV_COLUMNS,
+        };
+
+        app.print_tx
+            .send(PrinterCmd::msg(header, app.args.format()))
+            .await?;
+    }
+
+    for manifest in manifests {
+        if matches_filters(&manifest, &targets, &args.filter_inputs).not() {
+            continue;
+        }
+
+        let manifest_aid = ArtifactId::id_manifest(&manifest).map_err(Error::IdFailed)?;
+
+        app.print_tx
+            .send(PrinterCmd::msg(
+                ListManifestMsg {
+                    manifest_aid,
+                    target_aid: manifest.target(),
+                    input_count: manifest.relations().len(),
+                },
+                app.args.format(),
+            ))
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Check if a manifest passes the filters.
+///
+/// Each kind of filter matches if any of its values match, or if it's empty.
+/// A manifest must pass both kinds of filter.
+fn matches_filters(
+    manifest: &InputManifest<Sha256>,
+    targets: &[ArtifactId<Sha256>],
+    inputs: &[ArtifactId<Sha256>],
+) -> bool {
+    let target_matches = targets.is_empty()
+        || manifest
+            .target()
+            .is_some_and(|target| targets.contains(&target));
+
+    let input_matches = inputs.is_empty()
+        || manifest
+            .relations()
+            .iter()
+            .any(|relation| inputs.contains(&relation.artifact()));
+
+    target_matches && input_matches
+}
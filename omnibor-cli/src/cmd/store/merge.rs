@@ -0,0 +1,35 @@
+//! The `store merge` command, which merges another store into this one.
+
+use crate::{
+    app::App,
+    cli::StoreMergeArgs,
+    error::{Error, Result},
+    print::{merged::MergedMsg, PrinterCmd},
+};
+use omnibor::{
+    hashes::Sha256,
+    storage::{FileSystemStorage, Storage},
+};
+
+/// Run the `store merge` subcommand.
+pub async fn run(app: &App, args: &StoreMergeArgs) -> Result<()> {
+    let root = app.args.dir().ok_or(Error::NoRoot)?;
+    let mut storage = FileSystemStorage::new(root).map_err(Error::StorageInitFailed)?;
+    let other = FileSystemStorage::new(&args.from).map_err(Error::StorageInitFailed)?;
+
+    let stats = Storage::<Sha256>::merge(&mut storage, &other, args.on_conflict().into())
+        .map_err(Error::MergeFailed)?;
+
+    app.print_tx
+        .send(PrinterCmd::msg(
+            MergedMsg {
+                added: stats.added,
+                skipped_existing: stats.skipped_existing,
+                conflicts: stats.conflicts,
+            },
+            app.args.format(),
+        ))
+        .await?;
+
+    Ok(())
+}
@@ -1,3 +1,10 @@
 pub mod add;
+pub mod backup;
+pub mod checkpoint;
+pub mod gc;
+pub mod list;
 pub mod log;
+pub mod merge;
 pub mod remove;
+pub mod repair;
+pub mod restore;
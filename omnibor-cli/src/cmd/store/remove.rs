@@ -1,6 +1,31 @@
-use crate::{app::App, cli::StoreRemoveArgs, error::Result};
+//! The `store remove` command, which removes manifests from the store.
+
+use crate::{
+    app::App,
+    cli::StoreRemoveArgs,
+    error::{Error, Result},
+    print::{removed::RemovedMsg, PrinterCmd},
+};
+use omnibor::{
+    hashes::Sha256,
+    storage::{FileSystemStorage, Storage},
+};
+use std::ops::Not as _;
 
 /// Run the `store remove` subcommand.
-pub async fn run(_app: &App, _args: &StoreRemoveArgs) -> Result<()> {
-    todo!()
+pub async fn run(app: &App, args: &StoreRemoveArgs) -> Result<()> {
+    if args.all.not() {
+        return Err(Error::RemoveOneUnsupported);
+    }
+
+    let root = app.args.dir().ok_or(Error::NoRoot)?;
+    let mut storage = FileSystemStorage::new(root).map_err(Error::StorageInitFailed)?;
+    let count =
+        Storage::<Sha256>::remove_all_manifests(&mut storage).map_err(Error::RemoveFailed)?;
+
+    app.print_tx
+        .send(PrinterCmd::msg(RemovedMsg { count }, app.args.format()))
+        .await?;
+
+    Ok(())
 }
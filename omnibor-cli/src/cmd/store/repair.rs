@@ -0,0 +1,28 @@
+//! The `store repair` command, which rebuilds the store's target index.
+
+use crate::{
+    app::App,
+    cli::StoreRepairArgs,
+    error::{Error, Result},
+    print::{repaired::RepairedMsg, PrinterCmd},
+};
+use omnibor::{hashes::Sha256, storage::FileSystemStorage};
+
+/// Run the `store repair` subcommand.
+pub async fn run(app: &App, _args: &StoreRepairArgs) -> Result<()> {
+    let root = app.args.dir().ok_or(Error::NoRoot)?;
+    let mut storage = FileSystemStorage::new(root).map_err(Error::StorageInitFailed)?;
+
+    let restored_targets = storage
+        .rebuild_target_index::<Sha256>()
+        .map_err(Error::RepairFailed)?;
+
+    app.print_tx
+        .send(PrinterCmd::msg(
+            RepairedMsg { restored_targets },
+            app.args.format(),
+        ))
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,29 @@
+//! The `store restore` command, which creates the store from a backup.
+
+use crate::{
+    app::App,
+    cli::StoreRestoreArgs,
+    error::{Error, Result},
+    print::{backup::BackupMsg, PrinterCmd},
+};
+use omnibor::storage::FileSystemStorage;
+
+/// Run the `store restore` subcommand.
+pub async fn run(app: &App, args: &StoreRestoreArgs) -> Result<()> {
+    let root = app.args.dir().ok_or(Error::NoRoot)?;
+
+    FileSystemStorage::restore_from_backup(&args.source, root).map_err(Error::RestoreFailed)?;
+
+    app.print_tx
+        .send(PrinterCmd::msg(
+            BackupMsg {
+                action: "restored",
+                path: args.source.clone(),
+                stats: None,
+            },
+            app.args.format(),
+        ))
+        .await?;
+
+    Ok(())
+}
@@ -1,9 +1,10 @@
 use crate::{
     cli::DEFAULT_CONFIG,
     error::{Error, Result},
+    log::LOG_VAR,
 };
 use serde::Deserialize;
-use std::{fs::File, io::Read, path::Path};
+use std::{env::var_os, fs::File, io::Read, path::Path};
 use tokio::runtime::Handle;
 use tracing::debug;
 
@@ -143,3 +144,54 @@ impl Default for NumWorkers {
         NumWorkers(num)
     }
 }
+
+/// An environment variable read by the CLI.
+#[derive(Debug, Clone, Copy)]
+pub struct EnvVar {
+    /// The name of the variable.
+    pub name: &'static str,
+
+    /// What setting the variable does.
+    pub description: &'static str,
+}
+
+impl EnvVar {
+    /// Get the current value of the variable, if it's set.
+    pub fn value(&self) -> Option<String> {
+        var_os(self.name).map(|value| value.to_string_lossy().into_owned())
+    }
+}
+
+/// The environment variables read by the CLI, printed by `--list-env-vars`.
+///
+/// Keep this in sync with the `env` attributes in `cli.rs`.
+pub static ENV_VARS: &[EnvVar] = &[
+    EnvVar {
+        name: "OMNIBOR_DIR",
+        description: "Directory to store manifests.",
+    },
+    EnvVar {
+        name: "OMNIBOR_CONFIG",
+        description: "Path to a configuration file.",
+    },
+    EnvVar {
+        name: "OMNIBOR_FORMAT",
+        description: "Output format.",
+    },
+    EnvVar {
+        name: "OMNIBOR_HASH",
+        description: "Hash algorithm to use for Artifact IDs.",
+    },
+    EnvVar {
+        name: "OMNIBOR_DEBUG_CONSOLE",
+        description: "Turn on 'tokio-console' debug integration.",
+    },
+    EnvVar {
+        name: LOG_VAR,
+        description: "Filter directives for log output, like 'omnibor=debug'.",
+    },
+    EnvVar {
+        name: "NO_COLOR",
+        description: "Never colorize output, if set and not empty.",
+    },
+];
@@ -1,9 +1,10 @@
 //! Error types.
 
 use async_channel::SendError;
+use ignore::Error as IgnoreError;
 use omnibor::Error as OmniborError;
 use serde_json::Error as JsonError;
-use std::{io::Error as IoError, path::PathBuf, result::Result as StdResult};
+use std::{io::Error as IoError, path::PathBuf, result::Result as StdResult, str::Utf8Error};
 use tokio::task::JoinError;
 
 #[derive(Debug, thiserror::Error)]
@@ -11,15 +12,122 @@ pub enum Error {
     #[error("could not identify '{0}'")]
     NotIdentifiable(String),
 
+    #[error("timed out after {0}s")]
+    TimedOut(u64),
+
     #[error("can't specify both --no-store and --no-out")]
     NoStoreAndNoOut,
 
+    #[error("no target given; use --target or set 'target' in the JSON inputs file")]
+    NoTarget,
+
+    #[error("embedding mode is not yet supported")]
+    EmbeddingUnsupported,
+
+    #[error("could not read embedded manifest ID from '{}'", path.display())]
+    EmbeddedReadFailed {
+        path: PathBuf,
+        #[source]
+        source: OmniborError,
+    },
+
+    #[error("no embedded manifest ID found in '{}'", path.display())]
+    NoEmbeddedManifest { path: PathBuf },
+
+    #[error("embedded manifest '{0}' not found in the store")]
+    EmbeddedManifestNotStored(String),
+
+    #[error("'{}' doesn't match its embedded manifest; expected target '{expected}', found '{found}'", path.display())]
+    EmbeddedTargetMismatch {
+        path: PathBuf,
+        expected: String,
+        found: String,
+    },
+
+    #[error("could not read JSON inputs file '{}'", path.display())]
+    JsonInputsCouldNotRead {
+        path: PathBuf,
+        #[source]
+        source: IoError,
+    },
+
+    #[error("invalid JSON inputs file '{}'", path.display())]
+    JsonInputsInvalid {
+        path: PathBuf,
+        #[source]
+        source: JsonError,
+    },
+
+    #[error("invalid Artifact ID in JSON inputs file '{}'", path.display())]
+    JsonInputsInvalidArtifactId {
+        path: PathBuf,
+        #[source]
+        source: OmniborError,
+    },
+
+    #[cfg(feature = "build-log-parse")]
+    #[error("could not read build log '{}'", path.display())]
+    BuildLogCouldNotRead {
+        path: PathBuf,
+        #[source]
+        source: IoError,
+    },
+
+    #[cfg(feature = "build-log-parse")]
+    #[error("invalid compilation database '{}'", path.display())]
+    BuildLogInvalid {
+        path: PathBuf,
+        #[source]
+        source: JsonError,
+    },
+
+    #[error("could not read input list '{}'", path.display())]
+    InputListCouldNotRead {
+        path: PathBuf,
+        #[source]
+        source: IoError,
+    },
+
+    #[error("input list '{}' has an entry which isn't valid UTF-8", path.display())]
+    InputListInvalid {
+        path: PathBuf,
+        #[source]
+        source: Utf8Error,
+    },
+
     #[error("could not find root directory")]
     NoRoot,
 
     #[error("failed to initialize file system storage")]
     StorageInitFailed(#[source] OmniborError),
 
+    #[error("removing individual manifests is not yet supported; use --all")]
+    RemoveOneUnsupported,
+
+    #[error("failed to remove manifests from the store")]
+    RemoveFailed(#[source] OmniborError),
+
+    #[error("failed to list manifests in the store")]
+    ListFailed(#[source] OmniborError),
+
+    #[error("failed to merge manifests into the store")]
+    MergeFailed(#[source] OmniborError),
+
+    #[error("failed to remove unreachable manifests from the store")]
+    GcFailed(#[source] OmniborError),
+
+    #[error("failed to create or restore a store checkpoint")]
+    CheckpointFailed(#[source] OmniborError),
+
+    #[error("failed to back up the store")]
+    BackupFailed(#[source] OmniborError),
+
+    #[error("failed to restore the store from a backup")]
+    RestoreFailed(#[source] OmniborError),
+
+    #[error("failed to repair the store's target index")]
+    RepairFailed(#[source] OmniborError),
+
     #[error("failed to generate Artifact ID")]
     IdFailed(#[source] OmniborError),
 
@@ -38,6 +146,23 @@ pub enum Error {
     #[error("failed walking under directory '{}'", path.display())]
     WalkDirFailed { path: PathBuf, source: IoError },
 
+    #[error("invalid exclude pattern '{pattern}'")]
+    InvalidExcludePattern {
+        pattern: String,
+        #[source]
+        source: IgnoreError,
+    },
+
+    #[error("could not read exclude patterns from '{}'", path.display())]
+    ExcludeFromFailed {
+        path: PathBuf,
+        #[source]
+        source: IgnoreError,
+    },
+
+    #[error("could not build exclude patterns")]
+    ExcludeBuildFailed(#[source] IgnoreError),
+
     #[error("unable to identify file type for '{}'", path.display())]
     UnknownFileType {
         path: PathBuf,
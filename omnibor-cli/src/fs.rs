@@ -2,40 +2,63 @@
 
 use crate::{
     app::App,
-    cli::{Format, SelectedHash},
+    cli::{Format, IdSort, IdentifiableArg, SelectedHash},
     error::{Error, Result},
-    print::{error::ErrorMsg, id_file::IdFileMsg, PrintSender, PrinterCmd},
+    print::{
+        error::ErrorMsg,
+        id_file::{IdFileMsg, Timing},
+        PrintSender, PrinterCmd,
+    },
 };
 use async_channel::{bounded, Receiver, Sender as WorkSender};
 use async_walkdir::{DirEntry as AsyncDirEntry, WalkDir};
 use futures_util::{pin_mut, StreamExt};
-use omnibor::{hashes::Sha256, ArtifactId};
-use std::path::{Path, PathBuf};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use omnibor::{hashes::Sha256, ArtifactId, ComputedArtifactId};
+use std::{
+    fs,
+    ops::Not as _,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 use tokio::{fs::File as AsyncFile, task::JoinSet};
 use tracing::debug;
 use url::Url;
 
 // Identify, recursively, all the files under a directory.
+//
+// Unless `sort` is `IdSort::None`, results are buffered until every file
+// has been identified, then printed in order.
 pub async fn id_directory(
     app: &App,
+    format: Format,
     hash: SelectedHash,
-    tx: &PrintSender,
+    show_timing: bool,
+    exclude: Option<Gitignore>,
+    sort: IdSort,
     path: &Path,
 ) -> Result<()> {
+    let tx = &app.print_tx;
     let (sender, receiver) = bounded(app.config.perf.work_queue_size());
 
     tokio::spawn(walk_target(
         sender,
         tx.clone(),
-        app.args.format(),
+        format,
         path.to_path_buf(),
+        exclude,
     ));
 
     let mut join_set = JoinSet::new();
 
     // TODO: Make this tunable on the CLI, with the logic here as a fallback.
-    // Subtract 1, since we've spawned one task separately.
-    let num_workers = tokio::runtime::Handle::current().metrics().num_workers() - 1;
+    // Subtract 1, since we've spawned one task separately, but always
+    // keep at least one worker so single-threaded runtimes make progress.
+    let num_workers = tokio::runtime::Handle::current()
+        .metrics()
+        .num_workers()
+        .saturating_sub(1)
+        .max(1);
 
     debug!(num_workers = %num_workers);
 
@@ -43,25 +66,132 @@ pub async fn id_directory(
         join_set.spawn(open_and_id_files(
             receiver.clone(),
             tx.clone(),
-            app.args.format(),
+            format,
             hash,
+            show_timing,
+            sort != IdSort::None,
         ));
     }
 
+    let mut buffered = Vec::new();
+
     while let Some(result) = join_set.join_next().await {
-        result.map_err(Error::CouldNotJoinWorker)??;
+        buffered.extend(result.map_err(Error::CouldNotJoinWorker)??);
+    }
+
+    match sort {
+        IdSort::Hash => buffered.sort_by(|a, b| a.id.as_str().cmp(b.id.as_str())),
+        IdSort::Path => buffered.sort_by(|a, b| a.path.cmp(&b.path)),
+        IdSort::None => {}
+    }
+
+    for msg in buffered {
+        tx.send(PrinterCmd::msg(msg, format)).await?;
     }
 
     Ok(())
 }
 
+/// Build a matcher for paths to skip while walking a directory.
+///
+/// Patterns use `.gitignore` syntax and are matched relative to `root`.
+/// Returns `None` if there are no patterns to apply.
+pub fn build_exclusions(
+    root: &Path,
+    patterns: &[String],
+    exclude_from: Option<&Path>,
+) -> Result<Option<Gitignore>> {
+    if patterns.is_empty() && exclude_from.is_none() {
+        return Ok(None);
+    }
+
+    let mut builder = GitignoreBuilder::new(root);
+
+    if let Some(path) = exclude_from {
+        if let Some(source) = builder.add(path) {
+            return Err(Error::ExcludeFromFailed {
+                path: path.to_path_buf(),
+                source,
+            });
+        }
+    }
+
+    for pattern in patterns {
+        builder
+            .add_line(None, pattern)
+            .map_err(|source| Error::InvalidExcludePattern {
+                pattern: pattern.clone(),
+                source,
+            })?;
+    }
+
+    builder.build().map(Some).map_err(Error::ExcludeBuildFailed)
+}
+
+/// Read the inputs listed in a file, one path or Artifact ID per entry.
+///
+/// Entries are separated by newlines, either `\n` or `\r\n`, or by NUL bytes
+/// if `null_separated` is set. Empty entries and entries starting with `#`
+/// are skipped.
+///
+/// The file is split as raw bytes, so only Artifact IDs need to be valid
+/// UTF-8. On Unix, paths may be any bytes; elsewhere they must be UTF-8 too.
+pub fn read_input_list(path: &Path, null_separated: bool) -> Result<Vec<IdentifiableArg>> {
+    let contents = fs::read(path).map_err(|source| Error::InputListCouldNotRead {
+        path: path.to_path_buf(),
+        source,
+    })?;
+
+    let entries: Box<dyn Iterator<Item = &[u8]>> = if null_separated {
+        Box::new(contents.split(|byte| *byte == b'\0'))
+    } else {
+        Box::new(
+            contents
+                .split(|byte| *byte == b'\n')
+                .map(|line| line.strip_suffix(b"\r").unwrap_or(line)),
+        )
+    };
+
+    entries
+        .filter(|entry| entry.is_empty().not() && entry.starts_with(b"#").not())
+        .map(|entry| input_list_entry(path, entry))
+        .collect()
+}
+
+/// Parse a single entry of an input list.
+fn input_list_entry(path: &Path, entry: &[u8]) -> Result<IdentifiableArg> {
+    let invalid = |source| Error::InputListInvalid {
+        path: path.to_path_buf(),
+        source,
+    };
+
+    if entry.starts_with(b"gitoid:") {
+        let entry = std::str::from_utf8(entry).map_err(invalid)?;
+        return IdentifiableArg::from_str(entry);
+    }
+
+    #[cfg(unix)]
+    let entry = {
+        use std::os::unix::ffi::OsStrExt as _;
+        PathBuf::from(std::ffi::OsStr::from_bytes(entry))
+    };
+
+    #[cfg(not(unix))]
+    let entry = PathBuf::from(std::str::from_utf8(entry).map_err(invalid)?);
+
+    Ok(IdentifiableArg::Path(entry))
+}
+
 /// Walk the target path structure, printing errors and sending discovered
 /// paths out to workers.
+///
+/// Any path matched by `exclude`, or under a directory matched by it, is skipped.
 pub async fn walk_target(
     path_sender: WorkSender<PathBuf>,
     print_tx: PrintSender,
     format: Format,
     path: PathBuf,
+    exclude: Option<Gitignore>,
 ) -> Result<()> {
     let mut entries = WalkDir::new(&path);
 
@@ -81,8 +211,19 @@ pub async fn walk_target(
             }
             Some(Ok(entry)) => {
                 let path = &entry.path();
+                let is_dir = entry_is_dir(&entry).await?;
+
+                if let Some(exclude) = &exclude {
+                    if exclude
+                        .matched_path_or_any_parents(path, is_dir)
+                        .is_ignore()
+                    {
+                        debug!(excluded = %path.display());
+                        continue;
+                    }
+                }
 
-                if entry_is_dir(&entry).await? {
+                if is_dir {
                     continue;
                 }
 
@@ -99,20 +240,32 @@ pub async fn walk_target(
 ///
 /// The semantics of the channel being used mean each path sent will only
 /// be received by one receiver.
+///
+/// If `buffer` is set, results are returned rather than printed.
 async fn open_and_id_files(
     path_rx: Receiver<PathBuf>,
     print_tx: PrintSender,
     format: Format,
     hash: SelectedHash,
-) -> Result<()> {
+    show_timing: bool,
+    buffer: bool,
+) -> Result<Vec<IdFileMsg>> {
     pin_mut!(path_rx);
 
+    let mut buffered = Vec::new();
+
     while let Some(path) = path_rx.next().await {
         let mut file = open_async_file(&path).await?;
-        id_file(&print_tx, &mut file, &path, format, hash).await?;
+        let msg = identify_file(&mut file, &path, hash, show_timing).await?;
+
+        if buffer {
+            buffered.push(msg);
+        } else {
+            print_tx.send(PrinterCmd::msg(msg, format)).await?;
+        }
     }
 
-    Ok(())
+    Ok(buffered)
 }
 
 /// Identify a single file.
@@ -122,19 +275,32 @@ pub async fn id_file(
     path: &Path,
     format: Format,
     hash: SelectedHash,
+    show_timing: bool,
 ) -> Result<()> {
-    let url = hash_file(hash, file, path).await?;
+    let msg = identify_file(file, path, hash, show_timing).await?;
+    tx.send(PrinterCmd::msg(msg, format)).await?;
+    Ok(())
+}
 
-    tx.send(PrinterCmd::msg(
-        IdFileMsg {
-            path: path.to_path_buf(),
-            id: url.clone(),
-        },
-        format,
-    ))
-    .await?;
+/// Identify a single file, producing the message to print for it.
+async fn identify_file(
+    file: &mut AsyncFile,
+    path: &Path,
+    hash: SelectedHash,
+    show_timing: bool,
+) -> Result<IdFileMsg> {
+    let (url, timing) = if show_timing {
+        let (url, timing) = timed_hash_file(hash, file, path).await?;
+        (url, Some(timing))
+    } else {
+        (hash_file(hash, file, path).await?, None)
+    };
 
-    Ok(())
+    Ok(IdFileMsg {
+        path: path.to_path_buf(),
+        id: url,
+        timing,
+    })
 }
 
 /// Hash the file and produce a `gitoid`-scheme URL.
@@ -144,6 +310,25 @@ pub async fn hash_file(hash: SelectedHash, file: &mut AsyncFile, path: &Path) ->
     }
 }
 
+/// Hash the file and produce a `gitoid`-scheme URL, along with timing data.
+pub async fn timed_hash_file(
+    hash: SelectedHash,
+    file: &mut AsyncFile,
+    path: &Path,
+) -> Result<(Url, Timing)> {
+    match hash {
+        SelectedHash::Sha256 => sha256_timed_id_async_file(file, path)
+            .await
+            .map(|computed| {
+                let timing = Timing {
+                    duration: computed.duration(),
+                    bytes_processed: computed.bytes_processed(),
+                };
+                (computed.artifact_id().url(), timing)
+            }),
+    }
+}
+
 /// Check if the file is for a directory.
 pub async fn file_is_dir(file: &AsyncFile, path: &Path) -> Result<bool> {
     file.metadata()
@@ -186,3 +371,16 @@ pub async fn sha256_id_async_file(file: &mut AsyncFile, path: &Path) -> Result<A
             source,
         })
 }
+
+/// Identify a file using a SHA-256 hash, recording timing data.
+pub async fn sha256_timed_id_async_file(
+    file: &mut AsyncFile,
+    path: &Path,
+) -> Result<ComputedArtifactId<Sha256>> {
+    ArtifactId::timed_id_async_reader(file)
+        .await
+        .map_err(|source| Error::FileFailedToId {
+            path: path.to_path_buf(),
+            source,
+        })
+}
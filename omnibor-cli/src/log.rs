@@ -8,7 +8,7 @@ use tracing_subscriber::{
 };
 
 // The environment variable to use when configuring the log.
-const LOG_VAR: &str = "OMNIBOR_LOG";
+pub const LOG_VAR: &str = "OMNIBOR_LOG";
 
 pub fn init_log(verbosity: Verbosity<InfoLevel>, console: bool) {
     let level_filter = adapt_level_filter(verbosity.log_level_filter());
@@ -1,4 +1,6 @@
 mod app;
+#[cfg(feature = "build-log-parse")]
+mod build_log;
 mod cli;
 mod cmd;
 mod config;
@@ -9,25 +11,36 @@ mod print;
 
 use crate::{
     app::App,
-    cli::{Args, ArtifactCommand, Command, DebugCommand, ManifestCommand, StoreCommand},
-    cmd::{artifact, debug, manifest, store},
+    cli::{
+        Args, ArtifactCommand, Command, DebugCommand, ManifestCommand, StoreCheckpointCommand,
+        StoreCommand,
+    },
+    cmd::{artifact, debug, env_vars, manifest, store},
     config::Config,
-    error::Result,
+    error::{Error, Result},
     log::init_log,
-    print::{error::ErrorMsg, Printer, PrinterCmd},
+    print::{error::ErrorMsg, set_color, Printer, PrinterCmd},
 };
-use clap::Parser as _;
-use std::{error::Error as StdError, process::ExitCode};
-use tokio::runtime::Runtime;
+use std::{error::Error as StdError, process::ExitCode, time::Duration};
+use tokio::{runtime::Runtime, time::timeout};
 use tracing::{error, trace};
 
+/// The exit code when the command runs longer than `--timeout`.
+const TIMEOUT_EXIT_CODE: u8 = 2;
+
 fn main() -> ExitCode {
     let runtime = Runtime::new().expect("runtime construction succeeds");
-    runtime.block_on(async { run().await })
+    let exit_code = runtime.block_on(async { run().await });
+
+    // Don't wait on blocking tasks left running, like file reads stuck
+    // when the command timed out.
+    runtime.shutdown_background();
+    exit_code
 }
 
 async fn run() -> ExitCode {
-    let args = Args::parse();
+    let args = Args::parse_checked();
+    set_color(args.color());
     init_log(args.verbosity(), args.console());
 
     let config = match Config::init(args.config()) {
@@ -47,13 +60,18 @@ async fn run() -> ExitCode {
     };
     trace!(app = ?app);
 
-    let exit_code = match run_cmd(&app).await {
+    let exit_code = match run_cmd_with_timeout(&app).await {
         Ok(_) => ExitCode::SUCCESS,
         Err(e) => {
+            let exit_code = match e {
+                Error::TimedOut(_) => ExitCode::from(TIMEOUT_EXIT_CODE),
+                _ => ExitCode::FAILURE,
+            };
+
             printer
                 .send(PrinterCmd::msg(ErrorMsg::new(e), app.args.format()))
                 .await;
-            ExitCode::FAILURE
+            exit_code
         }
     };
 
@@ -63,8 +81,23 @@ async fn run() -> ExitCode {
     exit_code
 }
 
+/// Run the chosen command, stopping it if it exceeds `--timeout`.
+async fn run_cmd_with_timeout(app: &App) -> Result<()> {
+    let Some(seconds) = app.args.timeout() else {
+        return run_cmd(app).await;
+    };
+
+    timeout(Duration::from_secs(seconds), run_cmd(app))
+        .await
+        .unwrap_or(Err(Error::TimedOut(seconds)))
+}
+
 /// Select and run the chosen command.
 async fn run_cmd(app: &App) -> Result<()> {
+    if app.args.list_env_vars() {
+        return env_vars::run(app).await;
+    }
+
     match app.args.command() {
         Command::Artifact(ref args) => match args.command {
             ArtifactCommand::Id(ref args) => artifact::id::run(app, args).await,
@@ -72,11 +105,28 @@ async fn run_cmd(app: &App) -> Result<()> {
         },
         Command::Manifest(ref args) => match args.command {
             ManifestCommand::Create(ref args) => manifest::create::run(app, args).await,
+            ManifestCommand::VerifyEmbedded(ref args) => {
+                manifest::verify_embedded::run(app, args).await
+            }
         },
         Command::Store(ref args) => match args.command {
             StoreCommand::Add(ref args) => store::add::run(app, args).await,
             StoreCommand::Remove(ref args) => store::remove::run(app, args).await,
+            StoreCommand::List(ref args) => store::list::run(app, args).await,
             StoreCommand::Log(ref args) => store::log::run(app, args).await,
+            StoreCommand::Merge(ref args) => store::merge::run(app, args).await,
+            StoreCommand::Gc(ref args) => store::gc::run(app, args).await,
+            StoreCommand::Checkpoint(ref args) => match args.command {
+                StoreCheckpointCommand::Create(ref args) => {
+                    store::checkpoint::create::run(app, args).await
+                }
+                StoreCheckpointCommand::Restore(ref args) => {
+                    store::checkpoint::restore::run(app, args).await
+                }
+            },
+            StoreCommand::Backup(ref args) => store::backup::run(app, args).await,
+            StoreCommand::Restore(ref args) => store::restore::run(app, args).await,
+            StoreCommand::Repair(ref args) => store::repair::run(app, args).await,
         },
         Command::Debug(ref args) => match args.command {
             DebugCommand::Paths(ref args) => debug::paths::run(app, args).await,
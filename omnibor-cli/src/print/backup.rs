@@ -0,0 +1,54 @@
+use crate::print::{CommandOutput, Status};
+use console::Style;
+use omnibor::storage::backup::BackupStats;
+use serde_json::json;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone)]
+pub struct BackupMsg {
+    pub action: &'static str,
+    pub path: PathBuf,
+    pub stats: Option<BackupStats>,
+}
+
+impl CommandOutput for BackupMsg {
+    fn plain_output(&self) -> String {
+        let mut output = format!(
+            "{} {}",
+            Style::new()
+                .dim()
+                .apply_to(format!("{} store:", self.action)),
+            Style::new().green().apply_to(self.path.display()),
+        );
+
+        if let Some(stats) = &self.stats {
+            output.push_str(&format!(
+                "\n{} {}\n{} {}",
+                Style::new().dim().apply_to("manifests:"),
+                Style::new().blue().bold().apply_to(stats.manifest_count),
+                Style::new().dim().apply_to("bytes copied:"),
+                Style::new().blue().bold().apply_to(stats.bytes_copied),
+            ));
+        }
+
+        output
+    }
+
+    fn short_output(&self) -> String {
+        self.path.display().to_string()
+    }
+
+    fn json_output(&self) -> serde_json::Value {
+        json!({
+            "action": self.action,
+            "path": self.path.display().to_string(),
+            "manifest_count": self.stats.map(|stats| stats.manifest_count),
+            "bytes_copied": self.stats.map(|stats| stats.bytes_copied),
+            "elapsed_ms": self.stats.map(|stats| stats.elapsed.as_millis() as u64),
+        })
+    }
+
+    fn status(&self) -> Status {
+        Status::Success
+    }
+}
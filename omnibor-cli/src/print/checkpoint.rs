@@ -0,0 +1,51 @@
+use crate::print::{CommandOutput, Status};
+use console::Style;
+use serde_json::json;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone)]
+pub struct CheckpointMsg {
+    pub action: &'static str,
+    pub label: String,
+    pub path: Option<PathBuf>,
+}
+
+impl CheckpointMsg {
+    fn path_string(&self) -> Option<String> {
+        self.path.as_ref().map(|path| path.display().to_string())
+    }
+}
+
+impl CommandOutput for CheckpointMsg {
+    fn plain_output(&self) -> String {
+        let mut output = format!(
+            "{} {}",
+            Style::new()
+                .dim()
+                .apply_to(format!("{} checkpoint:", self.action)),
+            Style::new().green().apply_to(&self.label),
+        );
+
+        if let Some(path) = self.path_string() {
+            output.push_str(&format!(" {} {}", Style::new().dim().apply_to("=>"), path));
+        }
+
+        output
+    }
+
+    fn short_output(&self) -> String {
+        self.path_string().unwrap_or_else(|| self.label.clone())
+    }
+
+    fn json_output(&self) -> serde_json::Value {
+        json!({
+            "action": self.action,
+            "label": self.label,
+            "path": self.path_string(),
+        })
+    }
+
+    fn status(&self) -> Status {
+        Status::Success
+    }
+}
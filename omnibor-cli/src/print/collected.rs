@@ -0,0 +1,36 @@
+use crate::print::{CommandOutput, Status};
+use console::Style;
+use serde_json::json;
+
+#[derive(Debug, Clone)]
+pub struct CollectedMsg {
+    pub freed_manifests: usize,
+    pub freed_bytes: u64,
+}
+
+impl CommandOutput for CollectedMsg {
+    fn plain_output(&self) -> String {
+        format!(
+            "{} {}\n{} {}",
+            Style::new().dim().apply_to("freed manifests:"),
+            Style::new().blue().bold().apply_to(self.freed_manifests),
+            Style::new().dim().apply_to("freed bytes:"),
+            Style::new().blue().bold().apply_to(self.freed_bytes),
+        )
+    }
+
+    fn short_output(&self) -> String {
+        self.freed_manifests.to_string()
+    }
+
+    fn json_output(&self) -> serde_json::Value {
+        json!({
+            "freed_manifests": self.freed_manifests,
+            "freed_bytes": self.freed_bytes,
+        })
+    }
+
+    fn status(&self) -> Status {
+        Status::Success
+    }
+}
@@ -0,0 +1,60 @@
+use crate::print::{CommandOutput, Status};
+use serde_json::Value as JsonValue;
+
+/// Formats rows of comma-separated values.
+///
+/// Fields holding a comma, a double quote, or a line break are quoted, with
+/// any double quotes doubled, per RFC 4180.
+pub struct CsvFormatter;
+
+impl CsvFormatter {
+    /// Format one row, without a trailing newline.
+    pub fn row<I, S>(fields: I) -> String
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        fields
+            .into_iter()
+            .map(|field| Self::field(field.as_ref()))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    /// Format one field, quoting it if needed.
+    fn field(field: &str) -> String {
+        if field.contains([',', '"', '\r', '\n']) {
+            format!("\"{}\"", field.replace('"', "\"\""))
+        } else {
+            field.to_string()
+        }
+    }
+}
+
+/// The header row of CSV output, printed before any rows.
+#[derive(Debug, Clone)]
+pub struct CsvHeaderMsg {
+    pub columns: &'static [&'static str],
+}
+
+impl CommandOutput for CsvHeaderMsg {
+    fn plain_output(&self) -> String {
+        self.csv_output()
+    }
+
+    fn short_output(&self) -> String {
+        self.csv_output()
+    }
+
+    fn json_output(&self) -> JsonValue {
+        JsonValue::from(self.columns)
+    }
+
+    fn csv_output(&self) -> String {
+        CsvFormatter::row(self.columns)
+    }
+
+    fn status(&self) -> Status {
+        Status::Success
+    }
+}
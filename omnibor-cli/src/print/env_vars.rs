@@ -0,0 +1,78 @@
+use crate::{
+    config::EnvVar,
+    print::{CommandOutput, Status},
+};
+use console::Style;
+use serde_json::json;
+
+/// Placeholder printed for variables which aren't set.
+const NOT_SET: &str = "(not set)";
+
+#[derive(Debug, Clone)]
+pub struct EnvVarsMsg {
+    vars: Vec<(EnvVar, Option<String>)>,
+}
+
+impl EnvVarsMsg {
+    /// Capture the current values of the given variables.
+    pub fn new(vars: &[EnvVar]) -> Self {
+        EnvVarsMsg {
+            vars: vars.iter().map(|var| (*var, var.value())).collect(),
+        }
+    }
+}
+
+impl CommandOutput for EnvVarsMsg {
+    fn plain_output(&self) -> String {
+        let pad_width = self
+            .vars
+            .iter()
+            .map(|(var, _)| var.name.len())
+            .max()
+            .unwrap_or(10);
+
+        self.vars
+            .iter()
+            .fold(String::new(), |mut output, (var, value)| {
+                output.push_str(&format!(
+                    "{:<width$}  {}\n{:<width$}  {}\n",
+                    Style::new().blue().bold().apply_to(var.name),
+                    value.as_deref().unwrap_or(NOT_SET),
+                    "",
+                    Style::new().dim().apply_to(var.description),
+                    width = pad_width,
+                ));
+                output
+            })
+    }
+
+    fn short_output(&self) -> String {
+        self.vars
+            .iter()
+            .fold(String::new(), |mut output, (var, value)| {
+                output.push_str(&format!(
+                    "{}={}\n",
+                    var.name,
+                    value.as_deref().unwrap_or_default()
+                ));
+                output
+            })
+    }
+
+    fn json_output(&self) -> serde_json::Value {
+        self.vars
+            .iter()
+            .map(|(var, value)| {
+                json!({
+                    "name": var.name,
+                    "value": value,
+                    "description": var.description,
+                })
+            })
+            .collect()
+    }
+
+    fn status(&self) -> Status {
+        Status::Success
+    }
+}
@@ -28,8 +28,11 @@ impl CommandOutput for ErrorMsg {
     fn plain_output(&self) -> String {
         format!(
             "{}: {}",
-            Style::new().red().apply_to("error"),
-            self.error_string()
+            Style::new().red().bold().for_stderr().apply_to("error"),
+            Style::new()
+                .red()
+                .for_stderr()
+                .apply_to(self.error_string())
         )
     }
 
@@ -24,7 +24,7 @@ impl CommandOutput for FindFileMsg {
     fn plain_output(&self) -> String {
         format!(
             "{} {} {}",
-            Style::new().blue().bold().apply_to(self.id_string()),
+            Style::new().green().apply_to(self.id_string()),
             Style::new().dim().apply_to("=>"),
             self.path_string()
         )
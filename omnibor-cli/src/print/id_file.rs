@@ -1,13 +1,30 @@
 use crate::print::{CommandOutput, Status};
 use console::Style;
 use serde_json::json;
-use std::path::PathBuf;
+use std::{path::PathBuf, time::Duration};
 use url::Url;
 
 #[derive(Debug, Clone)]
 pub struct IdFileMsg {
     pub path: PathBuf,
     pub id: Url,
+    pub timing: Option<Timing>,
+}
+
+/// Statistics on how long it took to identify a file.
+#[derive(Debug, Clone, Copy)]
+pub struct Timing {
+    pub duration: Duration,
+    pub bytes_processed: Option<u64>,
+}
+
+impl Timing {
+    fn timing_string(&self) -> String {
+        match self.bytes_processed {
+            Some(bytes) => format!("[{:.6}s, {}b]", self.duration.as_secs_f64(), bytes),
+            None => format!("[{:.6}s]", self.duration.as_secs_f64()),
+        }
+    }
 }
 
 impl IdFileMsg {
@@ -18,27 +35,69 @@ impl IdFileMsg {
     fn id_string(&self) -> String {
         self.id.to_string()
     }
+
+    /// Get the hex hash of the ID, without the `gitoid:blob:<hash>:` prefix.
+    fn hash_string(&self) -> String {
+        let id = self.id.as_str();
+        id.rsplit_once(':')
+            .map(|(_, hash)| hash)
+            .unwrap_or(id)
+            .to_string()
+    }
+
+    fn timing_suffix(&self) -> String {
+        self.timing
+            .map(|timing| format!(" {}", timing.timing_string()))
+            .unwrap_or_default()
+    }
 }
 
 impl CommandOutput for IdFileMsg {
     fn plain_output(&self) -> String {
         format!(
-            "{} {} {}",
+            "{} {} {}{}",
             Style::new().blue().bold().apply_to(self.path_string()),
             Style::new().dim().apply_to("=>"),
-            self.id_string()
+            Style::new().green().apply_to(self.id_string()),
+            Style::new().dim().apply_to(self.timing_suffix())
         )
     }
 
     fn short_output(&self) -> String {
-        self.id_string()
+        format!("{}{}", self.id_string(), self.timing_suffix())
     }
 
     fn json_output(&self) -> serde_json::Value {
-        json!({"path": self.path_string(), "id": self.id_string()})
+        match self.timing {
+            Some(timing) => json!({
+                "path": self.path_string(),
+                "id": self.id_string(),
+                "hash": self.hash_string(),
+                "duration_secs": timing.duration.as_secs_f64(),
+                "bytes_processed": timing.bytes_processed,
+            }),
+            None => json!({
+                "path": self.path_string(),
+                "id": self.id_string(),
+                "hash": self.hash_string(),
+            }),
+        }
     }
 
     fn status(&self) -> Status {
         Status::Success
     }
+
+    fn oid_output(&self) -> String {
+        format!("{}{}", self.hash_string(), self.timing_suffix())
+    }
+
+    fn binary_output(&self) -> Option<Vec<u8>> {
+        let hash = self.hash_string();
+
+        (0..hash.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(hash.get(i..i + 2)?, 16).ok())
+            .collect()
+    }
 }
@@ -0,0 +1,64 @@
+use crate::print::{csv::CsvFormatter, CommandOutput, Status};
+use console::Style;
+use omnibor::{hashes::Sha256, ArtifactId};
+use serde_json::json;
+
+#[derive(Debug, Clone)]
+pub struct ListManifestMsg {
+    pub manifest_aid: ArtifactId<Sha256>,
+    pub target_aid: Option<ArtifactId<Sha256>>,
+    pub input_count: usize,
+}
+
+impl ListManifestMsg {
+    /// The columns of the CSV output.
+    pub const CSV_COLUMNS: &'static [&'static str] =
+        &["manifest_aid", "target_aid", "input_count", "detached"];
+}
+
+impl ListManifestMsg {
+    fn manifest_string(&self) -> String {
+        self.manifest_aid.to_string()
+    }
+
+    fn target_string(&self) -> Option<String> {
+        self.target_aid.map(|aid| aid.to_string())
+    }
+}
+
+impl CommandOutput for ListManifestMsg {
+    fn plain_output(&self) -> String {
+        let target = match self.target_string() {
+            Some(target) => target,
+            None => Style::new().dim().apply_to("(no target)").to_string(),
+        };
+
+        format!(
+            "{} {} {}",
+            Style::new().green().apply_to(self.manifest_string()),
+            Style::new().dim().apply_to("=>"),
+            target
+        )
+    }
+
+    fn short_output(&self) -> String {
+        self.manifest_string()
+    }
+
+    fn json_output(&self) -> serde_json::Value {
+        json!({"manifest": self.manifest_string(), "target": self.target_string()})
+    }
+
+    fn csv_output(&self) -> String {
+        CsvFormatter::row([
+            self.manifest_string(),
+            self.target_string().unwrap_or_default(),
+            self.input_count.to_string(),
+            self.target_aid.is_none().to_string(),
+        ])
+    }
+
+    fn status(&self) -> Status {
+        Status::Success
+    }
+}
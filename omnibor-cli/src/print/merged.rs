@@ -0,0 +1,53 @@
+use crate::print::{CommandOutput, Status};
+use console::Style;
+use omnibor::{hashes::Sha256, ArtifactId};
+use serde_json::json;
+
+#[derive(Debug, Clone)]
+pub struct MergedMsg {
+    pub added: usize,
+    pub skipped_existing: usize,
+    pub conflicts: Vec<ArtifactId<Sha256>>,
+}
+
+impl MergedMsg {
+    fn conflict_strings(&self) -> Vec<String> {
+        self.conflicts.iter().map(ToString::to_string).collect()
+    }
+}
+
+impl CommandOutput for MergedMsg {
+    fn plain_output(&self) -> String {
+        let mut output = format!(
+            "{} {}\n{} {}\n{} {}",
+            Style::new().dim().apply_to("added manifests:"),
+            Style::new().blue().bold().apply_to(self.added),
+            Style::new().dim().apply_to("skipped existing:"),
+            Style::new().blue().bold().apply_to(self.skipped_existing),
+            Style::new().dim().apply_to("conflicts:"),
+            Style::new().blue().bold().apply_to(self.conflicts.len()),
+        );
+
+        for conflict in self.conflict_strings() {
+            output.push_str(&format!("\n  {}", Style::new().green().apply_to(conflict)));
+        }
+
+        output
+    }
+
+    fn short_output(&self) -> String {
+        self.added.to_string()
+    }
+
+    fn json_output(&self) -> serde_json::Value {
+        json!({
+            "added": self.added,
+            "skipped_existing": self.skipped_existing,
+            "conflicts": self.conflict_strings(),
+        })
+    }
+
+    fn status(&self) -> Status {
+        Status::Success
+    }
+}
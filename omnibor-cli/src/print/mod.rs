@@ -1,9 +1,20 @@
 //! Defines a simple print queue abstraction.
 
+pub mod backup;
+pub mod checkpoint;
+pub mod collected;
+pub mod csv;
+pub mod env_vars;
 pub mod error;
 pub mod find_file;
 pub mod id_file;
+pub mod list_manifest;
+pub mod merged;
 pub mod paths;
+pub mod removed;
+pub mod repaired;
+pub mod schema;
+pub mod verified;
 
 use crate::{
     cli::Format,
@@ -49,12 +60,15 @@ impl Printer {
                     PrinterCmd::End => rx.close(),
                     PrinterCmd::Message { output, format } => {
                         let status = output.status();
-                        let output = output.format(format);
+                        let output = match (format, output.binary_output()) {
+                            (Format::Binary, Some(bytes)) => bytes,
+                            _ => output.format(format).into_bytes(),
+                        };
 
-                        if let Err(error) = sync_print(status, output.clone()) {
-                            let err_output = ErrorMsg::new(error).format(format);
+                        if let Err(error) = sync_print(status, &output) {
+                            let err_output = ErrorMsg::new(error).format(format).into_bytes();
 
-                            if let Err(err) = sync_print(Status::Error, err_output) {
+                            if let Err(err) = sync_print(Status::Error, &err_output) {
                                 error!(msg = "failed to print sync error message", error = %err);
                             }
                         }
@@ -165,12 +179,37 @@ pub trait CommandOutput: Debug + DynClone + Send + 'static {
     fn json_output(&self) -> JsonValue;
     fn status(&self) -> Status;
 
+    /// Output with Artifact IDs as bare hex hashes.
+    ///
+    /// Messages without Artifact IDs use their short output.
+    fn oid_output(&self) -> String {
+        self.short_output()
+    }
+
+    /// Output as a row of comma-separated values.
+    ///
+    /// Messages without a tabular form use their short output.
+    fn csv_output(&self) -> String {
+        self.short_output()
+    }
+
+    /// Output with Artifact IDs as the raw bytes of their hashes.
+    ///
+    /// Messages without Artifact IDs have no binary output, and are
+    /// printed as their short output instead.
+    fn binary_output(&self) -> Option<Vec<u8>> {
+        None
+    }
+
     fn format(&self, format: Format) -> String {
         let mut output = match format {
             Format::Plain => self.plain_output(),
             // SAFETY: serde_json::Value can always be converted to a string.
             Format::Json => serde_json::to_string(&self.json_output()).unwrap(),
             Format::Short => self.short_output(),
+            Format::Oid => self.oid_output(),
+            Format::Csv => self.csv_output(),
+            Format::Binary => self.short_output(),
         };
 
         if output.ends_with('\n').not() {
@@ -181,10 +220,18 @@ pub trait CommandOutput: Debug + DynClone + Send + 'static {
     }
 }
 
-/// Print the contents of the message synchronously.
-fn sync_print(status: Status, output: String) -> Result<()> {
-    let bytes = output.as_bytes();
+/// Apply the user's choice of whether to colorize output.
+///
+/// With no explicit choice, output is colorized only when written to a terminal.
+pub fn set_color(choice: Option<bool>) {
+    if let Some(enabled) = choice {
+        console::set_colors_enabled(enabled);
+        console::set_colors_enabled_stderr(enabled);
+    }
+}
 
+/// Print the contents of the message synchronously.
+fn sync_print(status: Status, bytes: &[u8]) -> Result<()> {
     match status {
         Status::Success => std::io::stdout()
             .write_all(bytes)
@@ -9,15 +9,22 @@ use std::{
 #[derive(Debug, Clone)]
 pub struct PathsMsg {
     data: BTreeMap<String, Option<PathBuf>>,
+    store: Option<&'static str>,
 }
 
 impl PathsMsg {
     pub fn new() -> Self {
         PathsMsg {
             data: BTreeMap::new(),
+            store: None,
         }
     }
 
+    /// Set the status of the store at the root, such as "valid" or "missing".
+    pub fn set_store_status(&mut self, status: &'static str) {
+        self.store = Some(status);
+    }
+
     pub fn insert(&mut self, name: &'static str, path: Option<&Path>) {
         self.data
             .insert(name.to_string(), path.map(ToOwned::to_owned));
@@ -34,7 +41,8 @@ impl CommandOutput for PathsMsg {
     fn plain_output(&self) -> String {
         let pad_width = self.data.keys().map(|key| key.len()).max().unwrap_or(10) + 2;
 
-        self.data
+        let mut output = self
+            .data
             .iter()
             .fold(String::new(), |mut output, (name, path)| {
                 output.push_str(&format!(
@@ -44,7 +52,18 @@ impl CommandOutput for PathsMsg {
                     width = pad_width,
                 ));
                 output
-            })
+            });
+
+        if let Some(store) = self.store {
+            output.push_str(&format!(
+                "{:>width$}: {}\n",
+                Style::new().blue().bold().apply_to("store"),
+                store,
+                width = pad_width,
+            ));
+        }
+
+        output
     }
 
     fn short_output(&self) -> String {
@@ -55,13 +74,19 @@ impl CommandOutput for PathsMsg {
     }
 
     fn json_output(&self) -> serde_json::Value {
-        self.data
+        let mut map = self
+            .data
             .iter()
             .fold(serde_json::Map::new(), |mut map, (name, path)| {
                 map.insert(name.to_string(), json!(opt_path(path)));
                 map
-            })
-            .into()
+            });
+
+        if let Some(store) = self.store {
+            map.insert(String::from("store"), json!(store));
+        }
+
+        map.into()
     }
 
     fn status(&self) -> Status {
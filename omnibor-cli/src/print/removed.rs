@@ -0,0 +1,30 @@
+use crate::print::{CommandOutput, Status};
+use console::Style;
+use serde_json::json;
+
+#[derive(Debug, Clone)]
+pub struct RemovedMsg {
+    pub count: usize,
+}
+
+impl CommandOutput for RemovedMsg {
+    fn plain_output(&self) -> String {
+        format!(
+            "{} {}",
+            Style::new().dim().apply_to("removed manifests:"),
+            Style::new().blue().bold().apply_to(self.count)
+        )
+    }
+
+    fn short_output(&self) -> String {
+        self.count.to_string()
+    }
+
+    fn json_output(&self) -> serde_json::Value {
+        json!({"removed": self.count})
+    }
+
+    fn status(&self) -> Status {
+        Status::Success
+    }
+}
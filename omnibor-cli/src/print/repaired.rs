@@ -0,0 +1,30 @@
+use crate::print::{CommandOutput, Status};
+use console::Style;
+use serde_json::json;
+
+#[derive(Debug, Clone)]
+pub struct RepairedMsg {
+    pub restored_targets: usize,
+}
+
+impl CommandOutput for RepairedMsg {
+    fn plain_output(&self) -> String {
+        format!(
+            "{} {}",
+            Style::new().dim().apply_to("restored targets:"),
+            Style::new().blue().bold().apply_to(self.restored_targets),
+        )
+    }
+
+    fn short_output(&self) -> String {
+        self.restored_targets.to_string()
+    }
+
+    fn json_output(&self) -> serde_json::Value {
+        json!({ "restored_targets": self.restored_targets })
+    }
+
+    fn status(&self) -> Status {
+        Status::Success
+    }
+}
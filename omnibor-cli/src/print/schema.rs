@@ -0,0 +1,27 @@
+use crate::print::{CommandOutput, Status};
+use serde_json::Value as JsonValue;
+
+#[derive(Debug, Clone)]
+pub struct SchemaMsg {
+    pub schema: JsonValue,
+}
+
+impl CommandOutput for SchemaMsg {
+    fn plain_output(&self) -> String {
+        // SAFETY: serde_json::Value can always be converted to a string.
+        serde_json::to_string_pretty(&self.schema).unwrap()
+    }
+
+    fn short_output(&self) -> String {
+        // SAFETY: serde_json::Value can always be converted to a string.
+        serde_json::to_string(&self.schema).unwrap()
+    }
+
+    fn json_output(&self) -> JsonValue {
+        self.schema.clone()
+    }
+
+    fn status(&self) -> Status {
+        Status::Success
+    }
+}
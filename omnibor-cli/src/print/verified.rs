@@ -0,0 +1,45 @@
+use crate::print::{CommandOutput, Status};
+use console::Style;
+use omnibor::{hashes::Sha256, ArtifactId};
+use serde_json::json;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone)]
+pub struct VerifiedMsg {
+    pub path: PathBuf,
+    pub manifest_aid: ArtifactId<Sha256>,
+    pub target_aid: ArtifactId<Sha256>,
+}
+
+impl VerifiedMsg {
+    fn path_string(&self) -> String {
+        self.path.display().to_string()
+    }
+}
+
+impl CommandOutput for VerifiedMsg {
+    fn plain_output(&self) -> String {
+        format!(
+            "{} {} {}",
+            self.path_string(),
+            Style::new().dim().apply_to("=>"),
+            Style::new().green().apply_to(self.manifest_aid.to_string()),
+        )
+    }
+
+    fn short_output(&self) -> String {
+        self.manifest_aid.to_string()
+    }
+
+    fn json_output(&self) -> serde_json::Value {
+        json!({
+            "path": self.path_string(),
+            "manifest": self.manifest_aid.to_string(),
+            "target": self.target_aid.to_string(),
+        })
+    }
+
+    fn status(&self) -> Status {
+        Status::Success
+    }
+}
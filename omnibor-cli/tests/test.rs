@@ -7,6 +7,7 @@ macro_rules! settings {
         let mut settings = Settings::clone_current();
         settings.add_filter(r#"omnibor(?:\.exe)?"#, "omnibor");
         settings.add_filter(r#"gitoid:blob:sha256:[a-f0-9]{64}"#, "<GITOID>");
+        settings.add_filter(r#"\b[a-f0-9]{64}\b"#, "<HASH>");
         settings.bind(|| $block);
     };
 }
@@ -80,3 +81,340 @@ fn artifact_id_json() {
         ]))
     });
 }
+
+#[test]
+fn artifact_id_oid() {
+    settings!({
+        assert_cmd_snapshot!(Command::new(get_cargo_bin("omnibor")).args([
+            "artifact",
+            "id",
+            "--format",
+            "oid",
+            "--path",
+            "tests/data/main.c"
+        ]))
+    });
+}
+
+#[test]
+fn artifact_id_sort_path() {
+    settings!({
+        assert_cmd_snapshot!(Command::new(get_cargo_bin("omnibor")).args([
+            "artifact",
+            "id",
+            "--format",
+            "plain",
+            "--sort",
+            "path",
+            "--path",
+            "tests/data"
+        ]))
+    });
+}
+
+#[test]
+fn artifact_id_hash_only_binary() {
+    let run = |args: &[&str]| {
+        let output = Command::new(get_cargo_bin("omnibor"))
+            .args(["artifact", "id", "--path", "tests/data", "--hash-only"])
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{output:?}");
+        output.stdout
+    };
+
+    let hex = String::from_utf8(run(&["--hex"])).unwrap();
+    let mut hex_lines = hex.lines().collect::<Vec<_>>();
+    hex_lines.sort();
+
+    // The binary hashes are in path order, so compare them as a set.
+    let binary = run(&["--binary"]);
+    assert_eq!(binary.len(), 32 * hex_lines.len());
+    let mut binary_lines = binary
+        .chunks(32)
+        .map(|hash| hash.iter().map(|byte| format!("{byte:02x}")).collect())
+        .collect::<Vec<String>>();
+    binary_lines.sort();
+
+    assert_eq!(binary_lines, hex_lines);
+}
+
+/// Create a manifest for `tests/data/main.c` with some extra arguments,
+/// returning the lines of the written manifest.
+fn manifest_create(args: &[&str], out_dir: &str) -> Vec<String> {
+    let out_dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join(out_dir);
+    let _ = std::fs::remove_dir_all(&out_dir);
+    std::fs::create_dir_all(&out_dir).unwrap();
+
+    let output = Command::new(get_cargo_bin("omnibor"))
+        .args(["manifest", "create", "--no-store", "--target"])
+        .arg("tests/data/main.c")
+        .args(args)
+        .arg("--output")
+        .arg(&out_dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+
+    let manifest = std::fs::read_dir(&out_dir)
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap()
+        .path();
+    let lines = std::fs::read_to_string(manifest)
+        .unwrap()
+        .lines()
+        .map(String::from)
+        .collect();
+    std::fs::remove_dir_all(&out_dir).unwrap();
+    lines
+}
+
+/// Create a manifest for `tests/data/main.c` with inputs from a build log,
+/// returning the lines of the written manifest.
+#[cfg(feature = "build-log-parse")]
+fn manifest_from_build_log(log: &str, out_dir: &str) -> Vec<String> {
+    manifest_create(&["--inputs-from-build-log", log], out_dir)
+}
+
+#[test]
+fn manifest_create_parallel_matches_sequential() {
+    let inputs = [
+        "-i",
+        "tests/data/main.c",
+        "-i",
+        "tests/data/build-logs/main.d",
+        "-i",
+        "tests/data/build-logs/compile_commands.json",
+    ];
+    let sequential = manifest_create(&inputs, "sequential");
+    let parallel = manifest_create(&[&inputs[..], &["--parallel", "3"]].concat(), "parallel");
+    assert_eq!(sequential.len(), 4, "{sequential:?}");
+    assert_eq!(sequential, parallel);
+}
+
+#[test]
+fn manifest_create_inputs_from_file_list() {
+    let aid = "gitoid:blob:sha256:09ca7e4eaa6e8ae9c7d261167129184883644d07dfba7cbfbc4c8a2e08360d5b";
+    let expected = manifest_create(
+        &[
+            "-i",
+            "tests/data/main.c",
+            "-i",
+            aid,
+            "-i",
+            "tests/data/build-logs/main.d",
+        ],
+        "file_list_expected",
+    );
+
+    let tmp = std::path::Path::new(env!("CARGO_TARGET_TMPDIR"));
+    let newline_list = tmp.join("inputs.txt");
+    std::fs::write(
+        &newline_list,
+        format!("# Inputs\ntests/data/main.c\r\n\n{aid}\n"),
+    )
+    .unwrap();
+    let null_list = tmp.join("inputs.nul");
+    std::fs::write(&null_list, format!("{aid}\0tests/data/main.c\0")).unwrap();
+
+    let from_lines = manifest_create(
+        &[
+            "--inputs-from-file-list",
+            newline_list.to_str().unwrap(),
+            "-i",
+            "tests/data/build-logs/main.d",
+        ],
+        "file_list_lines",
+    );
+    let from_nul = manifest_create(
+        &[
+            "--inputs-from-file-list",
+            null_list.to_str().unwrap(),
+            "--null-separated",
+            "-i",
+            "tests/data/build-logs/main.d",
+        ],
+        "file_list_nul",
+    );
+
+    assert_eq!(expected.len(), 4, "{expected:?}");
+    assert_eq!(from_lines, expected);
+    assert_eq!(from_nul, expected);
+}
+
+#[cfg(unix)]
+#[test]
+fn manifest_create_inputs_from_file_list_non_utf8_path() {
+    use std::os::unix::ffi::OsStrExt as _;
+
+    let tmp = std::path::Path::new(env!("CARGO_TARGET_TMPDIR"));
+    let input = tmp.join(std::ffi::OsStr::from_bytes(b"main-\xff.c"));
+    std::fs::copy("tests/data/main.c", &input).unwrap();
+
+    let list = tmp.join("inputs-non-utf8.txt");
+    let mut contents = input.as_os_str().as_bytes().to_vec();
+    contents.push(b'\n');
+    std::fs::write(&list, contents).unwrap();
+
+    let expected = manifest_create(&["-i", "tests/data/main.c"], "non_utf8_expected");
+    let from_list = manifest_create(
+        &["--inputs-from-file-list", list.to_str().unwrap()],
+        "non_utf8_list",
+    );
+
+    assert_eq!(expected.len(), 2, "{expected:?}");
+    assert_eq!(from_list, expected);
+}
+
+#[test]
+fn manifest_create_json_output_file() {
+    let out_dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("json_output_file");
+    let _ = std::fs::remove_dir_all(&out_dir);
+    std::fs::create_dir_all(&out_dir).unwrap();
+    let out_file = out_dir.join("main.manifest.json");
+
+    let output = Command::new(get_cargo_bin("omnibor"))
+        .args(["manifest", "create", "--no-store", "--target"])
+        .arg("tests/data/main.c")
+        .args(["-i", "tests/data/main.c", "--output-file"])
+        .arg(&out_file)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+
+    let manifest: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(&out_file).unwrap()).unwrap();
+    std::fs::remove_dir_all(&out_dir).unwrap();
+
+    assert!(manifest["manifest"]
+        .as_str()
+        .unwrap()
+        .starts_with("gitoid:blob:sha256:"));
+    assert!(manifest["target"]
+        .as_str()
+        .unwrap()
+        .starts_with("gitoid:blob:sha256:"));
+    assert_eq!(manifest["relations"].as_array().unwrap().len(), 1);
+    assert!(manifest["relations"][0]["manifest"].is_null());
+}
+
+#[test]
+fn store_list_csv() {
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("store_list_csv");
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let output = Command::new(get_cargo_bin("omnibor"))
+        .arg("--dir")
+        .arg(&dir)
+        .args(["manifest", "create", "--no-out", "--target"])
+        .arg("tests/data/main.c")
+        .args([
+            "-i",
+            "tests/data/main.c",
+            "-i",
+            "tests/data/build-logs/main.d",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+
+    let output = Command::new(get_cargo_bin("omnibor"))
+        .arg("--dir")
+        .arg(&dir)
+        .args(["--format", "csv", "store", "list"])
+        .output()
+        .unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(output.status.success(), "{output:?}");
+
+    let mut reader = csv::Reader::from_reader(&output.stdout[..]);
+    assert_eq!(
+        reader.headers().unwrap(),
+        vec!["manifest_aid", "target_aid", "input_count", "detached"]
+    );
+
+    let rows = reader.records().collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(rows.len(), 1);
+    assert!(rows[0][0].starts_with("gitoid:blob:sha256:"));
+    assert!(rows[0][1].starts_with("gitoid:blob:sha256:"));
+    assert_eq!(&rows[0][2], "2");
+    assert_eq!(&rows[0][3], "false");
+}
+
+/// Reading from a FIFO with no writer blocks forever, standing in for slow I/O.
+#[cfg(unix)]
+#[test]
+fn timeout_exits_with_code_2() {
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("timeout");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let fifo = dir.join("slow");
+
+    let status = Command::new("mkfifo").arg(&fifo).status().unwrap();
+    assert!(status.success());
+
+    let output = Command::new(get_cargo_bin("omnibor"))
+        .args([
+            "--timeout",
+            "1",
+            "--format",
+            "json",
+            "artifact",
+            "id",
+            "--path",
+        ])
+        .arg(&fifo)
+        .output()
+        .unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(output.status.code(), Some(2), "{output:?}");
+    let error: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(error["error"], "timed out after 1s");
+}
+
+#[test]
+fn list_env_vars_json() {
+    let output = Command::new(get_cargo_bin("omnibor"))
+        .args(["--list-env-vars", "--format", "json"])
+        .env("OMNIBOR_HASH", "sha256")
+        .env_remove("OMNIBOR_CONFIG")
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+
+    let vars: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let var = |name: &str| {
+        vars.as_array()
+            .unwrap()
+            .iter()
+            .find(|var| var["name"] == name)
+            .cloned()
+            .unwrap()
+    };
+
+    assert_eq!(var("OMNIBOR_HASH")["value"], "sha256");
+    assert!(var("OMNIBOR_CONFIG")["value"].is_null());
+    assert!(var("OMNIBOR_DIR")["description"].is_string());
+}
+
+#[cfg(feature = "build-log-parse")]
+#[test]
+fn manifest_create_inputs_from_dep_file() {
+    let lines = manifest_from_build_log("tests/data/build-logs/main.d", "dep_file");
+    assert_eq!(lines.len(), 3, "{lines:?}");
+    assert_eq!(lines[0], "gitoid:blob:sha256");
+}
+
+#[cfg(feature = "build-log-parse")]
+#[test]
+fn manifest_create_inputs_from_compile_commands() {
+    let lines = manifest_from_build_log(
+        "tests/data/build-logs/compile_commands.json",
+        "compile_commands",
+    );
+    assert_eq!(lines.len(), 2, "{lines:?}");
+}
@@ -0,0 +1,8 @@
+//! C-compatible static and dynamic libraries for the `omnibor` crate.
+//!
+//! The FFI functions themselves live in `omnibor`, in its hidden `ffi`
+//! module. This crate only exists to build them as a `staticlib` and
+//! `cdylib`, which `omnibor` can't do itself without breaking `no_std`
+//! builds: those artifacts always need `std` to link.
+
+pub use omnibor::ffi::*;
@@ -1,35 +1,108 @@
-#[cfg(doc)]
+#[cfg(feature = "cbor")]
+use crate::cbor;
 use crate::hashes::Sha256;
 use crate::hashes::SupportedHash;
+use crate::newline::normalize_newlines;
+#[cfg(feature = "std")]
+use crate::newline::NormalizeNewlines;
+#[cfg(feature = "nix-compat")]
+use crate::nix;
+#[cfg(feature = "parallel-hashing")]
+use crate::parallel;
+#[cfg(feature = "parallel-hashing")]
+use crate::parallel::ParallelChunkReader;
+use crate::similarity;
 use crate::Error;
+#[cfg(feature = "std")]
 use crate::InputManifest;
 use crate::Result;
+#[cfg(feature = "nix-compat")]
+use alloc::string::String;
+#[cfg(feature = "zeroize")]
+use alloc::vec;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::fmt::Debug;
+#[cfg(feature = "std")]
+use core::fmt::Display;
+use core::fmt::Formatter;
+use core::fmt::Result as FmtResult;
+use core::hash::Hash;
+use core::hash::Hasher;
+#[cfg(feature = "serde")]
+use core::marker::PhantomData;
+#[cfg(feature = "std")]
+use core::ops::Not as _;
+#[cfg(feature = "zeroize")]
+use core::ptr;
+#[cfg(feature = "serde")]
+use core::result::Result as StdResult;
+#[cfg(feature = "std")]
+use core::str::FromStr;
+#[cfg(feature = "zeroize")]
+use core::sync::atomic::compiler_fence;
+#[cfg(feature = "zeroize")]
+use core::sync::atomic::Ordering as AtomicOrdering;
+#[cfg(feature = "futures")]
+use futures_util::Stream;
+#[cfg(feature = "futures")]
+use futures_util::StreamExt as _;
 use gitoid::Blob;
+use gitoid::Error as GitOidError;
 use gitoid::GitOid;
 #[cfg(feature = "serde")]
 use serde::de::Deserializer;
 #[cfg(feature = "serde")]
+use serde::de::Error as DeError;
+#[cfg(feature = "serde")]
+use serde::de::SeqAccess;
+#[cfg(feature = "serde")]
+use serde::de::Visitor;
+#[cfg(feature = "serde")]
 use serde::Deserialize;
 #[cfg(feature = "serde")]
 use serde::Serialize;
 #[cfg(feature = "serde")]
 use serde::Serializer;
-use std::cmp::Ordering;
-use std::fmt::Debug;
-use std::fmt::Display;
-use std::fmt::Formatter;
-use std::fmt::Result as FmtResult;
-use std::hash::Hash;
-use std::hash::Hasher;
+#[cfg(feature = "std")]
+use std::fs::read_link;
+#[cfg(feature = "std")]
+use std::fs::File;
+#[cfg(feature = "std")]
+use std::io;
+#[cfg(feature = "std")]
+use std::io::BufReader;
+#[cfg(feature = "std")]
 use std::io::Read;
+#[cfg(feature = "std")]
 use std::io::Seek;
+#[cfg(feature = "std")]
+use std::io::SeekFrom;
+#[cfg(feature = "std")]
+use std::path::Path;
+#[cfg(feature = "std")]
 use std::path::PathBuf;
-#[cfg(feature = "serde")]
-use std::result::Result as StdResult;
-use std::str::FromStr;
+#[cfg(feature = "subtle")]
+use subtle::ConstantTimeEq as _;
+#[cfg(feature = "std")]
+use tokio::fs::File as AsyncFile;
+#[cfg(feature = "std")]
 use tokio::io::AsyncRead;
+#[cfg(feature = "std")]
+use tokio::io::AsyncReadExt as _;
+#[cfg(feature = "std")]
 use tokio::io::AsyncSeek;
+#[cfg(feature = "std")]
+use tokio::io::AsyncSeekExt as _;
+#[cfg(feature = "futures")]
+use tokio::task::spawn_blocking;
+#[cfg(feature = "std")]
 use url::Url;
+#[cfg(feature = "uuid")]
+use uuid::Uuid;
+#[cfg(feature = "zeroize")]
+use zeroize::Zeroize;
 
 /// An OmniBOR Artifact Identifier.
 ///
@@ -37,6 +110,32 @@ use url::Url;
 ///
 /// It is built around, per the specification, any supported hash algorithm.
 /// Currently, only SHA-256 is supported, but others may be added in the future.
+///
+/// # Newline Normalization
+///
+/// Per the specification, CRLF (`\r\n`) newlines in an artifact's content are
+/// normalized to LF (`\n`) before hashing, so a text file produces the same
+/// [`ArtifactId`] whether it was checked out with Windows or Unix line endings.
+/// This applies to every constructor which hashes content. Lone `\r` bytes are
+/// left unchanged.
+///
+/// # Unused Identifiers
+///
+/// Computing an [`ArtifactId`] and then dropping it is almost always a
+/// mistake, so the type is marked `#[must_use]`.
+///
+/// ```rust,compile_fail
+/// #![deny(unused_must_use)]
+/// # use omnibor::ArtifactId;
+/// # use omnibor::hashes::Sha256;
+/// ArtifactId::<Sha256>::id_str("hello, world");
+/// ```
+#[must_use = "computed ArtifactIds should be stored or compared; if this is intentional, use let _ = ..."]
+#[cfg_attr(
+    feature = "diesel",
+    derive(diesel::expression::AsExpression, diesel::deserialize::FromSqlRow)
+)]
+#[cfg_attr(feature = "diesel", diesel(sql_type = diesel::sql_types::Text))]
 pub struct ArtifactId<H: SupportedHash> {
     #[doc(hidden)]
     gitoid: GitOid<H::HashAlgorithm, Blob>,
@@ -60,7 +159,7 @@ impl<H: SupportedHash> ArtifactId<H> {
     /// If it were ever absolutely needed in the future, we might expose this
     /// constructor with a `#[doc(hidden)]` attribute, or with documentation
     /// which clearly outlines the extra complexity.
-    fn from_gitoid(gitoid: GitOid<H::HashAlgorithm, Blob>) -> ArtifactId<H> {
+    pub(crate) fn from_gitoid(gitoid: GitOid<H::HashAlgorithm, Blob>) -> ArtifactId<H> {
         ArtifactId { gitoid }
     }
 
@@ -82,8 +181,9 @@ impl<H: SupportedHash> ArtifactId<H> {
     /// let id: ArtifactId<Sha256> = ArtifactId::id_bytes(&[0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
     /// println!("Artifact ID: {}", id);
     /// ```
+    #[must_use = "this returns the new ArtifactId without storing it"]
     pub fn id_bytes<B: AsRef<[u8]>>(content: B) -> ArtifactId<H> {
-        ArtifactId::from_gitoid(GitOid::id_bytes(content))
+        ArtifactId::from_gitoid(GitOid::id_bytes(normalize_newlines(content.as_ref())))
     }
 
     /// Construct an [`ArtifactId`] from a string.
@@ -104,10 +204,12 @@ impl<H: SupportedHash> ArtifactId<H> {
     /// let id: ArtifactId<Sha256> = ArtifactId::id_str("hello, world");
     /// println!("Artifact ID: {}", id);
     /// ```
+    #[must_use = "this returns the new ArtifactId without storing it"]
     pub fn id_str<S: AsRef<str>>(s: S) -> ArtifactId<H> {
-        ArtifactId::from_gitoid(GitOid::id_str(s))
+        ArtifactId::id_bytes(s.as_ref().as_bytes())
     }
 
+    #[cfg(feature = "std")]
     /// Construct an [`ArtifactId`] from a synchronous reader.
     ///
     /// This reads the content of the reader and hashes it to produce an identifier.
@@ -132,7 +234,8 @@ impl<H: SupportedHash> ArtifactId<H> {
     /// error.
     ///
     /// Reads are buffered internally to reduce the number of syscalls and context switches
-    /// between the kernel and user code.
+    /// between the kernel and user code. The reader is read twice: once to find the length
+    /// of the content after newline normalization, and once to hash it.
     ///
     /// # Example
     ///
@@ -144,11 +247,23 @@ impl<H: SupportedHash> ArtifactId<H> {
     /// let id: ArtifactId<Sha256> = ArtifactId::id_reader(&file).unwrap();
     /// println!("Artifact ID: {}", id);
     /// ```
-    pub fn id_reader<R: Read + Seek>(reader: R) -> Result<ArtifactId<H>> {
-        let gitoid = GitOid::id_reader(reader)?;
+    pub fn id_reader<R: Read + Seek>(mut reader: R) -> Result<ArtifactId<H>> {
+        // Find the normalized length with a first pass, then rewind and hash.
+        let start = reader.stream_position().map_err(GitOidError::from)?;
+        let expected_length = io::copy(&mut NormalizeNewlines::new(&mut reader), &mut io::sink())
+            .map_err(GitOidError::from)?;
+        reader
+            .seek(SeekFrom::Start(start))
+            .map_err(GitOidError::from)?;
+
+        let gitoid = GitOid::id_reader_with_length(
+            NormalizeNewlines::new(reader),
+            expected_length as usize,
+        )?;
         Ok(ArtifactId::from_gitoid(gitoid))
     }
 
+    #[cfg(feature = "std")]
     /// Construct an [`ArtifactId`] from a synchronous reader with an expected length.
     ///
     /// This reads the content of the reader and hashes it to produce an identifier.
@@ -165,8 +280,12 @@ impl<H: SupportedHash> ArtifactId<H> {
     /// entire file. Make sure to provide the expected number of bytes for the full file
     /// to protect against this error.
     ///
-    /// Reads are buffered internally to reduce the number of syscalls and context switches
-    /// between the kernel and user code.
+    /// The expected length is the length of the content _before_ newline normalization.
+    /// The normalized length, which is hashed first, isn't known until the reader is
+    /// exhausted, and the reader can't be rewound, so the content is held in memory
+    /// before being hashed. No more than `expected_length` bytes, plus one to detect
+    /// extra content, are read. Use [`ArtifactId::id_reader`] to hash a seekable
+    /// reader without holding its content.
     ///
     /// # Example
     ///
@@ -182,10 +301,48 @@ impl<H: SupportedHash> ArtifactId<H> {
         reader: R,
         expected_length: usize,
     ) -> Result<ArtifactId<H>> {
-        let gitoid = GitOid::id_reader_with_length(reader, expected_length)?;
-        Ok(ArtifactId::from_gitoid(gitoid))
+        let mut content = Vec::new();
+        reader
+            .take(read_limit(expected_length))
+            .read_to_end(&mut content)
+            .map_err(GitOidError::from)?;
+        check_read_length(expected_length, content.len())?;
+        Ok(ArtifactId::id_bytes(content))
+    }
+
+    #[cfg(feature = "std")]
+    /// Construct an [`ArtifactId`] from a synchronous reader which can't seek.
+    ///
+    /// This reads the content of the reader and hashes it to produce an identifier.
+    ///
+    /// Unlike [`ArtifactId::id_reader`], this doesn't need [`Seek`], so it works with
+    /// readers like network streams or pipes. The whole content is read into memory
+    /// before being hashed. Because the reader isn't checked against a known length,
+    /// prefer [`ArtifactId::id_reader_with_length`] when the length is known ahead of
+    /// time, such as from a `Content-Length` header.
+    ///
+    /// Also note that this doesn't reset the reader to the beginning of its region; if
+    /// you provide a reader which has already read some portion of its content, the
+    /// resulting hash will _not_ encompass the entire content.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use omnibor::ArtifactId;
+    /// # use omnibor::hashes::Sha256;
+    /// let stream: &[u8] = b"hello, world";
+    /// let id: ArtifactId<Sha256> = ArtifactId::id_unseekable_reader(stream).unwrap();
+    /// assert_eq!(id, ArtifactId::id_str("hello, world"));
+    /// ```
+    pub fn id_unseekable_reader<R: Read>(mut reader: R) -> Result<ArtifactId<H>> {
+        let mut content = Vec::new();
+        reader
+            .read_to_end(&mut content)
+            .map_err(GitOidError::from)?;
+        Ok(ArtifactId::id_bytes(content))
     }
 
+    #[cfg(feature = "std")]
     /// Construct an [`ArtifactId`] from an asynchronous reader.
     ///
     /// This reads the content of the reader and hashes it to produce an identifier.
@@ -212,8 +369,8 @@ impl<H: SupportedHash> ArtifactId<H> {
     /// expected length of the full file in bytes to defend against this "partial hash"
     /// error.
     ///
-    /// Reads are buffered internally to reduce the number of syscalls and context switches
-    /// between the kernel and user code.
+    /// The reader is read twice: once to find the length of the content after newline
+    /// normalization, and once to hash it.
     ///
     /// # Example
     ///
@@ -228,12 +385,30 @@ impl<H: SupportedHash> ArtifactId<H> {
     /// # })
     /// ```
     pub async fn id_async_reader<R: AsyncRead + AsyncSeek + Unpin>(
-        reader: R,
+        mut reader: R,
     ) -> Result<ArtifactId<H>> {
-        let gitoid = GitOid::id_async_reader(reader).await?;
+        // Find the normalized length with a first pass, then rewind and hash.
+        let start = reader.stream_position().await.map_err(GitOidError::from)?;
+        let expected_length = tokio::io::copy(
+            &mut NormalizeNewlines::new(&mut reader),
+            &mut tokio::io::sink(),
+        )
+        .await
+        .map_err(GitOidError::from)?;
+        reader
+            .seek(SeekFrom::Start(start))
+            .await
+            .map_err(GitOidError::from)?;
+
+        let gitoid = GitOid::id_async_reader_with_length(
+            NormalizeNewlines::new(reader),
+            expected_length as usize,
+        )
+        .await?;
         Ok(ArtifactId::from_gitoid(gitoid))
     }
 
+    #[cfg(feature = "std")]
     /// Construct an [`ArtifactId`] from an asynchronous reader with an expected length.
     ///
     /// This reads the content of the reader and hashes it to produce an identifier.
@@ -253,8 +428,11 @@ impl<H: SupportedHash> ArtifactId<H> {
     /// entire file. Make sure to provide the expected number of bytes for the full file
     /// to protect against this error.
     ///
-    /// Reads are buffered internally to reduce the number of syscalls and context switches
-    /// between the kernel and user code.
+    /// The expected length is the length of the content _before_ newline normalization.
+    /// As with [`ArtifactId::id_reader_with_length`], the content is held in memory
+    /// before being hashed, and no more than `expected_length` bytes, plus one to detect
+    /// extra content, are read. Use [`ArtifactId::id_async_reader`] to hash a seekable
+    /// reader without holding its content.
     ///
     /// # Example
     ///
@@ -272,15 +450,275 @@ impl<H: SupportedHash> ArtifactId<H> {
         reader: R,
         expected_length: usize,
     ) -> Result<ArtifactId<H>> {
-        let gitoid = GitOid::id_async_reader_with_length(reader, expected_length).await?;
+        let mut content = Vec::new();
+        reader
+            .take(read_limit(expected_length))
+            .read_to_end(&mut content)
+            .await
+            .map_err(GitOidError::from)?;
+        check_read_length(expected_length, content.len())?;
+        Ok(ArtifactId::id_bytes(content))
+    }
+
+    #[cfg(feature = "reqwest")]
+    /// Construct an [`ArtifactId`] from the body of an asynchronous `reqwest` response.
+    ///
+    /// Headers are not included. The body is read chunk by chunk as it
+    /// arrives. It's still held in memory until it's complete, because newline
+    /// normalization can change its length, and the length is hashed first.
+    ///
+    /// If the response has a `Content-Length`, the number of bytes read is
+    /// checked against it.
+    ///
+    /// See the [`IntoArtifactId`](crate::IntoArtifactId) impl for
+    /// `reqwest::blocking::Response` for the synchronous equivalent.
+    pub async fn id_async_response(mut response: reqwest::Response) -> Result<ArtifactId<H>> {
+        let expected_length = response.content_length();
+        let mut content = Vec::new();
+
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(Error::CantReadResponseBody)?
+        {
+            content.extend_from_slice(&chunk);
+
+            // Stop reading a body which is already longer than promised.
+            if let Some(expected_length) = expected_length {
+                if content.len() as u64 > expected_length {
+                    break;
+                }
+            }
+        }
+
+        if let Some(expected_length) = expected_length {
+            check_read_length(expected_length as usize, content.len())?;
+        }
+
+        Ok(ArtifactId::id_bytes(content))
+    }
+
+    #[cfg(feature = "std")]
+    /// Construct an [`ArtifactId`] for the target path stored in a symlink.
+    ///
+    /// This reads the link itself with [`std::fs::read_link`] and hashes the
+    /// bytes of the path it points to, _without_ following it. This is how Git
+    /// identifies symlinks. On Unix the raw bytes of the target path are
+    /// hashed; elsewhere the target path must be valid UTF-8.
+    ///
+    /// Returns an [`Error`] if `link_path` is not a symlink.
+    ///
+    /// Use [`ArtifactId::compute_for_symlink_content`] to instead identify
+    /// the file the symlink points to.
+    pub fn compute_for_symlink_target_bytes(link_path: &Path) -> Result<ArtifactId<H>> {
+        let target = read_link(link_path)
+            .map_err(|e| Error::CantReadSymlink(link_path.display().to_string(), e))?;
+
+        #[cfg(unix)]
+        let bytes = {
+            use std::os::unix::ffi::OsStrExt as _;
+            target.as_os_str().as_bytes()
+        };
+
+        #[cfg(not(unix))]
+        let bytes = target
+            .to_str()
+            .ok_or_else(|| Error::NonUtf8SymlinkTarget(link_path.display().to_string()))?
+            .as_bytes();
+
+        Ok(ArtifactId::id_bytes(bytes))
+    }
+
+    #[cfg(feature = "std")]
+    /// Construct an [`ArtifactId`] for the file a symlink points to.
+    ///
+    /// This follows the link (and any further links) and hashes the content
+    /// of the file at the end, which is the same as identifying the path
+    /// with [`IntoArtifactId`](crate::IntoArtifactId).
+    ///
+    /// Use [`ArtifactId::compute_for_symlink_target_bytes`] to instead
+    /// identify the link itself.
+    pub fn compute_for_symlink_content(link_path: &Path) -> Result<ArtifactId<H>> {
+        let file = BufReader::new(File::open(link_path)?);
+        ArtifactId::id_reader(file)
+    }
+
+    #[cfg(feature = "parallel-hashing")]
+    /// Construct an [`ArtifactId`] for a file, reading it in parallel chunks.
+    ///
+    /// The file is split into blocks of `chunk_size` bytes (a size of zero is
+    /// treated as one), which are read concurrently on the Rayon thread pool
+    /// and fed to the hasher in file order. The GitOID construction hashes
+    /// content as a single stream, so the hash computation itself is still
+    /// sequential; this helps most when reads, rather than hashing, are the
+    /// bottleneck.
+    ///
+    /// The file is read twice: once to find its length after newline
+    /// normalization, and once to hash it. The result is the same as for
+    /// [`ArtifactId::id_reader`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use omnibor::ArtifactId;
+    /// # use omnibor::hashes::Sha256;
+    /// let id: ArtifactId<Sha256> =
+    ///     ArtifactId::compute_parallel("test/data/hello_world.txt", 4).unwrap();
+    /// println!("Artifact ID: {}", id);
+    /// ```
+    pub fn compute_parallel<P: AsRef<Path>>(path: P, chunk_size: usize) -> Result<ArtifactId<H>> {
+        let path = path.as_ref();
+        let chunk_size = chunk_size.max(1);
+        let file_len = path.metadata().map_err(GitOidError::from)?.len();
+
+        let expected_length =
+            parallel::normalized_len(path, file_len, chunk_size).map_err(GitOidError::from)?;
+        let reader = ParallelChunkReader::new(path, file_len, chunk_size);
+
+        let gitoid = GitOid::id_reader_with_length(
+            NormalizeNewlines::new(reader),
+            expected_length as usize,
+        )?;
         Ok(ArtifactId::from_gitoid(gitoid))
     }
 
+    #[cfg(feature = "std")]
+    /// Check whether two files have the same [`ArtifactId`].
+    ///
+    /// Both files are hashed in full and their identifiers compared, so
+    /// files which differ only in CRLF vs. LF newlines compare as equal.
+    ///
+    /// Use [`ArtifactId::compare_files_async`] to read the files with Tokio.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use omnibor::ArtifactId;
+    /// # use omnibor::hashes::Sha256;
+    /// let same = ArtifactId::<Sha256>::compare_files(
+    ///     "test/data/hello_world.txt",
+    ///     "test/data/hello_world.txt",
+    /// )
+    /// .unwrap();
+    /// assert!(same);
+    /// ```
+    pub fn compare_files<P1: AsRef<Path>, P2: AsRef<Path>>(a: P1, b: P2) -> Result<bool> {
+        let a = ArtifactId::<H>::id_reader(BufReader::new(File::open(a)?))?;
+        let b = ArtifactId::<H>::id_reader(BufReader::new(File::open(b)?))?;
+        Ok(a == b)
+    }
+
+    #[cfg(feature = "std")]
+    /// Check whether two files have the same [`ArtifactId`], reading them asynchronously.
+    ///
+    /// This is the asynchronous equivalent of [`ArtifactId::compare_files`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use omnibor::ArtifactId;
+    /// # use omnibor::hashes::Sha256;
+    /// # tokio_test::block_on(async {
+    /// let same = ArtifactId::<Sha256>::compare_files_async(
+    ///     "test/data/hello_world.txt",
+    ///     "test/data/hello_world.txt",
+    /// )
+    /// .await
+    /// .unwrap();
+    /// assert!(same);
+    /// # })
+    /// ```
+    pub async fn compare_files_async<P1: AsRef<Path>, P2: AsRef<Path>>(
+        a: P1,
+        b: P2,
+    ) -> Result<bool> {
+        let a = AsyncFile::open(a).await.map_err(GitOidError::from)?;
+        let b = AsyncFile::open(b).await.map_err(GitOidError::from)?;
+        let a = ArtifactId::<H>::id_async_reader(a).await?;
+        let b = ArtifactId::<H>::id_async_reader(b).await?;
+        Ok(a == b)
+    }
+
+    #[cfg(feature = "futures")]
+    /// Identify each file from a stream of paths, producing a stream of results.
+    ///
+    /// Each file is read and hashed on Tokio's blocking thread pool with
+    /// [`tokio::task::spawn_blocking`], so hashing doesn't block the async
+    /// executor. Files are identified one at a time, in the order their paths
+    /// arrive, and the next path isn't taken from `paths` until the result for
+    /// the current one has been consumed, so a slow consumer applies
+    /// backpressure to the producer of paths.
+    ///
+    /// This must be polled from within a Tokio runtime.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use omnibor::ArtifactId;
+    /// # use omnibor::hashes::Sha256;
+    /// # use futures_util::stream::{self, StreamExt as _};
+    /// # use std::path::PathBuf;
+    /// # tokio_test::block_on(async {
+    /// let paths = stream::iter([PathBuf::from("test/data/hello_world.txt")]);
+    /// let ids = ArtifactId::<Sha256>::identify_stream(paths)
+    ///     .collect::<Vec<_>>()
+    ///     .await;
+    /// assert!(ids[0].is_ok());
+    /// # })
+    /// ```
+    pub fn identify_stream<S>(paths: S) -> impl Stream<Item = Result<ArtifactId<H>>>
+    where
+        S: Stream<Item = PathBuf>,
+        H: 'static,
+        ArtifactId<H>: Send,
+    {
+        paths.then(|path| async move {
+            spawn_blocking(move || {
+                let file = BufReader::new(File::open(&path)?);
+                ArtifactId::<H>::id_reader(file)
+            })
+            .await
+            .map_err(Error::IdentifyTaskFailed)?
+        })
+    }
+
+    #[cfg(feature = "std")]
     /// Construct an [`ArtifactId`] for an [`InputManifest`].
     pub fn id_manifest(manifest: &InputManifest<H>) -> Result<Self> {
         Ok(ArtifactId::id_bytes(manifest.as_bytes()?))
     }
 
+    #[cfg(feature = "std")]
+    /// Get the [`ArtifactId`] of an [`InputManifest`], like one you just built.
+    ///
+    /// This is the ID the manifest is stored under, and the one to embed in
+    /// its target. Unlike [`ArtifactId::id_manifest`], this can't fail, since
+    /// serializing a manifest to memory never fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use omnibor::ArtifactId;
+    /// # use omnibor::InputManifestBuilder;
+    /// # use omnibor::ShouldStore;
+    /// # use omnibor::embedding::NoEmbed;
+    /// # use omnibor::hashes::Sha256;
+    /// # use omnibor::storage::InMemoryStorage;
+    /// # use std::path::Path;
+    /// let storage = InMemoryStorage::new();
+    /// let mut builder = InputManifestBuilder::<Sha256, NoEmbed, _>::with_storage(storage);
+    /// builder.add_relation(ArtifactId::id_str("input")).unwrap();
+    /// let linked = builder
+    ///     .finish(Path::new("test/data/hello_world.txt"), ShouldStore::No)
+    ///     .unwrap();
+    /// assert_eq!(ArtifactId::from_manifest(linked.manifest()), linked.manifest_aid());
+    /// ```
+    pub fn from_manifest(manifest: &InputManifest<H>) -> Self {
+        // PANIC SAFETY: Manifests are serialized to a `Vec`, which can't fail.
+        ArtifactId::id_manifest(manifest).unwrap()
+    }
+
+    #[cfg(feature = "std")]
     /// Construct an [`ArtifactId`] from a `gitoid`-scheme [`Url`].
     ///
     /// This validates that the provided URL has a hashing scheme which matches the one
@@ -308,11 +746,65 @@ impl<H: SupportedHash> ArtifactId<H> {
         ArtifactId::try_from(url)
     }
 
+    /// Construct an [`ArtifactId`] by wrapping an already-computed hash.
+    ///
+    /// This is for interoperating with systems which cache hashes, so an
+    /// artifact doesn't need to be read and hashed again. The bytes are used
+    /// as the hash directly, and their length is fixed by the hash algorithm,
+    /// so this can't fail.
+    ///
+    /// # Warning
+    ///
+    /// This bypasses the normal construction of an [`ArtifactId`], and
+    /// nothing checks where the bytes came from. Callers are responsible for
+    /// making sure they're the hash of the `gitoid` header and the
+    /// newline-normalized content, as [`ArtifactId::id_bytes`] computes it.
+    /// A plain hash of the content, like the output of `sha256sum`, gives an
+    /// [`ArtifactId`] which won't match the artifact's real one.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use omnibor::ArtifactId;
+    /// # use omnibor::hashes::Sha256;
+    /// let id: ArtifactId<Sha256> = ArtifactId::id_str("hello, world");
+    /// let cached: [u8; 32] = id.as_bytes().try_into().unwrap();
+    /// assert_eq!(ArtifactId::<Sha256>::from_raw_hash(cached), id);
+    /// ```
+    pub fn from_raw_hash(bytes: H::RawHash) -> ArtifactId<H> {
+        // PANIC SAFETY: `RawHash` is always the length of the hash.
+        ArtifactId::try_from(bytes.as_ref()).unwrap()
+    }
+
+    /// Construct an [`ArtifactId`] from the raw bytes of a hash, one at a time.
+    ///
+    /// This is for contexts which produce the hash bytes as an iterator, like
+    /// low-level deserializers. As with [`TryFrom<&[u8]>`](TryFrom), the bytes
+    /// are used as the hash directly, not hashed themselves. Fails with
+    /// [`Error::WrongByteSliceLength`] if the iterator produces more or fewer
+    /// bytes than the hash length.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use omnibor::ArtifactId;
+    /// # use omnibor::hashes::Sha256;
+    /// let id: ArtifactId<Sha256> = ArtifactId::id_str("hello, world");
+    /// let collected = ArtifactId::try_collect(id.as_bytes().iter().copied()).unwrap();
+    /// assert_eq!(collected, id);
+    /// ```
+    pub fn try_collect<I: IntoIterator<Item = u8>>(iter: I) -> Result<ArtifactId<H>> {
+        let bytes = iter.into_iter().collect::<Vec<_>>();
+        ArtifactId::try_from(&bytes[..])
+    }
+
+    #[cfg(feature = "std")]
     /// Try to construct an [`ArtifactId`] from a filesystem-safe representation.
     pub fn try_from_safe_name(s: &str) -> Result<ArtifactId<H>> {
         ArtifactId::from_str(&s.replace('_', ":"))
     }
 
+    #[cfg(feature = "std")]
     /// Get the [`Url`] representation of the [`ArtifactId`].
     ///
     /// This returns a `gitoid`-scheme URL for the [`ArtifactId`].
@@ -329,6 +821,7 @@ impl<H: SupportedHash> ArtifactId<H> {
         self.gitoid.url()
     }
 
+    #[cfg(feature = "std")]
     /// Get a filesystem-safe representation of the [`ArtifactId`].
     ///
     /// This is a conservative method that tries to use _only_ characters
@@ -360,6 +853,27 @@ impl<H: SupportedHash> ArtifactId<H> {
         self.gitoid.as_bytes()
     }
 
+    #[cfg(feature = "subtle")]
+    /// Check if two [`ArtifactId`]s are equal, in constant time.
+    ///
+    /// The regular `==` operator is __not__ constant-time, and may leak how
+    /// much of two hashes match through timing. Use this instead when
+    /// comparing against an [`ArtifactId`] received over a network.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use omnibor::ArtifactId;
+    /// # use omnibor::hashes::Sha256;
+    /// let id: ArtifactId<Sha256> = ArtifactId::id_str("hello, world");
+    /// let other: ArtifactId<Sha256> = ArtifactId::id_str("hello, world");
+    /// assert!(id.eq_constant_time(&other));
+    /// ```
+    pub fn eq_constant_time(&self, other: &ArtifactId<H>) -> bool {
+        self.as_bytes().ct_eq(other.as_bytes()).into()
+    }
+
+    #[cfg(feature = "std")]
     /// Get the bytes of the [`ArtifactId`] hash as a hexadecimal string.
     ///
     /// This returns a [`String`] rather than [`str`] because the string must be
@@ -428,8 +942,338 @@ impl<H: SupportedHash> ArtifactId<H> {
     pub fn hash_len(&self) -> usize {
         self.gitoid.hash_len()
     }
+
+    /// Get the number of bits which differ between the hashes of two [`ArtifactId`]s.
+    ///
+    /// # Warning
+    ///
+    /// This is __not__ a similarity metric for the artifacts themselves, and
+    /// must not be used for security purposes. Hashes of near-identical
+    /// artifacts differ in about half their bits, the same as hashes of
+    /// unrelated ones.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use omnibor::ArtifactId;
+    /// # use omnibor::hashes::Sha256;
+    /// let id: ArtifactId<Sha256> = ArtifactId::id_str("hello, world");
+    /// assert_eq!(id.hamming_distance(&id), 0);
+    /// ```
+    pub fn hamming_distance(&self, other: &ArtifactId<H>) -> u32 {
+        similarity::hamming_distance(self.as_bytes(), other.as_bytes())
+    }
+
+    /// Get the number of bytes which differ between the hashes of two [`ArtifactId`]s.
+    ///
+    /// # Warning
+    ///
+    /// Like [`ArtifactId::hamming_distance`], this is __not__ a similarity
+    /// metric for the artifacts themselves, and must not be used for
+    /// security purposes.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use omnibor::ArtifactId;
+    /// # use omnibor::hashes::Sha256;
+    /// let id: ArtifactId<Sha256> = ArtifactId::id_str("hello, world");
+    /// assert_eq!(id.bytes_differ(&id), 0);
+    /// ```
+    pub fn bytes_differ(&self, other: &ArtifactId<H>) -> u32 {
+        similarity::bytes_differ(self.as_bytes(), other.as_bytes())
+    }
+
+    #[cfg(feature = "nix-compat")]
+    /// Encode the hash of the [`ArtifactId`] in Nix's base32 encoding.
+    ///
+    /// This is the encoding Nix uses for hashes in store paths and
+    /// fixed-output derivations, so it can be used to match an
+    /// [`ArtifactId`] against a hash Nix reports. Note that an
+    /// [`ArtifactId`] hashes content with a `gitoid` header, so it won't
+    /// match Nix's hash of the same file.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use omnibor::ArtifactId;
+    /// # use omnibor::hashes::Sha256;
+    /// let id: ArtifactId<Sha256> = ArtifactId::id_str("hello, world");
+    /// println!("Artifact ID as a Nix hash: {}", id.to_nix_hash());
+    /// ```
+    pub fn to_nix_hash(&self) -> String {
+        nix::encode(self.as_bytes())
+    }
+
+    #[cfg(feature = "nix-compat")]
+    /// Construct an [`ArtifactId`] from a hash in Nix's base32 encoding.
+    ///
+    /// Fails if the string isn't valid Nix base32, or doesn't decode to
+    /// the hash length of `H`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use omnibor::ArtifactId;
+    /// # use omnibor::hashes::Sha256;
+    /// let id: ArtifactId<Sha256> = ArtifactId::id_str("hello, world");
+    /// let nix_hash = id.to_nix_hash();
+    /// assert_eq!(ArtifactId::from_nix_hash(&nix_hash).unwrap(), id);
+    /// ```
+    pub fn from_nix_hash(s: &str) -> Result<ArtifactId<H>> {
+        let bytes = nix::decode(s).ok_or_else(|| Error::InvalidNixHash(String::from(s)))?;
+        ArtifactId::try_from(&bytes[..])
+    }
+
+    #[cfg(feature = "serde")]
+    /// Serialize the [`ArtifactId`] as the raw bytes of its hash.
+    ///
+    /// The regular [`Serialize`] impl writes the `gitoid` URL string. This
+    /// writes just the hash bytes instead, which is more compact in binary
+    /// formats like CBOR. It can be used with `#[serde(serialize_with)]`,
+    /// or through [`ArtifactIdBytes`](crate::ArtifactIdBytes).
+    pub fn serialize_as_bytes<S>(&self, serializer: S) -> StdResult<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_bytes(self.as_bytes())
+    }
+
+    #[cfg(feature = "serde")]
+    /// Deserialize an [`ArtifactId`] from the raw bytes of its hash.
+    ///
+    /// This is the counterpart to [`ArtifactId::serialize_as_bytes`], and can
+    /// be used with `#[serde(deserialize_with)]`. Both byte strings and
+    /// sequences of bytes are accepted, since some formats write bytes as
+    /// sequences. Fails if the length doesn't match the hash length of `H`.
+    pub fn deserialize_from_bytes<'de, D>(deserializer: D) -> StdResult<ArtifactId<H>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_bytes(ArtifactIdBytesVisitor(PhantomData))
+    }
+
+    #[cfg(feature = "cbor")]
+    /// Encode the [`ArtifactId`] as CBOR, tagged as an [`ArtifactId`].
+    ///
+    /// The value is the `gitoid` URL as a text string, wrapped in the
+    /// [`ARTIFACT_ID_CBOR_TAG`](crate::ARTIFACT_ID_CBOR_TAG) tag. This is the
+    /// form to use in CBOR-based attestation formats like COSE and RATS
+    /// Evidence.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use omnibor::ArtifactId;
+    /// # use omnibor::hashes::Sha256;
+    /// let id: ArtifactId<Sha256> = ArtifactId::id_str("hello, world");
+    /// let cbor = id.to_cbor_tagged();
+    /// assert_eq!(ArtifactId::from_cbor_tagged(&cbor).unwrap(), id);
+    /// ```
+    pub fn to_cbor_tagged(&self) -> Vec<u8> {
+        cbor::encode(self.url().to_string())
+    }
+
+    #[cfg(feature = "cbor")]
+    /// Decode an [`ArtifactId`] from CBOR written by [`ArtifactId::to_cbor_tagged`].
+    ///
+    /// Fails if the bytes aren't CBOR, the value isn't a text string tagged
+    /// with [`ARTIFACT_ID_CBOR_TAG`](crate::ARTIFACT_ID_CBOR_TAG), or the
+    /// string isn't a `gitoid` URL for the hash algorithm `H`.
+    pub fn from_cbor_tagged(bytes: &[u8]) -> Result<ArtifactId<H>> {
+        let url = cbor::decode(bytes).map_err(Error::InvalidCborTaggedArtifactId)?;
+        ArtifactId::from_str(&url)
+    }
+
+    #[cfg(feature = "uuid")]
+    /// Convert the [`ArtifactId`] to a [`Uuid`] made of the first 16 bytes of the hash.
+    ///
+    /// # Warning
+    ///
+    /// This is __not__ collision-safe. It discards half of the hash, so two
+    /// different artifacts may produce the same [`Uuid`], and the original
+    /// [`ArtifactId`] can't be recovered from it. Only use this where a
+    /// [`Uuid`] is required as a key and the full [`ArtifactId`] is stored
+    /// alongside it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use omnibor::ArtifactId;
+    /// # use omnibor::hashes::Sha256;
+    /// let id: ArtifactId<Sha256> = ArtifactId::id_str("hello, world");
+    /// println!("Artifact ID as a lossy UUID: {}", id.to_uuid_lossy());
+    /// ```
+    pub fn to_uuid_lossy(&self) -> Uuid {
+        Uuid::from_bytes(self.uuid_bytes(|first, _| first))
+    }
+
+    #[cfg(feature = "uuid")]
+    /// Convert the [`ArtifactId`] to a version 8 (custom) [`Uuid`] derived from the full hash.
+    ///
+    /// A [`Uuid`] only holds 128 bits, so the 256-bit hash can't be encoded
+    /// in full. Instead, the two halves of the hash are XOR-ed together so
+    /// every byte of the hash contributes to the result, and the version and
+    /// variant bits are then set to mark it as a UUIDv8.
+    ///
+    /// # Warning
+    ///
+    /// Like [`ArtifactId::to_uuid_lossy`], this is __not__ collision-safe and
+    /// can't be converted back to an [`ArtifactId`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use omnibor::ArtifactId;
+    /// # use omnibor::hashes::Sha256;
+    /// let id: ArtifactId<Sha256> = ArtifactId::id_str("hello, world");
+    /// assert_eq!(id.to_uuid_v8().get_version_num(), 8);
+    /// ```
+    pub fn to_uuid_v8(&self) -> Uuid {
+        Uuid::new_v8(self.uuid_bytes(|first, second| first ^ second))
+    }
+
+    #[cfg(feature = "uuid")]
+    /// Combine the two 16-byte halves of the hash into UUID bytes.
+    ///
+    /// Hashes shorter than 32 bytes are treated as if padded with zeroes.
+    fn uuid_bytes(&self, combine: impl Fn(u8, u8) -> u8) -> [u8; 16] {
+        let hash = self.as_bytes();
+        let byte_at = |index: usize| hash.get(index).copied().unwrap_or(0);
+        core::array::from_fn(|index| combine(byte_at(index), byte_at(index + 16)))
+    }
+}
+
+#[cfg(feature = "raw-hashing")]
+impl ArtifactId<Sha256> {
+    /// Construct an [`ArtifactId`] from the output of a [`sha2::Sha256`] hasher.
+    ///
+    /// This reuses a SHA-256 hash already computed for some other purpose.
+    ///
+    /// # Warning
+    ///
+    /// This does __not__ produce a spec-compliant [`ArtifactId`]. The hash
+    /// is taken as-is, so it's missing the `gitoid` header with the content
+    /// length, and won't match the [`ArtifactId`] of the same content. Only
+    /// use it for interoperability with systems which key on the plain
+    /// SHA-256 hash.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use omnibor::ArtifactId;
+    /// # use omnibor::hashes::Sha256;
+    /// use sha2::Digest as _;
+    ///
+    /// let output = sha2::Sha256::digest(b"hello, world");
+    /// let id = ArtifactId::from_sha256_digest_output(output);
+    /// assert_eq!(id.as_bytes(), output.as_slice());
+    /// assert_ne!(id, ArtifactId::<Sha256>::id_str("hello, world"));
+    /// ```
+    pub fn from_sha256_digest_output(output: sha2::digest::Output<sha2::Sha256>) -> Self {
+        let bytes: [u8; 32] = output.into();
+        ArtifactId::from(bytes)
+    }
+}
+
+#[cfg(feature = "std")]
+impl ArtifactId<Sha256> {
+    /// Parse a line of `sha256sum` output into an [`ArtifactId`] and a path.
+    ///
+    /// Lines look like `<hash>  <path>`, or `<hash> *<path>` when written
+    /// with `sha256sum --binary`. Lines starting with `\`, which
+    /// `sha256sum` writes for paths containing a backslash or newline, have
+    /// those characters unescaped.
+    ///
+    /// # Warning
+    ///
+    /// `sha256sum` hashes a file's content without the `gitoid` header, so
+    /// the [`ArtifactId`] is built from the raw hash and will __not__ match
+    /// the [`ArtifactId`] of the file itself. It's only useful for carrying
+    /// the checksum alongside other [`ArtifactId`]s.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use omnibor::ArtifactId;
+    /// # use std::path::Path;
+    /// let line = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855  empty.txt";
+    /// let (id, path) = ArtifactId::from_sha256sum_line(line).unwrap();
+    /// assert_eq!(path, Path::new("empty.txt"));
+    /// ```
+    pub fn from_sha256sum_line(line: &str) -> Result<(ArtifactId<Sha256>, PathBuf)> {
+        let invalid = || Error::InvalidSha256SumLine(line.to_owned());
+
+        let trimmed = line.trim_end_matches(['\n', '\r']);
+        let (escaped, rest) = match trimmed.strip_prefix('\\') {
+            Some(rest) => (true, rest),
+            None => (false, trimmed),
+        };
+
+        let (hash, path) = rest.split_once(' ').ok_or_else(invalid)?;
+        let path = path
+            .strip_prefix(' ')
+            .or_else(|| path.strip_prefix('*'))
+            .ok_or_else(invalid)?;
+
+        if hash.len() != 64 || hash.bytes().all(|b| b.is_ascii_hexdigit()).not() || path.is_empty()
+        {
+            return Err(invalid());
+        }
+
+        let path = if escaped {
+            unescape_sha256sum_path(path).ok_or_else(invalid)?
+        } else {
+            path.to_owned()
+        };
+
+        let aid = ArtifactId::from_str(&format!("gitoid:blob:sha256:{}", hash))?;
+        Ok((aid, PathBuf::from(path)))
+    }
+}
+
+/// Undo the escaping `sha256sum` applies to paths with `\\` or `\n` in them.
+#[cfg(feature = "std")]
+fn unescape_sha256sum_path(path: &str) -> Option<String> {
+    let mut unescaped = String::with_capacity(path.len());
+    let mut chars = path.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+
+        match chars.next()? {
+            '\\' => unescaped.push('\\'),
+            'n' => unescaped.push('\n'),
+            _ => return None,
+        }
+    }
+
+    Some(unescaped)
+}
+
+/// Get how many bytes to read from a reader expected to hold `expected_length`.
+///
+/// This is one more than expected, so extra content is caught by
+/// [`check_read_length`] without reading all of it.
+#[cfg(feature = "std")]
+fn read_limit(expected_length: usize) -> u64 {
+    (expected_length as u64).saturating_add(1)
+}
+
+/// Check that the number of bytes read matches what was expected.
+#[cfg(feature = "std")]
+pub(crate) fn check_read_length(expected: usize, observed: usize) -> Result<()> {
+    if expected != observed {
+        return Err(GitOidError::UnexpectedReadLength { expected, observed }.into());
+    }
+
+    Ok(())
 }
 
+#[cfg(feature = "std")]
 impl<H: SupportedHash> FromStr for ArtifactId<H> {
     type Err = Error;
 
@@ -447,6 +1291,12 @@ impl<H: SupportedHash> Clone for ArtifactId<H> {
 
 impl<H: SupportedHash> Copy for ArtifactId<H> {}
 
+/// Compare two [`ArtifactId`]s.
+///
+/// This is __not__ constant-time, so it may leak how much of two hashes
+/// match through timing. Don't use it to compare against an [`ArtifactId`]
+/// received over a network; use `ArtifactId::eq_constant_time` with the
+/// `subtle` feature instead.
 impl<H: SupportedHash> PartialEq<ArtifactId<H>> for ArtifactId<H> {
     fn eq(&self, other: &Self) -> bool {
         self.gitoid == other.gitoid
@@ -455,14 +1305,58 @@ impl<H: SupportedHash> PartialEq<ArtifactId<H>> for ArtifactId<H> {
 
 impl<H: SupportedHash> Eq for ArtifactId<H> {}
 
+/// Compare an [`ArtifactId`] to its `gitoid` URL string.
+///
+/// This formats the [`ArtifactId`] and compares the strings, which is
+/// convenient in tests. It is __not__ constant-time, so don't use it for
+/// security-critical comparisons.
+#[cfg(feature = "std")]
+impl<H: SupportedHash> PartialEq<str> for ArtifactId<H> {
+    fn eq(&self, other: &str) -> bool {
+        self.to_string().as_str() == other
+    }
+}
+
+#[cfg(feature = "std")]
+impl<H: SupportedHash> PartialEq<&str> for ArtifactId<H> {
+    fn eq(&self, other: &&str) -> bool {
+        self == *other
+    }
+}
+
+#[cfg(feature = "std")]
+impl<H: SupportedHash> PartialEq<ArtifactId<H>> for str {
+    fn eq(&self, other: &ArtifactId<H>) -> bool {
+        other == self
+    }
+}
+
+#[cfg(feature = "std")]
+impl<H: SupportedHash> PartialEq<ArtifactId<H>> for &str {
+    fn eq(&self, other: &ArtifactId<H>) -> bool {
+        other == *self
+    }
+}
+
 impl<H: SupportedHash> PartialOrd<ArtifactId<H>> for ArtifactId<H> {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
     }
 }
 
+/// Order [`ArtifactId`]s by their hash bytes.
+///
+/// Both [`ArtifactId`]s always use the same hash algorithm, since it's
+/// fixed by `H`. Debug builds assert this, to catch an [`ArtifactId`]
+/// corrupted through unsafe code, like a bad write over FFI.
 impl<H: SupportedHash> Ord for ArtifactId<H> {
     fn cmp(&self, other: &Self) -> Ordering {
+        debug_assert_eq!(
+            self.hash_algorithm(),
+            other.hash_algorithm(),
+            "compared Artifact IDs with different hash algorithms"
+        );
+
         self.gitoid.cmp(&other.gitoid)
     }
 }
@@ -476,6 +1370,45 @@ impl<H: SupportedHash> Hash for ArtifactId<H> {
     }
 }
 
+/// Overwrite the hash bytes with zeros.
+///
+/// The hash of an artifact isn't secret, so this is defense in depth, for
+/// when an [`ArtifactId`] is used as a key alongside secret material. Since
+/// [`ArtifactId`] is `Copy`, it can't zero itself on drop; wrap it in
+/// [`zeroize::Zeroizing`] to do that. Copies made earlier aren't zeroed.
+#[cfg(feature = "zeroize")]
+impl<H: SupportedHash> Zeroize for ArtifactId<H> {
+    fn zeroize(&mut self) {
+        let zeros = vec![0; self.hash_len()];
+
+        // PANIC SAFETY: The zeros are the hash length of `H`.
+        let zeroed = ArtifactId::try_from(&zeros[..]).unwrap();
+
+        // SAFETY: `self` is a valid, aligned, and exclusive reference. The
+        //         volatile write and fence keep the compiler from eliding the
+        //         write to a value that's never read again.
+        unsafe { ptr::write_volatile(self, zeroed) };
+        compiler_fence(AtomicOrdering::SeqCst);
+    }
+}
+
+/// Gets the raw bytes of the hash, like [`ArtifactId::as_bytes`].
+impl<H: SupportedHash> AsRef<[u8]> for ArtifactId<H> {
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+/// Borrows the raw bytes of the hash, so collections keyed by [`ArtifactId`]
+/// can be looked up by hash bytes.
+///
+/// [`ArtifactId`]s compare, order, and hash the same way as their bytes.
+impl<H: SupportedHash> Borrow<[u8]> for ArtifactId<H> {
+    fn borrow(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
 impl<H: SupportedHash> Debug for ArtifactId<H> {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
         f.debug_struct("ArtifactId")
@@ -484,6 +1417,7 @@ impl<H: SupportedHash> Debug for ArtifactId<H> {
     }
 }
 
+#[cfg(feature = "std")]
 impl<H: SupportedHash> Display for ArtifactId<H> {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
         write!(f, "{}", self.gitoid)
@@ -496,6 +1430,48 @@ impl<H: SupportedHash> From<GitOid<H::HashAlgorithm, Blob>> for ArtifactId<H> {
     }
 }
 
+impl<H: SupportedHash> From<ArtifactId<H>> for GitOid<H::HashAlgorithm, Blob> {
+    fn from(artifact_id: ArtifactId<H>) -> Self {
+        artifact_id.gitoid
+    }
+}
+
+impl<'b, H: SupportedHash> TryFrom<&'b [u8]> for ArtifactId<H> {
+    type Error = Error;
+
+    /// Construct an [`ArtifactId`] from the raw bytes of a hash.
+    ///
+    /// The bytes are used as the hash directly, not hashed themselves. Fails
+    /// if the slice length doesn't match the hash length.
+    fn try_from(bytes: &'b [u8]) -> Result<Self> {
+        GitOid::try_from_hash_bytes(bytes)
+            .map(ArtifactId::from_gitoid)
+            .map_err(|e| match e {
+                GitOidError::UnexpectedHashLength { expected, observed } => {
+                    Error::WrongByteSliceLength {
+                        expected,
+                        got: observed,
+                    }
+                }
+                e => Error::from(e),
+            })
+    }
+}
+
+impl From<[u8; 32]> for ArtifactId<Sha256> {
+    fn from(bytes: [u8; 32]) -> Self {
+        ArtifactId::from(&bytes)
+    }
+}
+
+impl From<&[u8; 32]> for ArtifactId<Sha256> {
+    fn from(bytes: &[u8; 32]) -> Self {
+        // PANIC SAFETY: A SHA-256 hash is always 32 bytes long.
+        ArtifactId::try_from(&bytes[..]).unwrap()
+    }
+}
+
+#[cfg(feature = "std")]
 impl<'r, H: SupportedHash> TryFrom<&'r str> for ArtifactId<H> {
     type Error = Error;
 
@@ -504,6 +1480,7 @@ impl<'r, H: SupportedHash> TryFrom<&'r str> for ArtifactId<H> {
     }
 }
 
+#[cfg(feature = "std")]
 impl<H: SupportedHash> TryFrom<Url> for ArtifactId<H> {
     type Error = Error;
 
@@ -533,3 +1510,36 @@ impl<'de, H: SupportedHash> Deserialize<'de> for ArtifactId<H> {
         Ok(ArtifactId::from_gitoid(gitoid))
     }
 }
+
+/// Visits the raw bytes of an [`ArtifactId`] hash.
+#[cfg(feature = "serde")]
+struct ArtifactIdBytesVisitor<H: SupportedHash>(PhantomData<H>);
+
+#[cfg(feature = "serde")]
+impl<'de, H: SupportedHash> Visitor<'de> for ArtifactIdBytesVisitor<H> {
+    type Value = ArtifactId<H>;
+
+    fn expecting(&self, formatter: &mut Formatter<'_>) -> FmtResult {
+        write!(formatter, "the bytes of an artifact ID hash")
+    }
+
+    fn visit_bytes<E>(self, bytes: &[u8]) -> StdResult<Self::Value, E>
+    where
+        E: DeError,
+    {
+        ArtifactId::try_from(bytes).map_err(E::custom)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> StdResult<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+
+        self.visit_bytes(&bytes)
+    }
+}
@@ -0,0 +1,89 @@
+use crate::hashes::SupportedHash;
+use crate::ArtifactId;
+use core::fmt::Debug;
+use core::fmt::Formatter;
+use core::fmt::Result as FmtResult;
+use core::hash::Hash;
+use core::hash::Hasher;
+use core::result::Result as StdResult;
+use serde::Deserialize;
+use serde::Deserializer;
+use serde::Serialize;
+use serde::Serializer;
+
+/// An [`ArtifactId`] which always serializes as the raw bytes of its hash.
+///
+/// [`ArtifactId`] serializes as a `gitoid` URL string, which is readable in
+/// text formats like JSON but wasteful in binary formats like CBOR. Wrap it
+/// in [`ArtifactIdBytes`] to serialize just the hash bytes instead. This
+/// uses [`ArtifactId::serialize_as_bytes`] and
+/// [`ArtifactId::deserialize_from_bytes`].
+pub struct ArtifactIdBytes<H: SupportedHash>(pub ArtifactId<H>);
+
+impl<H: SupportedHash> ArtifactIdBytes<H> {
+    /// Get the wrapped [`ArtifactId`].
+    pub fn artifact_id(&self) -> ArtifactId<H> {
+        self.0
+    }
+}
+
+impl<H: SupportedHash> From<ArtifactId<H>> for ArtifactIdBytes<H> {
+    fn from(artifact_id: ArtifactId<H>) -> Self {
+        ArtifactIdBytes(artifact_id)
+    }
+}
+
+impl<H: SupportedHash> From<ArtifactIdBytes<H>> for ArtifactId<H> {
+    fn from(bytes: ArtifactIdBytes<H>) -> Self {
+        bytes.0
+    }
+}
+
+impl<H: SupportedHash> Clone for ArtifactIdBytes<H> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<H: SupportedHash> Copy for ArtifactIdBytes<H> {}
+
+impl<H: SupportedHash> PartialEq for ArtifactIdBytes<H> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<H: SupportedHash> Eq for ArtifactIdBytes<H> {}
+
+impl<H: SupportedHash> Hash for ArtifactIdBytes<H> {
+    fn hash<H2>(&self, state: &mut H2)
+    where
+        H2: Hasher,
+    {
+        self.0.hash(state);
+    }
+}
+
+impl<H: SupportedHash> Debug for ArtifactIdBytes<H> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.debug_tuple("ArtifactIdBytes").field(&self.0).finish()
+    }
+}
+
+impl<H: SupportedHash> Serialize for ArtifactIdBytes<H> {
+    fn serialize<S>(&self, serializer: S) -> StdResult<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.0.serialize_as_bytes(serializer)
+    }
+}
+
+impl<'de, H: SupportedHash> Deserialize<'de> for ArtifactIdBytes<H> {
+    fn deserialize<D>(deserializer: D) -> StdResult<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        ArtifactId::deserialize_from_bytes(deserializer).map(ArtifactIdBytes)
+    }
+}
@@ -0,0 +1,139 @@
+use crate::artifact_id::check_read_length;
+use crate::hashes::SupportedHash;
+use crate::newline::push_normalized;
+use crate::ArtifactId;
+use crate::Result;
+use gitoid::GitOid;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+use std::io::Result as IoResult;
+use std::io::Write;
+use std::marker::PhantomData;
+
+/// Compute an [`ArtifactId`] from content written to it in pieces.
+///
+/// This is for pipelines which push content along rather than offering a
+/// reader, like copying a download to disk while identifying it. Write the
+/// content with [`Write`], then call [`ArtifactIdHasher::finalize`] with the
+/// total length to get the [`ArtifactId`].
+///
+/// The `gitoid` header hashed before the content holds the length of the
+/// content _after_ newline normalization, which isn't known until all of it
+/// has been written. So, like [`ArtifactId::id_reader_with_length`], the
+/// normalized content is held in memory until the hasher is finalized.
+///
+/// # Example
+///
+/// ```rust
+/// # use omnibor::ArtifactId;
+/// # use omnibor::ArtifactIdHasher;
+/// # use omnibor::hashes::Sha256;
+/// use std::io::Write as _;
+///
+/// let mut hasher = ArtifactIdHasher::<Sha256>::new();
+/// hasher.write_all(b"hello, ").unwrap();
+/// hasher.write_all(b"world").unwrap();
+/// let id = hasher.finalize(12).unwrap();
+/// assert_eq!(id, ArtifactId::<Sha256>::id_str("hello, world"));
+/// ```
+pub struct ArtifactIdHasher<H: SupportedHash> {
+    /// The normalized content written so far.
+    content: Vec<u8>,
+
+    /// Whether the last byte written was a `\r` we haven't pushed yet.
+    pending_cr: bool,
+
+    /// The number of bytes written, before normalization.
+    written: usize,
+
+    _hash: PhantomData<H>,
+}
+
+impl<H: SupportedHash> ArtifactIdHasher<H> {
+    /// Construct a new hasher with no content written.
+    pub fn new() -> Self {
+        ArtifactIdHasher {
+            content: Vec::new(),
+            pending_cr: false,
+            written: 0,
+            _hash: PhantomData,
+        }
+    }
+
+    /// Get the [`ArtifactId`] of the content written.
+    ///
+    /// `total_length` is the length of the content _before_ newline
+    /// normalization. Returns an [`Error`](crate::Error) if it doesn't match
+    /// the number of bytes written, rather than produce an [`ArtifactId`] for
+    /// partial content.
+    pub fn finalize(mut self, total_length: usize) -> Result<ArtifactId<H>> {
+        check_read_length(total_length, self.written)?;
+
+        if self.pending_cr {
+            self.content.push(b'\r');
+        }
+
+        // The content is already normalized, so hash it as-is.
+        Ok(ArtifactId::from_gitoid(GitOid::id_bytes(self.content)))
+    }
+}
+
+impl<H: SupportedHash> Write for ArtifactIdHasher<H> {
+    fn write(&mut self, buf: &[u8]) -> IoResult<usize> {
+        self.content.reserve(buf.len());
+
+        for &byte in buf {
+            push_normalized(&mut self.content, &mut self.pending_cr, byte);
+        }
+
+        self.written += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> IoResult<()> {
+        Ok(())
+    }
+}
+
+impl<H: SupportedHash> Default for ArtifactIdHasher<H> {
+    fn default() -> Self {
+        ArtifactIdHasher::new()
+    }
+}
+
+impl<H: SupportedHash> Debug for ArtifactIdHasher<H> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.debug_struct("ArtifactIdHasher")
+            .field("written", &self.written)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hashes::Sha256;
+
+    #[test]
+    fn matches_id_bytes_across_split_crlf() {
+        let content = b"one\r\ntwo\r\n\r\r\nthree\r";
+        let mut hasher = ArtifactIdHasher::<Sha256>::new();
+
+        // Split inside the first CRLF, so normalization has to carry over.
+        hasher.write_all(&content[..4]).unwrap();
+        hasher.write_all(&content[4..]).unwrap();
+
+        assert_eq!(
+            hasher.finalize(content.len()).unwrap(),
+            ArtifactId::<Sha256>::id_bytes(content)
+        );
+    }
+
+    #[test]
+    fn wrong_length_fails() {
+        let mut hasher = ArtifactIdHasher::<Sha256>::new();
+        hasher.write_all(b"hello, world").unwrap();
+        assert!(hasher.finalize(11).is_err());
+    }
+}
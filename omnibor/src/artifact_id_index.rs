@@ -0,0 +1,154 @@
+use crate::hashes::SupportedHash;
+use crate::ArtifactId;
+#[cfg(feature = "serde")]
+use serde::Deserialize;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+/// An index of [`ArtifactId`]s, with details for finding them by path or time.
+///
+/// This is a building block for a local database of artifacts, for example
+/// to find what a build produced at a given path, or during a given window
+/// of time. Entries are ordered by [`ArtifactId`].
+///
+/// With the `serde` feature, the index can be serialized to persist it. It
+/// serializes as a map from `gitoid` URLs to entries.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = "", transparent))]
+pub struct ArtifactIdIndex<H: SupportedHash> {
+    entries: BTreeMap<ArtifactId<H>, IndexEntry>,
+}
+
+/// The details recorded for an [`ArtifactId`] in an [`ArtifactIdIndex`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct IndexEntry {
+    /// The path the artifact was found at, if known.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub path: Option<PathBuf>,
+
+    /// When the artifact was built, if known.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub build_time: Option<SystemTime>,
+
+    /// Any other details about the artifact.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub metadata: HashMap<String, String>,
+}
+
+impl<H: SupportedHash> ArtifactIdIndex<H> {
+    /// Construct a new, empty index.
+    pub fn new() -> Self {
+        ArtifactIdIndex {
+            entries: BTreeMap::new(),
+        }
+    }
+
+    /// Add an entry for an [`ArtifactId`], returning any entry it replaces.
+    pub fn insert(&mut self, aid: ArtifactId<H>, entry: IndexEntry) -> Option<IndexEntry> {
+        self.entries.insert(aid, entry)
+    }
+
+    /// Get the entry for an [`ArtifactId`], if it's in the index.
+    pub fn get(&self, aid: ArtifactId<H>) -> Option<&IndexEntry> {
+        self.entries.get(&aid)
+    }
+
+    /// Get the entry for an [`ArtifactId`] for in-place changes.
+    pub fn entry(&mut self, aid: ArtifactId<H>) -> Entry<'_, ArtifactId<H>, IndexEntry> {
+        self.entries.entry(aid)
+    }
+
+    /// Remove the entry for an [`ArtifactId`], returning it if it was present.
+    pub fn remove(&mut self, aid: ArtifactId<H>) -> Option<IndexEntry> {
+        self.entries.remove(&aid)
+    }
+
+    /// Find the [`ArtifactId`]s of artifacts found at the given path.
+    ///
+    /// Paths are compared as given, without being canonicalized. Several
+    /// artifacts may share a path, for example from successive builds.
+    pub fn find_by_path(&self, path: &Path) -> Vec<ArtifactId<H>> {
+        self.entries
+            .iter()
+            .filter(|(_, entry)| entry.path.as_deref() == Some(path))
+            .map(|(aid, _)| *aid)
+            .collect()
+    }
+
+    /// Find the [`ArtifactId`]s of artifacts built from `start` up to, but
+    /// not including, `end`.
+    ///
+    /// Artifacts without a build time are never included.
+    pub fn find_by_time_range(&self, start: SystemTime, end: SystemTime) -> Vec<ArtifactId<H>> {
+        self.entries
+            .iter()
+            .filter(|(_, entry)| {
+                entry
+                    .build_time
+                    .is_some_and(|time| start <= time && time < end)
+            })
+            .map(|(aid, _)| *aid)
+            .collect()
+    }
+
+    /// Iterate over the [`ArtifactId`]s and entries in the index, in order.
+    pub fn iter(&self) -> impl Iterator<Item = (ArtifactId<H>, &IndexEntry)> {
+        self.entries.iter().map(|(aid, entry)| (*aid, entry))
+    }
+
+    /// Get the number of [`ArtifactId`]s in the index.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check if the index is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl<H: SupportedHash> Default for ArtifactIdIndex<H> {
+    fn default() -> Self {
+        ArtifactIdIndex::new()
+    }
+}
+
+impl<H: SupportedHash> Clone for ArtifactIdIndex<H> {
+    fn clone(&self) -> Self {
+        ArtifactIdIndex {
+            entries: self.entries.clone(),
+        }
+    }
+}
+
+impl<H: SupportedHash> PartialEq for ArtifactIdIndex<H> {
+    fn eq(&self, other: &Self) -> bool {
+        self.entries == other.entries
+    }
+}
+
+impl<H: SupportedHash> Eq for ArtifactIdIndex<H> {}
+
+impl<H: SupportedHash> Debug for ArtifactIdIndex<H> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.debug_map().entries(self.entries.iter()).finish()
+    }
+}
+
+impl<H: SupportedHash> FromIterator<(ArtifactId<H>, IndexEntry)> for ArtifactIdIndex<H> {
+    fn from_iter<I: IntoIterator<Item = (ArtifactId<H>, IndexEntry)>>(iter: I) -> Self {
+        ArtifactIdIndex {
+            entries: iter.into_iter().collect(),
+        }
+    }
+}
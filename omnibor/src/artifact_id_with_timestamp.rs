@@ -0,0 +1,141 @@
+use crate::hashes::SupportedHash;
+use crate::ArtifactId;
+use crate::IntoArtifactId;
+use crate::Result;
+#[cfg(feature = "serde")]
+use serde::Deserialize;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+use std::fmt::Debug;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+/// An [`ArtifactId`] bound to the time it was produced.
+///
+/// This is intended for provenance records, which need to say not just what
+/// an artifact was but when it was seen. It displays as the `gitoid` URL
+/// followed by `@` and the Unix timestamp in seconds, like
+/// `gitoid:blob:sha256:...@1700000000`.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct ArtifactIdWithTimestamp<H: SupportedHash> {
+    /// The [`ArtifactId`] of the artifact.
+    pub artifact_id: ArtifactId<H>,
+
+    /// When the [`ArtifactId`] was produced.
+    pub timestamp: SystemTime,
+}
+
+impl<H: SupportedHash> ArtifactIdWithTimestamp<H> {
+    /// Construct an [`ArtifactId`], recording the current time.
+    ///
+    /// The timestamp is taken once the [`ArtifactId`] has been produced.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use omnibor::ArtifactIdWithTimestamp;
+    /// # use omnibor::hashes::Sha256;
+    /// # use std::path::Path;
+    /// let path = Path::new("test/data/hello_world.txt");
+    /// let stamped = ArtifactIdWithTimestamp::<Sha256>::now(path).unwrap();
+    /// println!("{}", stamped);
+    /// ```
+    pub fn now<I: IntoArtifactId<H>>(target: I) -> Result<Self> {
+        let artifact_id = target.into_artifact_id()?;
+
+        Ok(ArtifactIdWithTimestamp {
+            artifact_id,
+            timestamp: SystemTime::now(),
+        })
+    }
+
+    /// Check if the [`ArtifactId`] was produced no later than `deadline`.
+    pub fn verify_not_after(&self, deadline: SystemTime) -> bool {
+        self.timestamp <= deadline
+    }
+
+    /// Get the timestamp as whole seconds since the Unix epoch.
+    ///
+    /// Times before the epoch give negative values.
+    fn unix_timestamp(&self) -> i64 {
+        match self.timestamp.duration_since(UNIX_EPOCH) {
+            Ok(since) => since.as_secs() as i64,
+            Err(e) => -(e.duration().as_secs() as i64),
+        }
+    }
+}
+
+impl<H: SupportedHash> Display for ArtifactIdWithTimestamp<H> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{}@{}", self.artifact_id, self.unix_timestamp())
+    }
+}
+
+impl<H: SupportedHash> Debug for ArtifactIdWithTimestamp<H> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.debug_struct("ArtifactIdWithTimestamp")
+            .field("artifact_id", &self.artifact_id)
+            .field("timestamp", &self.timestamp)
+            .finish()
+    }
+}
+
+impl<H: SupportedHash> Clone for ArtifactIdWithTimestamp<H> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<H: SupportedHash> Copy for ArtifactIdWithTimestamp<H> {}
+
+impl<H: SupportedHash> PartialEq for ArtifactIdWithTimestamp<H> {
+    fn eq(&self, other: &Self) -> bool {
+        self.artifact_id == other.artifact_id && self.timestamp == other.timestamp
+    }
+}
+
+impl<H: SupportedHash> Eq for ArtifactIdWithTimestamp<H> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hashes::Sha256;
+    use std::time::Duration;
+
+    #[test]
+    fn displays_with_unix_timestamp() {
+        let artifact_id = ArtifactId::<Sha256>::id_str("hello world");
+        let stamped = ArtifactIdWithTimestamp {
+            artifact_id,
+            timestamp: UNIX_EPOCH + Duration::from_millis(1_700_000_000_500),
+        };
+
+        assert_eq!(stamped.to_string(), format!("{}@1700000000", artifact_id));
+    }
+
+    #[test]
+    fn now_is_not_after_later_deadline() {
+        let before = SystemTime::now();
+        let stamped = ArtifactIdWithTimestamp::<Sha256>::now(&b"hello world"[..]).unwrap();
+
+        assert_eq!(
+            stamped.artifact_id,
+            ArtifactId::<Sha256>::id_str("hello world")
+        );
+        assert!(stamped.verify_not_after(SystemTime::now()));
+        assert!(!stamped.verify_not_after(before - Duration::from_secs(1)));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let stamped = ArtifactIdWithTimestamp::<Sha256>::now(&b"hello world"[..]).unwrap();
+        let json = serde_json::to_string(&stamped).unwrap();
+        let parsed: ArtifactIdWithTimestamp<Sha256> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, stamped);
+    }
+}
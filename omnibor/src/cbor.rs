@@ -0,0 +1,77 @@
+//! CBOR encoding of [`ArtifactId`]s as tagged `gitoid` URLs.
+//!
+//! This backs [`ArtifactId::to_cbor_tagged`] and
+//! [`ArtifactId::from_cbor_tagged`]. The tagged form lets [`ArtifactId`]s
+//! appear in CBOR-based formats like COSE (RFC 9052) and RATS attestation
+//! Evidence, where a tag tells consumers how to interpret the value:
+//!
+//! ```text
+//! 1869442665("gitoid:blob:sha256:...")
+//! ```
+
+#[cfg(doc)]
+use crate::ArtifactId;
+use ciborium::value::Value;
+use std::result::Result as StdResult;
+
+/// The CBOR tag number wrapping [`ArtifactId`]s.
+///
+/// This is `"omni"` in ASCII, in the first-come, first-served range of the
+/// IANA CBOR tags registry. It isn't registered yet, so treat it as
+/// provisional; if a different number is assigned, this will change in a
+/// breaking release.
+pub const ARTIFACT_ID_CBOR_TAG: u64 = 0x6F6D_6E69;
+
+/// Encode a `gitoid` URL as a tagged CBOR text string.
+pub(crate) fn encode(url: String) -> Vec<u8> {
+    let value = Value::Tag(ARTIFACT_ID_CBOR_TAG, Box::new(Value::Text(url)));
+    let mut bytes = Vec::new();
+
+    // SAFETY: Writing to a `Vec` can't fail, and a tagged string is always
+    //         serializable.
+    ciborium::into_writer(&value, &mut bytes).unwrap();
+    bytes
+}
+
+/// Decode a `gitoid` URL from a tagged CBOR text string.
+pub(crate) fn decode(bytes: &[u8]) -> StdResult<String, &'static str> {
+    let value: Value = ciborium::from_reader(bytes).map_err(|_| "not valid CBOR")?;
+
+    match value {
+        Value::Tag(ARTIFACT_ID_CBOR_TAG, inner) => match *inner {
+            Value::Text(url) => Ok(url),
+            _ => Err("tagged value isn't a text string"),
+        },
+        Value::Tag(..) => Err("wrong CBOR tag"),
+        _ => Err("missing CBOR tag"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_tag_header() {
+        let bytes = encode(String::from("gitoid"));
+
+        // Major type 6 (tag) with a four-byte argument, then the tag number,
+        // then major type 3 (text) with a length of 6.
+        assert_eq!(&bytes[..6], &[0xDA, 0x6F, 0x6D, 0x6E, 0x69, 0x66]);
+        assert_eq!(&bytes[6..], b"gitoid");
+    }
+
+    #[test]
+    fn rejects_untagged_and_mistagged() {
+        let mut untagged = Vec::new();
+        ciborium::into_writer(&Value::Text(String::from("gitoid")), &mut untagged).unwrap();
+        assert_eq!(decode(&untagged), Err("missing CBOR tag"));
+
+        let mut mistagged = Vec::new();
+        let value = Value::Tag(32, Box::new(Value::Text(String::from("gitoid"))));
+        ciborium::into_writer(&value, &mut mistagged).unwrap();
+        assert_eq!(decode(&mistagged), Err("wrong CBOR tag"));
+
+        assert_eq!(decode(&[0xFF]), Err("not valid CBOR"));
+    }
+}
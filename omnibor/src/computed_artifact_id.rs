@@ -0,0 +1,283 @@
+use crate::hashes::SupportedHash;
+use crate::ArtifactId;
+use crate::IntoArtifactId;
+use crate::Result;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+use std::io;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::pin::Pin;
+use std::task::ready;
+use std::task::Context;
+use std::task::Poll;
+use std::time::Duration;
+use std::time::Instant;
+use tokio::io::AsyncRead;
+use tokio::io::AsyncSeek;
+use tokio::io::AsyncSeekExt as _;
+use tokio::io::ReadBuf;
+
+/// An [`ArtifactId`] along with statistics about how it was computed.
+///
+/// This is intended to help build system integrations profile how much
+/// time they spend producing [`ArtifactId`]s.
+pub struct ComputedArtifactId<H: SupportedHash> {
+    /// The computed [`ArtifactId`].
+    artifact_id: ArtifactId<H>,
+
+    /// The number of bytes hashed, if known.
+    bytes_processed: Option<u64>,
+
+    /// How long the computation took.
+    duration: Duration,
+}
+
+impl<H: SupportedHash> ComputedArtifactId<H> {
+    /// Get the computed [`ArtifactId`].
+    pub fn artifact_id(&self) -> ArtifactId<H> {
+        self.artifact_id
+    }
+
+    /// Get the number of bytes hashed to produce the [`ArtifactId`].
+    ///
+    /// This is `None` when the number of bytes could not be determined,
+    /// as is the case for [`ArtifactId::timed_new`].
+    pub fn bytes_processed(&self) -> Option<u64> {
+        self.bytes_processed
+    }
+
+    /// Get how long it took to compute the [`ArtifactId`].
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+}
+
+impl<H: SupportedHash> Debug for ComputedArtifactId<H> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.debug_struct("ComputedArtifactId")
+            .field("artifact_id", &self.artifact_id)
+            .field("bytes_processed", &self.bytes_processed)
+            .field("duration", &self.duration)
+            .finish()
+    }
+}
+
+impl<H: SupportedHash> Clone for ComputedArtifactId<H> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<H: SupportedHash> Copy for ComputedArtifactId<H> {}
+
+impl<H: SupportedHash> ArtifactId<H> {
+    /// Construct an [`ArtifactId`], recording how long it took.
+    ///
+    /// Because this accepts anything implementing [`IntoArtifactId`], the
+    /// number of bytes processed is not known and will be `None`. Use
+    /// [`ArtifactId::timed_id_reader`] or [`ArtifactId::timed_id_async_reader`]
+    /// to also record the number of bytes.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use omnibor::ArtifactId;
+    /// # use omnibor::hashes::Sha256;
+    /// # use std::path::Path;
+    /// let path = Path::new("test/data/hello_world.txt");
+    /// let computed = ArtifactId::<Sha256>::timed_new(path).unwrap();
+    /// println!("Artifact ID: {} ({:?})", computed.artifact_id(), computed.duration());
+    /// ```
+    pub fn timed_new<I: IntoArtifactId<H>>(target: I) -> Result<ComputedArtifactId<H>> {
+        let start = Instant::now();
+        let artifact_id = target.into_artifact_id()?;
+
+        Ok(ComputedArtifactId {
+            artifact_id,
+            bytes_processed: None,
+            duration: start.elapsed(),
+        })
+    }
+
+    /// Construct an [`ArtifactId`] from a synchronous reader, recording
+    /// how long it took and how many bytes were hashed.
+    ///
+    /// This has the same behavior as [`ArtifactId::id_reader`]. Only the
+    /// bytes from the reader's current position onward are counted.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use omnibor::ArtifactId;
+    /// # use omnibor::hashes::Sha256;
+    /// # use std::fs::File;
+    /// let file = File::open("test/data/hello_world.txt").unwrap();
+    /// let computed = ArtifactId::<Sha256>::timed_id_reader(&file).unwrap();
+    /// assert_eq!(computed.bytes_processed(), Some(11));
+    /// ```
+    pub fn timed_id_reader<R: Read + Seek>(mut reader: R) -> Result<ComputedArtifactId<H>> {
+        let start = Instant::now();
+        let mut reader = CountingReader::new(&mut reader)?;
+        let artifact_id = ArtifactId::id_reader(&mut reader)?;
+
+        Ok(ComputedArtifactId {
+            artifact_id,
+            bytes_processed: Some(reader.bytes_read()),
+            duration: start.elapsed(),
+        })
+    }
+
+    /// Construct an [`ArtifactId`] from an asynchronous reader, recording
+    /// how long it took and how many bytes were hashed.
+    ///
+    /// This has the same behavior as [`ArtifactId::id_async_reader`]. Only
+    /// the bytes from the reader's current position onward are counted.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use omnibor::ArtifactId;
+    /// # use omnibor::hashes::Sha256;
+    /// # use tokio::fs::File;
+    /// # tokio_test::block_on(async {
+    /// let mut file = File::open("test/data/hello_world.txt").await.unwrap();
+    /// let computed = ArtifactId::<Sha256>::timed_id_async_reader(&mut file).await.unwrap();
+    /// assert_eq!(computed.bytes_processed(), Some(11));
+    /// # })
+    /// ```
+    pub async fn timed_id_async_reader<R: AsyncRead + AsyncSeek + Unpin>(
+        mut reader: R,
+    ) -> Result<ComputedArtifactId<H>> {
+        let start = Instant::now();
+        let position = reader.stream_position().await?;
+        let mut reader = CountingReader::at(&mut reader, position);
+        let artifact_id = ArtifactId::id_async_reader(&mut reader).await?;
+
+        Ok(ComputedArtifactId {
+            artifact_id,
+            bytes_processed: Some(reader.bytes_read()),
+            duration: start.elapsed(),
+        })
+    }
+}
+
+/// A reader which counts the bytes read from it, from where it started.
+///
+/// Bytes read more than once, like when [`ArtifactId::id_reader`] rewinds
+/// for a second pass, are only counted once.
+struct CountingReader<R> {
+    /// The underlying reader.
+    inner: R,
+
+    /// The position the reader started at.
+    start: u64,
+
+    /// The current position of the reader.
+    position: u64,
+
+    /// The furthest position the reader has read up to.
+    furthest: u64,
+}
+
+impl<R> CountingReader<R> {
+    /// Wrap a reader which is at `position`.
+    fn at(inner: R, position: u64) -> Self {
+        CountingReader {
+            inner,
+            start: position,
+            position,
+            furthest: position,
+        }
+    }
+
+    /// Get the number of distinct bytes read since the reader started.
+    fn bytes_read(&self) -> u64 {
+        self.furthest - self.start
+    }
+
+    /// Record that `n` bytes were read.
+    fn advance(&mut self, n: usize) {
+        self.position += n as u64;
+        self.furthest = self.furthest.max(self.position);
+    }
+}
+
+impl<R: Seek> CountingReader<R> {
+    /// Wrap a reader at its current position.
+    fn new(mut inner: R) -> Result<Self> {
+        let position = inner.stream_position()?;
+        Ok(CountingReader::at(inner, position))
+    }
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.advance(n);
+        Ok(n)
+    }
+}
+
+impl<R: Seek> Seek for CountingReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.position = self.inner.seek(pos)?;
+        Ok(self.position)
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for CountingReader<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        let before = buf.filled().len();
+        ready!(Pin::new(&mut this.inner).poll_read(cx, buf))?;
+        this.advance(buf.filled().len() - before);
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<R: AsyncSeek + Unpin> AsyncSeek for CountingReader<R> {
+    fn start_seek(self: Pin<&mut Self>, pos: SeekFrom) -> io::Result<()> {
+        Pin::new(&mut self.get_mut().inner).start_seek(pos)
+    }
+
+    fn poll_complete(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<u64>> {
+        let this = self.get_mut();
+        this.position = ready!(Pin::new(&mut this.inner).poll_complete(cx))?;
+        Poll::Ready(Ok(this.position))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hashes::Sha256;
+    use std::io::Cursor;
+
+    #[test]
+    fn timed_id_reader_counts_from_current_position() {
+        let mut reader = Cursor::new(b"hello\r\nworld".to_vec());
+        reader.set_position(5);
+
+        let computed = ArtifactId::<Sha256>::timed_id_reader(&mut reader).unwrap();
+        assert_eq!(computed.artifact_id(), ArtifactId::id_bytes(b"\nworld"));
+        assert_eq!(computed.bytes_processed(), Some(7));
+    }
+
+    #[test]
+    fn timed_id_async_reader_counts_from_current_position() {
+        let mut reader = Cursor::new(b"hello\r\nworld".to_vec());
+        reader.set_position(5);
+
+        let computed =
+            tokio_test::block_on(ArtifactId::<Sha256>::timed_id_async_reader(&mut reader)).unwrap();
+        assert_eq!(computed.artifact_id(), ArtifactId::id_bytes(b"\nworld"));
+        assert_eq!(computed.bytes_processed(), Some(7));
+    }
+}
@@ -0,0 +1,51 @@
+//! Store [`ArtifactId`]s in PostgreSQL with Diesel.
+//!
+//! An [`ArtifactId`] is stored as `TEXT`, holding its `gitoid` URL, the same
+//! as with the `postgres` feature. Declare [`ArtifactId`] columns with the
+//! [`Text`] SQL type in `table!`, and they can be used in queries and loaded
+//! into [`ArtifactId`] fields directly:
+//!
+//! ```rust
+//! # use diesel::pg::Pg;
+//! # use diesel::prelude::*;
+//! # use omnibor::ArtifactId;
+//! # use omnibor::hashes::Sha256;
+//! diesel::table! {
+//!     manifests (manifest_aid) {
+//!         manifest_aid -> Text,
+//!         target_aid -> Nullable<Text>,
+//!     }
+//! }
+//!
+//! let target_aid = ArtifactId::<Sha256>::id_str("hello, world");
+//! let query = manifests::table.filter(manifests::target_aid.eq(target_aid));
+//! println!("{}", diesel::debug_query::<Pg, _>(&query));
+//! ```
+
+use crate::hashes::SupportedHash;
+use crate::ArtifactId;
+use diesel::deserialize;
+use diesel::deserialize::FromSql;
+use diesel::pg::Pg;
+use diesel::pg::PgValue;
+use diesel::serialize;
+use diesel::serialize::IsNull;
+use diesel::serialize::Output;
+use diesel::serialize::ToSql;
+use diesel::sql_types::Text;
+use std::io::Write as _;
+use std::str::FromStr;
+
+impl<H: SupportedHash> ToSql<Text, Pg> for ArtifactId<H> {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        write!(out, "{}", self)?;
+        Ok(IsNull::No)
+    }
+}
+
+impl<H: SupportedHash> FromSql<Text, Pg> for ArtifactId<H> {
+    fn from_sql(value: PgValue<'_>) -> deserialize::Result<Self> {
+        let s = <String as FromSql<Text, Pg>>::from_sql(value)?;
+        Ok(ArtifactId::from_str(&s)?)
+    }
+}
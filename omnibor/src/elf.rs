@@ -0,0 +1,356 @@
+//! Read embedded manifest [`ArtifactId`]s from ELF files.
+//!
+//! OmniBOR embeds the [`ArtifactId`] of an ELF file's input manifest in a
+//! `.note.omnibor` section. The section holds one or more ELF notes, each
+//! named `OMNIBOR`, whose type identifies the hash algorithm and whose
+//! descriptor is the raw hash.
+
+use crate::hashes::SupportedHash;
+use crate::ArtifactId;
+use crate::Error;
+use crate::Result;
+use gitoid::HashAlgorithm;
+use std::fs;
+use std::ops::Not as _;
+use std::path::Path;
+use std::result::Result as StdResult;
+use std::str::FromStr;
+
+/// The name of the section holding OmniBOR notes.
+const NOTE_SECTION_NAME: &[u8] = b".note.omnibor";
+
+/// The name of each OmniBOR note, including its NUL terminator.
+pub(crate) const NOTE_NAME: &[u8] = b"OMNIBOR\0";
+
+/// The error for any offset that runs past the end of the file.
+const TRUNCATED: &str = "truncated ELF file";
+
+/// The note type for a SHA-1 gitoid.
+const NT_GITOID_SHA1: u32 = 1;
+
+/// The note type for a SHA-256 gitoid.
+pub(crate) const NT_GITOID_SHA256: u32 = 2;
+
+/// Read the manifest [`ArtifactId`] embedded in an ELF file, if any.
+///
+/// Returns `Ok(None)` if the file has no `.note.omnibor` section, or the
+/// section has no note for the hash algorithm `H`. Returns an [`Error`] if
+/// the file can't be read or isn't a valid ELF file.
+pub fn read_embedded_aid_from_elf<H: SupportedHash>(path: &Path) -> Result<Option<ArtifactId<H>>> {
+    let data = fs::read(path)?;
+    find_embedded_aid::<H>(&data)
+        .map_err(|reason| Error::InvalidElfFile(path.display().to_string(), reason))?
+        .map(|hex| {
+            let url = format!("gitoid:blob:{}:{}", H::HashAlgorithm::NAME, hex);
+            ArtifactId::from_str(&url)
+        })
+        .transpose()
+}
+
+/// Check if some data starts with the ELF magic number.
+pub(crate) fn is_elf(data: &[u8]) -> bool {
+    data.starts_with(b"\x7fELF")
+}
+
+/// Find the hex-encoded hash in the OmniBOR note for `H`, if present.
+fn find_embedded_aid<H: SupportedHash>(data: &[u8]) -> StdResult<Option<String>, &'static str> {
+    let Some(note_type) = note_type::<H>() else {
+        return Ok(None);
+    };
+
+    let elf = Elf::parse(data)?;
+
+    let Some(notes) = elf.section_named(NOTE_SECTION_NAME)? else {
+        return Ok(None);
+    };
+
+    let mut offset = 0;
+
+    while add(offset, 12)? <= notes.len() {
+        let name_size = elf.read_u32(notes, offset)? as usize;
+        let desc_size = elf.read_u32(notes, add(offset, 4)?)? as usize;
+        let kind = elf.read_u32(notes, add(offset, 8)?)?;
+
+        let name_start = add(offset, 12)?;
+        let desc_start = add(name_start, align4(name_size)?)?;
+        let next = add(desc_start, align4(desc_size)?)?;
+
+        let name = notes
+            .get(name_start..add(name_start, name_size)?)
+            .ok_or("truncated note name")?;
+        let desc = notes
+            .get(desc_start..add(desc_start, desc_size)?)
+            .ok_or("truncated note descriptor")?;
+
+        if name == NOTE_NAME && kind == note_type {
+            let hex = desc.iter().map(|byte| format!("{:02x}", byte)).collect();
+            return Ok(Some(hex));
+        }
+
+        offset = next;
+    }
+
+    Ok(None)
+}
+
+/// Get the OmniBOR note type for the hash algorithm `H`.
+fn note_type<H: SupportedHash>() -> Option<u32> {
+    match H::HashAlgorithm::NAME {
+        "sha1" => Some(NT_GITOID_SHA1),
+        "sha256" => Some(NT_GITOID_SHA256),
+        _ => None,
+    }
+}
+
+/// Round up to a multiple of four, the alignment of note fields.
+fn align4(n: usize) -> StdResult<usize, &'static str> {
+    n.checked_next_multiple_of(4).ok_or(TRUNCATED)
+}
+
+/// Add two offsets, failing instead of overflowing.
+fn add(a: usize, b: usize) -> StdResult<usize, &'static str> {
+    a.checked_add(b).ok_or(TRUNCATED)
+}
+
+/// Just enough of an ELF file to find its sections.
+struct Elf<'d> {
+    data: &'d [u8],
+    is_64_bit: bool,
+    is_big_endian: bool,
+}
+
+impl<'d> Elf<'d> {
+    /// Check the ELF identification bytes.
+    fn parse(data: &'d [u8]) -> StdResult<Self, &'static str> {
+        if is_elf(data).not() {
+            return Err("missing ELF magic number");
+        }
+
+        let is_64_bit = match data.get(4) {
+            Some(1) => false,
+            Some(2) => true,
+            _ => return Err("unknown ELF class"),
+        };
+
+        let is_big_endian = match data.get(5) {
+            Some(1) => false,
+            Some(2) => true,
+            _ => return Err("unknown ELF data encoding"),
+        };
+
+        Ok(Elf {
+            data,
+            is_64_bit,
+            is_big_endian,
+        })
+    }
+
+    /// Get the contents of the section with the given name, if present.
+    fn section_named(&self, name: &[u8]) -> StdResult<Option<&'d [u8]>, &'static str> {
+        let data = self.data;
+
+        let (shoff, shentsize, shnum, shstrndx) = if self.is_64_bit {
+            (
+                self.read_u64(data, 0x28)? as usize,
+                self.read_u16(data, 0x3A)? as usize,
+                self.read_u16(data, 0x3C)? as usize,
+                self.read_u16(data, 0x3E)? as usize,
+            )
+        } else {
+            (
+                self.read_u32(data, 0x20)? as usize,
+                self.read_u16(data, 0x2E)? as usize,
+                self.read_u16(data, 0x30)? as usize,
+                self.read_u16(data, 0x32)? as usize,
+            )
+        };
+
+        if shoff == 0 || shnum == 0 {
+            return Ok(None);
+        }
+
+        let header = |index: usize| {
+            index
+                .checked_mul(shentsize)
+                .and_then(|offset| offset.checked_add(shoff))
+                .ok_or(TRUNCATED)
+        };
+        let names = self.section_data(header(shstrndx)?)?;
+
+        for index in 0..shnum {
+            let name_offset = self.read_u32(data, header(index)?)? as usize;
+            let section_name = names
+                .get(name_offset..)
+                .and_then(|rest| rest.split(|byte| *byte == 0).next())
+                .ok_or("section name out of bounds")?;
+
+            if section_name == name {
+                return self.section_data(header(index)?).map(Some);
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Get the contents of the section whose header starts at `header`.
+    fn section_data(&self, header: usize) -> StdResult<&'d [u8], &'static str> {
+        let (offset, size) = if self.is_64_bit {
+            (
+                self.read_u64(self.data, add(header, 0x18)?)? as usize,
+                self.read_u64(self.data, add(header, 0x20)?)? as usize,
+            )
+        } else {
+            (
+                self.read_u32(self.data, add(header, 0x10)?)? as usize,
+                self.read_u32(self.data, add(header, 0x14)?)? as usize,
+            )
+        };
+
+        self.data
+            .get(offset..add(offset, size)?)
+            .ok_or("section out of bounds")
+    }
+
+    fn read_u16(&self, data: &[u8], at: usize) -> StdResult<u16, &'static str> {
+        let bytes = read_array(data, at)?;
+        Ok(if self.is_big_endian {
+            u16::from_be_bytes(bytes)
+        } else {
+            u16::from_le_bytes(bytes)
+        })
+    }
+
+    fn read_u32(&self, data: &[u8], at: usize) -> StdResult<u32, &'static str> {
+        let bytes = read_array(data, at)?;
+        Ok(if self.is_big_endian {
+            u32::from_be_bytes(bytes)
+        } else {
+            u32::from_le_bytes(bytes)
+        })
+    }
+
+    fn read_u64(&self, data: &[u8], at: usize) -> StdResult<u64, &'static str> {
+        let bytes = read_array(data, at)?;
+        Ok(if self.is_big_endian {
+            u64::from_be_bytes(bytes)
+        } else {
+            u64::from_le_bytes(bytes)
+        })
+    }
+}
+
+/// Read a fixed number of bytes at an offset.
+fn read_array<const N: usize>(data: &[u8], at: usize) -> StdResult<[u8; N], &'static str> {
+    data.get(at..add(at, N)?)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or(TRUNCATED)
+}
+
+/// Build a minimal 64-bit little-endian ELF file with the given notes
+/// in a `.note.omnibor` section, for tests.
+#[cfg(test)]
+pub(crate) fn elf_with_notes(notes: &[(&[u8], u32, &[u8])]) -> Vec<u8> {
+    let mut note_data = Vec::new();
+    for (name, kind, desc) in notes {
+        note_data.extend((name.len() as u32).to_le_bytes());
+        note_data.extend((desc.len() as u32).to_le_bytes());
+        note_data.extend(kind.to_le_bytes());
+        note_data.extend(*name);
+        note_data.resize(align4(note_data.len()).unwrap(), 0);
+        note_data.extend(*desc);
+        note_data.resize(align4(note_data.len()).unwrap(), 0);
+    }
+
+    let names = b"\0.shstrtab\0.note.omnibor\0";
+    let names_offset = 64;
+    let notes_offset = names_offset + names.len();
+    let shoff = notes_offset + note_data.len();
+
+    let mut elf = vec![0; 64];
+    elf[..6].copy_from_slice(b"\x7fELF\x02\x01");
+    elf[0x28..0x30].copy_from_slice(&(shoff as u64).to_le_bytes());
+    elf[0x3A..0x3C].copy_from_slice(&64u16.to_le_bytes());
+    elf[0x3C..0x3E].copy_from_slice(&3u16.to_le_bytes());
+    elf[0x3E..0x40].copy_from_slice(&1u16.to_le_bytes());
+    elf.extend(names);
+    elf.extend(&note_data);
+
+    let section = |name: u32, offset: usize, size: usize| {
+        let mut header = vec![0; 64];
+        header[..4].copy_from_slice(&name.to_le_bytes());
+        header[0x18..0x20].copy_from_slice(&(offset as u64).to_le_bytes());
+        header[0x20..0x28].copy_from_slice(&(size as u64).to_le_bytes());
+        header
+    };
+
+    elf.extend(section(0, 0, 0));
+    elf.extend(section(1, names_offset, names.len()));
+    elf.extend(section(11, notes_offset, note_data.len()));
+    elf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hashes::Sha256;
+    use pathbuf::pathbuf;
+
+    #[test]
+    fn finds_sha256_note() {
+        let aid = ArtifactId::<Sha256>::id_str("manifest");
+        let elf = elf_with_notes(&[
+            (b"OTHER\0", NT_GITOID_SHA256, &[0; 4]),
+            (NOTE_NAME, NT_GITOID_SHA1, &[0; 20]),
+            (NOTE_NAME, NT_GITOID_SHA256, aid.as_bytes()),
+        ]);
+
+        let hex = find_embedded_aid::<Sha256>(&elf).unwrap();
+        assert_eq!(hex, Some(aid.as_hex()));
+    }
+
+    #[test]
+    fn missing_note_is_none() {
+        let elf = elf_with_notes(&[(NOTE_NAME, NT_GITOID_SHA1, &[0; 20])]);
+        assert_eq!(find_embedded_aid::<Sha256>(&elf).unwrap(), None);
+    }
+
+    #[test]
+    fn truncated_elf_fails() {
+        let aid = ArtifactId::<Sha256>::id_str("manifest");
+        let elf = elf_with_notes(&[(NOTE_NAME, NT_GITOID_SHA256, aid.as_bytes())]);
+        assert!(find_embedded_aid::<Sha256>(&elf[..100]).is_err());
+    }
+
+    #[test]
+    fn malformed_header_fails() {
+        let elf = elf_with_notes(&[(NOTE_NAME, NT_GITOID_SHA1, &[0; 20])]);
+
+        let mut huge_offset = elf.clone();
+        huge_offset[0x28..0x30].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert_eq!(find_embedded_aid::<Sha256>(&huge_offset), Err(TRUNCATED));
+
+        let mut huge_section = elf;
+        let shoff = huge_section.len() - 64;
+        huge_section[shoff + 0x18..shoff + 0x20].copy_from_slice(&u64::MAX.to_le_bytes());
+        huge_section[shoff + 0x20..shoff + 0x28].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert_eq!(find_embedded_aid::<Sha256>(&huge_section), Err(TRUNCATED));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn real_elf_without_note_is_none() {
+        let exe = std::env::current_exe().unwrap();
+        assert_eq!(read_embedded_aid_from_elf::<Sha256>(&exe).unwrap(), None);
+    }
+
+    #[test]
+    fn non_elf_file_fails() {
+        let path = pathbuf![
+            env!("CARGO_MANIFEST_DIR"),
+            "test",
+            "data",
+            "hello_world.txt"
+        ];
+        assert!(read_embedded_aid_from_elf::<Sha256>(&path).is_err());
+    }
+}
@@ -1,7 +1,7 @@
 use crate::sealed::Sealed;
 #[cfg(doc)]
 use crate::InputManifest;
-use std::marker::PhantomData;
+use core::marker::PhantomData;
 
 /// The embedding mode to use when making new [`InputManifest`]s.
 pub trait EmbeddingMode: Sealed {
@@ -1,15 +1,32 @@
 #[cfg(doc)]
 use crate::ArtifactId;
-#[cfg(doc)]
+#[cfg(all(doc, feature = "std"))]
 use crate::InputManifest;
+#[cfg(all(feature = "nix-compat", not(feature = "std")))]
+use alloc::string::String;
+#[cfg(not(feature = "std"))]
+use core::fmt::Display;
+#[cfg(not(feature = "std"))]
+use core::fmt::Formatter;
+#[cfg(not(feature = "std"))]
+use core::fmt::Result as FmtResult;
+use core::result::Result as StdResult;
 use gitoid::Error as GitOidError;
+#[cfg(feature = "env-inputs")]
+use std::env::VarError;
+#[cfg(feature = "std")]
 use std::io::Error as IoError;
-use std::result::Result as StdResult;
+#[cfg(feature = "process-identify")]
+use std::process::ExitStatus;
+#[cfg(feature = "futures")]
+use tokio::task::JoinError;
+#[cfg(feature = "std")]
 use url::ParseError as UrlError;
 
 pub type Result<T> = StdResult<T, Error>;
 
 /// Errors arising from [`ArtifactId`] use or [`InputManifest`] interaction.
+#[cfg(feature = "std")]
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
     #[error("no storage root found; provide one or set the 'OMNIBOR_DIR' environment variable")]
@@ -33,6 +50,12 @@ pub enum Error {
     #[error("can't write manifest directory '{0}'")]
     CantWriteManifestDir(String, #[source] IoError),
 
+    #[error("can't remove manifest file '{0}'")]
+    CantRemoveManifest(String, #[source] IoError),
+
+    #[error("can't remove manifest directory '{0}'")]
+    CantRemoveManifestDir(String, #[source] IoError),
+
     #[error("can't open target index file '{0}'")]
     CantOpenTargetIndex(String, #[source] IoError),
 
@@ -57,6 +80,51 @@ pub enum Error {
     #[error("missing manifest_aid or target_aid from target index upsert operation")]
     InvalidTargetIndexUpsert,
 
+    #[error("manifest '{0}' not found in storage")]
+    ManifestNotFound(String),
+
+    #[error("no manifest found in storage with target '{0}'")]
+    TargetNotFound(String),
+
+    #[error("this storage doesn't support removing manifests")]
+    RemoveNotSupported,
+
+    #[error("invalid checkpoint label '{0}'; use letters, digits, '-', '_', and '.'")]
+    InvalidCheckpointLabel(String),
+
+    #[error("can't write checkpoint '{0}'")]
+    CantWriteCheckpoint(String, #[source] IoError),
+
+    #[error("can't read checkpoint '{0}'")]
+    CantReadCheckpoint(String, #[source] IoError),
+
+    #[error("no checkpoint found with label '{0}'")]
+    CheckpointNotFound(String),
+
+    #[error("can't write backup '{0}'")]
+    CantWriteBackup(String, #[source] IoError),
+
+    #[error("backup destination '{0}' already exists and isn't an empty directory")]
+    BackupDestinationNotEmpty(String),
+
+    #[error("'{0}' isn't a backup of a manifest store")]
+    InvalidBackup(String),
+
+    #[error("can't back up target index to '{0}'")]
+    CantBackUpTargetIndex(String, #[source] IoError),
+
+    #[error("can't replace target index '{0}'")]
+    CantReplaceTargetIndex(String, #[source] IoError),
+
+    #[error("can't lock storage with lock file '{0}'")]
+    CantLockStorage(String, #[source] IoError),
+
+    #[error("manifest '{0}' has a different target in each store being merged")]
+    MergeConflict(String),
+
+    #[error("storage lock was poisoned by a panicking thread")]
+    StorageLockPoisoned,
+
     #[error("invalid relation kind in input manifest: '{0}'")]
     InvalidRelationKind(String),
 
@@ -84,6 +152,16 @@ pub enum Error {
     #[error("wrong hash algorithm; expected '{expected}', got '{got}'")]
     WrongHashAlgorithm { expected: &'static str, got: String },
 
+    #[error("wrong byte slice length for hash; expected {expected} bytes, got {got}")]
+    WrongByteSliceLength { expected: usize, got: usize },
+
+    #[cfg(feature = "nix-compat")]
+    #[error("invalid Nix base32 hash '{0}'")]
+    InvalidNixHash(String),
+
+    #[error("invalid sha256sum line '{0}'")]
+    InvalidSha256SumLine(String),
+
     #[error("missing manifest-for entry in manifest")]
     MissingManifestForRelation,
 
@@ -93,6 +171,50 @@ pub enum Error {
     #[error("unknown file type for manifest ID embedding")]
     UnknownEmbeddingTarget,
 
+    #[error("embedding manifest IDs in {0} files isn't supported yet")]
+    UnsupportedEmbeddingTarget(&'static str),
+
+    #[error("can't find the root element's closing tag in XML file '{0}'")]
+    NoXmlRootClosingTag(String),
+
+    #[error("invalid Jupyter notebook '{0}': {1}")]
+    InvalidJupyterNotebook(String, &'static str),
+
+    #[error("invalid ELF file '{0}': {1}")]
+    InvalidElfFile(String, &'static str),
+
+    #[error("invalid PE file '{0}': {1}")]
+    InvalidPeFile(String, &'static str),
+
+    #[cfg(feature = "cbor")]
+    #[error("invalid CBOR-tagged Artifact ID: {0}")]
+    InvalidCborTaggedArtifactId(&'static str),
+
+    #[cfg(feature = "futures")]
+    #[error("the task identifying a file failed")]
+    IdentifyTaskFailed(#[source] JoinError),
+
+    #[cfg(feature = "process-identify")]
+    #[error("can't identify the output of a command which failed with {0}")]
+    NonZeroExitStatus(ExitStatus),
+
+    #[error("can't identify '{0}', which is a directory")]
+    CannotIdentifyDirectory(String),
+
+    #[error("can't read symlink '{0}'")]
+    CantReadSymlink(String, #[source] IoError),
+
+    #[error("target of symlink '{0}' is not valid UTF-8")]
+    NonUtf8SymlinkTarget(String),
+
+    #[cfg(feature = "env-inputs")]
+    #[error("can't read environment variable '{0}'")]
+    CantReadEnvVar(String, #[source] VarError),
+
+    #[cfg(feature = "reqwest")]
+    #[error("can't read HTTP response body")]
+    CantReadResponseBody(#[source] reqwest::Error),
+
     #[error("failed to read input manifest file")]
     FailedManifestRead(#[from] IoError),
 
@@ -102,3 +224,43 @@ pub enum Error {
     #[error(transparent)]
     Url(#[from] UrlError),
 }
+
+/// Errors arising from [`ArtifactId`] use.
+///
+/// Without `std`, the only fallible operations are those delegated to
+/// the underlying `gitoid` crate, and constructing an [`ArtifactId`] from
+/// raw hash bytes.
+#[cfg(not(feature = "std"))]
+#[derive(Debug)]
+pub enum Error {
+    WrongByteSliceLength {
+        expected: usize,
+        got: usize,
+    },
+    #[cfg(feature = "nix-compat")]
+    InvalidNixHash(String),
+    GitOid(GitOidError),
+}
+
+#[cfg(not(feature = "std"))]
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Error::WrongByteSliceLength { expected, got } => write!(
+                f,
+                "wrong byte slice length for hash; expected {} bytes, got {}",
+                expected, got
+            ),
+            #[cfg(feature = "nix-compat")]
+            Error::InvalidNixHash(s) => write!(f, "invalid Nix base32 hash '{}'", s),
+            Error::GitOid(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl From<GitOidError> for Error {
+    fn from(e: GitOidError) -> Self {
+        Error::GitOid(e)
+    }
+}
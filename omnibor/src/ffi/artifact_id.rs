@@ -81,7 +81,7 @@ pub unsafe extern "C" fn ob_str_free(s: *const c_char) {
 }
 
 /// A `ArtifactId` constructed with the specified hash algorithm.
-pub struct ArtifactIdSha256(ArtifactId<Sha256>);
+pub struct ArtifactIdSha256(pub(crate) ArtifactId<Sha256>);
 
 /// Construct a new `ArtifactId` from a buffer of bytes.
 ///
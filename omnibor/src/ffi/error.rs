@@ -84,6 +84,10 @@ pub(crate) enum Error {
     ContentPtrIsNull,
     StringPtrIsNull,
     ArtifactIdPtrIsNull,
+    ManifestBuilderPtrIsNull,
+    ManifestPtrIsNull,
+    MissingTargetPath,
+    ManifestBuildFailed(ArtifactIdError),
     Utf8UnexpectedEnd,
     Utf8InvalidByte(usize, usize),
     NotValidUrl(UrlError),
@@ -97,6 +101,10 @@ impl Display for Error {
             Error::ContentPtrIsNull => write!(f, "data pointer is null"),
             Error::StringPtrIsNull => write!(f, "string pointer is null"),
             Error::ArtifactIdPtrIsNull => write!(f, "ArtifactId pointer is null"),
+            Error::ManifestBuilderPtrIsNull => write!(f, "InputManifest builder pointer is null"),
+            Error::ManifestPtrIsNull => write!(f, "InputManifest pointer is null"),
+            Error::MissingTargetPath => write!(f, "InputManifest target path was not set"),
+            Error::ManifestBuildFailed(_) => write!(f, "failed to build InputManifest"),
             Error::Utf8UnexpectedEnd => write!(f, "UTF-8 byte sequence ended unexpectedly"),
             Error::Utf8InvalidByte(start, len) => write!(
                 f,
@@ -117,6 +125,7 @@ impl StdError for Error {
         match self {
             Error::NotValidUrl(e) => Some(e),
             Error::NotArtifactIdUrl(e) => Some(e),
+            Error::ManifestBuildFailed(e) => Some(e),
             _ => None,
         }
     }
@@ -0,0 +1,382 @@
+//! The main InputManifest FFI functions.
+
+use crate::embedding::NoEmbed;
+use crate::ffi::artifact_id::ArtifactIdSha256;
+use crate::ffi::error::catch_panic;
+use crate::ffi::error::Error;
+use crate::ffi::status::Status;
+use crate::ffi::util::check_null;
+use crate::hashes::Sha256;
+use crate::storage::InMemoryStorage;
+use crate::ArtifactId;
+use crate::InputManifest;
+use crate::InputManifestBuilder;
+use crate::ShouldStore;
+use core::ffi::c_char;
+use core::ffi::c_int;
+use core::ffi::CStr;
+use core::ptr::null;
+use core::ptr::null_mut;
+use core::slice::from_raw_parts;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// A builder for `InputManifest`s using the SHA-256 hash algorithm.
+///
+/// Manifests built this way are not persisted to any storage.
+pub struct OmniborManifestBuilder {
+    /// The underlying builder.
+    builder: InputManifestBuilder<Sha256, NoEmbed, InMemoryStorage>,
+
+    /// The path of the target artifact, once set.
+    target: Option<PathBuf>,
+}
+
+/// An `InputManifest` using the SHA-256 hash algorithm.
+pub struct OmniborManifest(InputManifest<Sha256>);
+
+/// Construct a new, empty `InputManifest` builder.
+///
+/// # Safety
+///
+/// The returned builder must be freed with `ob_manifest_builder_free`.
+#[no_mangle]
+pub unsafe extern "C" fn ob_manifest_builder_new() -> *mut OmniborManifestBuilder {
+    let output = catch_panic(|| {
+        let builder = OmniborManifestBuilder {
+            builder: InputManifestBuilder::with_storage(InMemoryStorage::new()),
+            target: None,
+        };
+        let boxed = Box::new(builder);
+        Ok(Box::into_raw(boxed))
+    });
+
+    output.unwrap_or_else(null_mut)
+}
+
+/// Add an input to the manifest builder by identifying a buffer of bytes.
+///
+/// Returns 0 on success, or a negative status code on failure.
+///
+/// # Safety
+///
+/// `builder` must be a pointer returned by `ob_manifest_builder_new`.
+///
+/// `content` must not be null, and the length of the buffer must match the
+/// length in bytes passed by `content_len`.
+#[no_mangle]
+pub unsafe extern "C" fn ob_manifest_builder_add_input_bytes(
+    builder: *mut OmniborManifestBuilder,
+    content: *const u8,
+    content_len: usize,
+) -> c_int {
+    let output = catch_panic(|| {
+        check_null(builder, Error::ManifestBuilderPtrIsNull)?;
+        check_null(content, Error::ContentPtrIsNull)?;
+        let builder = unsafe { &mut *builder };
+        let content = unsafe { from_raw_parts(content, content_len) };
+        builder
+            .builder
+            .add_relation(content)
+            .map_err(Error::ManifestBuildFailed)?;
+        Ok(0)
+    });
+
+    output.unwrap_or(Status::OperationFailed as c_int)
+}
+
+/// Add an input to the manifest builder by identifying the file at a path.
+///
+/// Returns 0 on success, or a negative status code on failure.
+///
+/// # Safety
+///
+/// `builder` must be a pointer returned by `ob_manifest_builder_new`.
+///
+/// The path passed _must_ be a valid C-string with a nul-terminator at the
+/// end, all contained in a single contiguous allocation. The pointer must also
+/// not be null.
+#[no_mangle]
+pub unsafe extern "C" fn ob_manifest_builder_add_input_path(
+    builder: *mut OmniborManifestBuilder,
+    path: *const c_char,
+) -> c_int {
+    let output = catch_panic(|| {
+        check_null(builder, Error::ManifestBuilderPtrIsNull)?;
+        check_null(path, Error::StringPtrIsNull)?;
+        let builder = unsafe { &mut *builder };
+        let path = Path::new(unsafe { CStr::from_ptr(path) }.to_str()?);
+        builder
+            .builder
+            .add_relation(path)
+            .map_err(Error::ManifestBuildFailed)?;
+        Ok(0)
+    });
+
+    output.unwrap_or(Status::OperationFailed as c_int)
+}
+
+/// Set the path of the target artifact the manifest describes.
+///
+/// Returns 0 on success, or a negative status code on failure.
+///
+/// # Safety
+///
+/// `builder` must be a pointer returned by `ob_manifest_builder_new`.
+///
+/// The path passed _must_ be a valid C-string with a nul-terminator at the
+/// end, all contained in a single contiguous allocation. The pointer must also
+/// not be null.
+#[no_mangle]
+pub unsafe extern "C" fn ob_manifest_builder_set_target_path(
+    builder: *mut OmniborManifestBuilder,
+    path: *const c_char,
+) -> c_int {
+    let output = catch_panic(|| {
+        check_null(builder, Error::ManifestBuilderPtrIsNull)?;
+        check_null(path, Error::StringPtrIsNull)?;
+        let builder = unsafe { &mut *builder };
+        let path = unsafe { CStr::from_ptr(path) }.to_str()?;
+        builder.target = Some(PathBuf::from(path));
+        Ok(0)
+    });
+
+    output.unwrap_or(Status::OperationFailed as c_int)
+}
+
+/// Build an `InputManifest` from the inputs added to the builder.
+///
+/// The target path must have been set with `ob_manifest_builder_set_target_path`.
+/// After building, the builder's inputs are cleared so it can be reused.
+///
+/// # Safety
+///
+/// `builder` must be a pointer returned by `ob_manifest_builder_new`.
+///
+/// Returns a `NULL` pointer if building the manifest fails.
+///
+/// The returned manifest must be freed with `ob_manifest_free`.
+#[no_mangle]
+pub unsafe extern "C" fn ob_manifest_builder_build(
+    builder: *mut OmniborManifestBuilder,
+) -> *const OmniborManifest {
+    let output = catch_panic(|| {
+        check_null(builder, Error::ManifestBuilderPtrIsNull)?;
+        let builder = unsafe { &mut *builder };
+        let target = builder.target.as_deref().ok_or(Error::MissingTargetPath)?;
+        let linked = builder
+            .builder
+            .finish(target, ShouldStore::No)
+            .map_err(Error::ManifestBuildFailed)?;
+        let manifest = OmniborManifest(linked.manifest().clone());
+        let boxed = Box::new(manifest);
+        Ok(Box::into_raw(boxed) as *const _)
+    });
+
+    output.unwrap_or_else(null)
+}
+
+/// Get the `ArtifactId` of an `InputManifest`.
+///
+/// # Safety
+///
+/// Returns a `NULL` pointer if the manifest pointer is null or the
+/// `ArtifactId` can't be constructed.
+///
+/// The returned `ArtifactId` must be freed with `ob_aid_sha256_free`.
+#[no_mangle]
+pub unsafe extern "C" fn ob_manifest_id(ptr: *const OmniborManifest) -> *const ArtifactIdSha256 {
+    let output = catch_panic(|| {
+        check_null(ptr, Error::ManifestPtrIsNull)?;
+        let manifest = unsafe { &*ptr };
+        let artifact_id =
+            ArtifactId::id_manifest(&manifest.0).map_err(Error::ManifestBuildFailed)?;
+        let boxed = Box::new(ArtifactIdSha256(artifact_id));
+        Ok(Box::into_raw(boxed) as *const _)
+    });
+
+    output.unwrap_or_else(null)
+}
+
+/// Free the `InputManifest` builder from memory.
+///
+/// # Safety
+///
+/// Does nothing if passed a null pointer.
+#[no_mangle]
+pub unsafe extern "C" fn ob_manifest_builder_free(builder: *mut OmniborManifestBuilder) {
+    if check_null(builder, Error::ManifestBuilderPtrIsNull).is_err() {
+        return;
+    }
+
+    let _ = unsafe { Box::from_raw(builder) };
+
+    // Dropped and freed automatically when the `Box` goes out of scope.
+}
+
+/// Free the `InputManifest` from memory.
+///
+/// # Safety
+///
+/// Does nothing if passed a null pointer.
+#[no_mangle]
+pub unsafe extern "C" fn ob_manifest_free(ptr: *const OmniborManifest) {
+    if check_null(ptr, Error::ManifestPtrIsNull).is_err() {
+        return;
+    }
+
+    // SAFETY: This const-to-mut conversion is safe because the provenance was originally
+    //         mut on creation in all constructors.
+    let _ = unsafe { Box::from_raw(ptr as *mut OmniborManifest) };
+
+    // Dropped and freed automatically when the `Box` goes out of scope.
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ffi::artifact_id::ob_aid_sha256_free;
+    use crate::ffi::error::get_error_msg;
+    use crate::Relation;
+    use pathbuf::pathbuf;
+    use std::ffi::CString;
+    use std::fs::File;
+    use std::ops::Not as _;
+
+    fn c_path(path: &Path) -> CString {
+        CString::new(path.to_str().unwrap()).unwrap()
+    }
+
+    fn hello_world_path() -> PathBuf {
+        pathbuf![
+            env!("CARGO_MANIFEST_DIR"),
+            "test",
+            "data",
+            "hello_world.txt"
+        ]
+    }
+
+    /// Get the ID of a built manifest, freeing it afterwards.
+    unsafe fn manifest_aid(manifest: *const OmniborManifest) -> ArtifactId<Sha256> {
+        assert!(manifest.is_null().not());
+        let aid_ptr = unsafe { ob_manifest_id(manifest) };
+        assert!(aid_ptr.is_null().not());
+        let aid = unsafe { (*aid_ptr).0 };
+        unsafe {
+            ob_aid_sha256_free(aid_ptr);
+            ob_manifest_free(manifest);
+        }
+        aid
+    }
+
+    /// The ID of a manifest with the given inputs.
+    fn expected_aid(inputs: impl IntoIterator<Item = ArtifactId<Sha256>>) -> ArtifactId<Sha256> {
+        let mut relations = inputs
+            .into_iter()
+            .map(Relation::from_artifact)
+            .collect::<Vec<_>>();
+        relations.sort();
+        ArtifactId::id_manifest(&InputManifest::with_relations(relations.into_iter())).unwrap()
+    }
+
+    #[test]
+    fn null_pointers_fail() {
+        let path = c_path(&hello_world_path());
+
+        unsafe {
+            let status = ob_manifest_builder_add_input_bytes(null_mut(), b"x".as_ptr(), 1);
+            assert_eq!(status, Status::OperationFailed as c_int);
+            assert_eq!(
+                get_error_msg().as_deref(),
+                Some("InputManifest builder pointer is null")
+            );
+
+            let status = ob_manifest_builder_add_input_path(null_mut(), path.as_ptr());
+            assert_eq!(status, Status::OperationFailed as c_int);
+            let status = ob_manifest_builder_set_target_path(null_mut(), path.as_ptr());
+            assert_eq!(status, Status::OperationFailed as c_int);
+            assert!(ob_manifest_builder_build(null_mut()).is_null());
+
+            assert!(ob_manifest_id(null()).is_null());
+            assert_eq!(
+                get_error_msg().as_deref(),
+                Some("InputManifest pointer is null")
+            );
+
+            let builder = ob_manifest_builder_new();
+            assert!(builder.is_null().not());
+            let status = ob_manifest_builder_add_input_bytes(builder, null(), 0);
+            assert_eq!(status, Status::OperationFailed as c_int);
+            assert_eq!(get_error_msg().as_deref(), Some("data pointer is null"));
+            let status = ob_manifest_builder_add_input_path(builder, null());
+            assert_eq!(status, Status::OperationFailed as c_int);
+            let status = ob_manifest_builder_set_target_path(builder, null());
+            assert_eq!(status, Status::OperationFailed as c_int);
+            ob_manifest_builder_free(builder);
+        }
+    }
+
+    #[test]
+    fn build_without_target_fails() {
+        unsafe {
+            let builder = ob_manifest_builder_new();
+            assert_eq!(
+                ob_manifest_builder_add_input_bytes(builder, b"input".as_ptr(), 5),
+                0
+            );
+            assert!(ob_manifest_builder_build(builder).is_null());
+            assert_eq!(
+                get_error_msg().as_deref(),
+                Some("InputManifest target path was not set")
+            );
+            ob_manifest_builder_free(builder);
+        }
+    }
+
+    #[test]
+    fn build_matches_manifest_id() {
+        let hello_world = hello_world_path();
+        let path = c_path(&hello_world);
+        let hello_world_aid = ArtifactId::id_reader(File::open(&hello_world).unwrap()).unwrap();
+
+        unsafe {
+            let builder = ob_manifest_builder_new();
+            assert_eq!(
+                ob_manifest_builder_add_input_bytes(builder, b"input".as_ptr(), 5),
+                0
+            );
+            assert_eq!(
+                ob_manifest_builder_add_input_path(builder, path.as_ptr()),
+                0
+            );
+            assert_eq!(
+                ob_manifest_builder_set_target_path(builder, path.as_ptr()),
+                0
+            );
+
+            let aid = manifest_aid(ob_manifest_builder_build(builder));
+            assert_eq!(
+                aid,
+                expected_aid([ArtifactId::id_bytes(b"input"), hello_world_aid])
+            );
+
+            // The inputs are cleared after building, but the target is kept.
+            assert_eq!(
+                ob_manifest_builder_add_input_bytes(builder, b"other".as_ptr(), 5),
+                0
+            );
+            let aid = manifest_aid(ob_manifest_builder_build(builder));
+            assert_eq!(aid, expected_aid([ArtifactId::id_bytes(b"other")]));
+
+            ob_manifest_builder_free(builder);
+        }
+    }
+
+    #[test]
+    fn freeing_null_does_nothing() {
+        unsafe {
+            ob_manifest_builder_free(null_mut());
+            ob_manifest_free(null());
+        }
+    }
+}
@@ -2,8 +2,10 @@
 
 mod artifact_id;
 pub(crate) mod error;
+mod input_manifest;
 pub(crate) mod status;
 pub(crate) mod util;
 
 // Re-export
 pub use crate::ffi::artifact_id::*;
+pub use crate::ffi::input_manifest::*;
@@ -12,4 +12,6 @@ pub(crate) enum Status {
     BufferWriteFailed = -4,
     /// Input pointer is invalid.
     InvalidPtr = -5,
+    /// The requested operation failed; see the last error message.
+    OperationFailed = -6,
 }
@@ -1,13 +1,16 @@
 //! [`InputManifest`] type that represents build inputs for an artifact.
 
 use crate::hashes::SupportedHash;
+use crate::storage::Storage;
 use crate::ArtifactId;
 use crate::Error;
+use crate::IntoArtifactId as _;
 use crate::Result;
 use gitoid::Blob;
 use gitoid::HashAlgorithm;
 use gitoid::ObjectType;
 use std::cmp::Ordering;
+use std::collections::HashSet;
 use std::fmt::Debug;
 use std::fmt::Formatter;
 use std::fmt::Result as FmtResult;
@@ -15,7 +18,9 @@ use std::fs::File;
 use std::io::BufRead;
 use std::io::BufReader;
 use std::io::Write;
+use std::ops::Not as _;
 use std::path::Path;
+use std::result::Result as StdResult;
 use std::str::FromStr;
 
 /// A manifest describing the inputs used to build an artifact.
@@ -83,8 +88,12 @@ impl<H: SupportedHash> InputManifest<H> {
 
     /// Construct an [`InputManifest`] from a file at a specified path.
     pub fn from_path(path: &Path) -> Result<Self> {
-        let file = BufReader::new(File::open(path)?);
-        let mut lines = file.lines();
+        InputManifest::from_reader(BufReader::new(File::open(path)?))
+    }
+
+    /// Construct an [`InputManifest`] from the contents of a manifest file.
+    pub(crate) fn from_reader<R: BufRead>(reader: R) -> Result<Self> {
+        let mut lines = reader.lines();
 
         let first_line = lines
             .next()
@@ -154,6 +163,140 @@ impl<H: SupportedHash> InputManifest<H> {
 
         Ok(bytes)
     }
+
+    /// Check that the manifest complies with the specification.
+    ///
+    /// This checks that relations are sorted by the lexicographic order of
+    /// their artifact IDs, and that no artifact appears more than once. All
+    /// problems found are returned, rather than stopping at the first one.
+    ///
+    /// The header line is checked when a manifest is parsed with
+    /// [`InputManifest::from_path`], and is always well-formed when written
+    /// with [`InputManifest::as_bytes`], so it isn't checked again here.
+    ///
+    /// Use [`InputManifest::validate_with_storage`] to also check that the
+    /// manifest IDs in relations refer to manifests which exist.
+    pub fn validate(&self) -> StdResult<(), Vec<InputManifestValidationError<H>>> {
+        let errors = self.validation_errors();
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Check that the manifest complies with the specification, and that
+    /// every manifest ID in its relations refers to a manifest in `storage`.
+    ///
+    /// See [`InputManifest::validate`] for the other checks performed.
+    pub fn validate_with_storage<S: Storage<H>>(
+        &self,
+        storage: &S,
+    ) -> StdResult<(), Vec<InputManifestValidationError<H>>> {
+        let mut errors = self.validation_errors();
+
+        let has_manifest_refs = self
+            .relations
+            .iter()
+            .any(|relation| relation.manifest.is_some());
+
+        if has_manifest_refs {
+            match stored_manifest_ids(storage) {
+                Ok(stored) => errors.extend(
+                    self.relations
+                        .iter()
+                        .filter_map(|relation| relation.manifest)
+                        .filter(|manifest_aid| stored.contains(manifest_aid).not())
+                        .map(InputManifestValidationError::MissingManifest),
+                ),
+                Err(e) => errors.push(InputManifestValidationError::StorageFailed(e)),
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Collect the problems found by the checks which don't need storage.
+    fn validation_errors(&self) -> Vec<InputManifestValidationError<H>> {
+        let mut errors = Vec::new();
+
+        for pair in self.relations.windows(2) {
+            let (prev, next) = (pair[0].artifact, pair[1].artifact);
+
+            if prev > next {
+                errors.push(InputManifestValidationError::OutOfOrder { prev, next });
+            }
+        }
+
+        let mut seen = HashSet::new();
+
+        for relation in &self.relations {
+            if seen.insert(relation.artifact).not() {
+                errors.push(InputManifestValidationError::DuplicateInput(
+                    relation.artifact,
+                ));
+            }
+        }
+
+        errors
+    }
+}
+
+/// Get the IDs of all manifests in the storage.
+fn stored_manifest_ids<H: SupportedHash, S: Storage<H>>(
+    storage: &S,
+) -> Result<HashSet<ArtifactId<H>>> {
+    storage
+        .get_manifests()?
+        .iter()
+        .map(ArtifactId::id_manifest)
+        .collect()
+}
+
+/// A problem found when validating an [`InputManifest`].
+#[derive(thiserror::Error)]
+pub enum InputManifestValidationError<H: SupportedHash> {
+    #[error("relations out of order; '{prev}' should come after '{next}'")]
+    OutOfOrder {
+        prev: ArtifactId<H>,
+        next: ArtifactId<H>,
+    },
+
+    #[error("duplicate relation for artifact '{0}'")]
+    DuplicateInput(ArtifactId<H>),
+
+    #[error("relation refers to manifest '{0}', which is not in storage")]
+    MissingManifest(ArtifactId<H>),
+
+    #[error("could not read manifests from storage")]
+    StorageFailed(#[source] Error),
+}
+
+// Implemented by hand so `H` doesn't need to implement `Debug`.
+impl<H: SupportedHash> Debug for InputManifestValidationError<H> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            InputManifestValidationError::OutOfOrder { prev, next } => f
+                .debug_struct("OutOfOrder")
+                .field("prev", prev)
+                .field("next", next)
+                .finish(),
+            InputManifestValidationError::DuplicateInput(aid) => {
+                f.debug_tuple("DuplicateInput").field(aid).finish()
+            }
+            InputManifestValidationError::MissingManifest(aid) => {
+                f.debug_tuple("MissingManifest").field(aid).finish()
+            }
+            InputManifestValidationError::StorageFailed(e) => {
+                f.debug_tuple("StorageFailed").field(e).finish()
+            }
+        }
+    }
 }
 
 impl<H: SupportedHash> Debug for InputManifest<H> {
@@ -178,11 +321,13 @@ impl<H: SupportedHash> Clone for InputManifest<H> {
 fn parse_relation<H: SupportedHash>(input: &str) -> Result<Relation<H>> {
     let parts = input.split(' ').collect::<Vec<_>>();
 
-    if parts.len() < 2 {
+    // Relations without a manifest are just the artifact hash.
+    //
+    // Panic Safety: `split` always produces at least one item.
+    if parts[0].is_empty() {
         return Err(Error::MissingRelationParts);
     }
 
-    // Panic Safety: we've already checked the length.
     let (aid_hex, manifest_indicator, manifest_aid_hex) = (parts[0], parts.get(1), parts.get(2));
 
     let artifact = ArtifactId::<H>::from_str(&format!(
@@ -272,6 +417,50 @@ impl<H: SupportedHash> Relation<H> {
         Relation { artifact, manifest }
     }
 
+    /// Construct a [`Relation`] for an artifact without a manifest.
+    ///
+    /// Use [`Relation::with_manifest_id`] to attach a manifest afterward.
+    pub fn new_without_manifest(artifact: ArtifactId<H>) -> Relation<H> {
+        Relation::new(artifact, None)
+    }
+
+    /// Construct a [`Relation`] for an artifact without a manifest.
+    ///
+    /// This is an alias for [`Relation::new_without_manifest`].
+    pub fn from_artifact(artifact: ArtifactId<H>) -> Relation<H> {
+        Relation::new_without_manifest(artifact)
+    }
+
+    /// Construct a [`Relation`] for the file at a path, with the ID of its
+    /// manifest if the storage has one.
+    ///
+    /// This identifies the file, then looks up its manifest with
+    /// [`Storage::get_manifest_id_for_artifact`], the same way as
+    /// [`InputManifestBuilder::add_relation`](crate::InputManifestBuilder::add_relation).
+    pub fn from_path_and_storage<P: AsRef<Path>, S: Storage<H>>(
+        path: P,
+        storage: &S,
+    ) -> Result<Relation<H>> {
+        let artifact = path.as_ref().into_artifact_id()?;
+        Relation::from_storage(artifact, storage)
+    }
+
+    /// Construct a [`Relation`] for an artifact, with the ID of its manifest
+    /// if the storage has one.
+    pub(crate) fn from_storage<S: Storage<H>>(
+        artifact: ArtifactId<H>,
+        storage: &S,
+    ) -> Result<Relation<H>> {
+        let manifest = storage.get_manifest_id_for_artifact(artifact)?;
+        Ok(Relation::new(artifact, manifest))
+    }
+
+    /// Set the ID of the manifest for the artifact.
+    pub fn with_manifest_id(mut self, manifest_id: ArtifactId<H>) -> Relation<H> {
+        self.manifest = Some(manifest_id);
+        self
+    }
+
     /// Get the ID of the artifact.
     #[inline]
     pub fn artifact(&self) -> ArtifactId<H> {
@@ -284,3 +473,110 @@ impl<H: SupportedHash> Relation<H> {
         self.manifest
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hashes::Sha256;
+    use crate::storage::InMemoryStorage;
+    use pathbuf::pathbuf;
+
+    fn sorted_inputs() -> Vec<Relation<Sha256>> {
+        let mut relations = ["a", "b", "c"]
+            .into_iter()
+            .map(|s| Relation::from_artifact(ArtifactId::id_str(s)))
+            .collect::<Vec<_>>();
+        relations.sort();
+        relations
+    }
+
+    #[test]
+    fn valid_manifest_passes() {
+        let manifest = InputManifest::with_relations(sorted_inputs().into_iter());
+        assert!(manifest.validate().is_ok());
+    }
+
+    #[test]
+    fn reports_all_problems() {
+        let mut relations = sorted_inputs();
+        relations.reverse();
+        relations.push(relations[0].clone());
+        let manifest = InputManifest::with_relations(relations.into_iter());
+
+        let errors = manifest.validate().unwrap_err();
+        let out_of_order = errors
+            .iter()
+            .filter(|e| matches!(e, InputManifestValidationError::OutOfOrder { .. }))
+            .count();
+        let duplicates = errors
+            .iter()
+            .filter(|e| matches!(e, InputManifestValidationError::DuplicateInput(_)))
+            .count();
+
+        assert_eq!(out_of_order, 2);
+        assert_eq!(duplicates, 1);
+    }
+
+    #[test]
+    fn relations_without_manifest_round_trip() {
+        let bare = Relation::from_artifact(ArtifactId::<Sha256>::id_str("bare"));
+        let with_manifest = Relation::from_artifact(ArtifactId::id_str("with_manifest"))
+            .with_manifest_id(ArtifactId::id_str("manifest"));
+        let mut relations = vec![bare.clone(), with_manifest];
+        relations.sort();
+        let manifest = InputManifest::with_relations(relations.into_iter());
+
+        let bytes = manifest.as_bytes().unwrap();
+        let bare_line = format!("{}\n", bare.artifact().as_hex());
+        assert!(String::from_utf8_lossy(&bytes).contains(&bare_line));
+
+        let parsed = InputManifest::<Sha256>::from_reader(&bytes[..]).unwrap();
+        assert_eq!(parsed.relations(), manifest.relations());
+        assert_eq!(parsed.as_bytes().unwrap(), bytes);
+    }
+
+    #[test]
+    fn relation_from_path_and_storage() {
+        let path = pathbuf![
+            env!("CARGO_MANIFEST_DIR"),
+            "test",
+            "data",
+            "hello_world.txt"
+        ];
+        let artifact = ArtifactId::<Sha256>::id_reader(File::open(&path).unwrap()).unwrap();
+        let mut storage = InMemoryStorage::new();
+
+        let relation = Relation::from_path_and_storage(&path, &storage).unwrap();
+        assert_eq!(relation, Relation::from_artifact(artifact));
+
+        let mut manifest = InputManifest::with_relations(sorted_inputs().into_iter());
+        manifest.set_target(Some(artifact));
+        let manifest_aid = storage.batch_write_manifests(&[manifest]).unwrap()[0];
+
+        let relation = Relation::from_path_and_storage(&path, &storage).unwrap();
+        assert_eq!(relation.artifact(), artifact);
+        assert_eq!(relation.manifest(), Some(manifest_aid));
+    }
+
+    #[test]
+    fn checks_manifest_refs_against_storage() {
+        let mut storage = InMemoryStorage::new();
+        let dep_manifest = InputManifest::with_relations(sorted_inputs().into_iter());
+        let stored_aid = storage.write_manifest(&dep_manifest).unwrap();
+        let missing_aid = ArtifactId::id_str("missing");
+
+        let mut relations = vec![
+            Relation::from_artifact(ArtifactId::id_str("x")).with_manifest_id(stored_aid),
+            Relation::from_artifact(ArtifactId::id_str("y")).with_manifest_id(missing_aid),
+        ];
+        relations.sort();
+        let manifest = InputManifest::with_relations(relations.into_iter());
+
+        let errors = manifest.validate_with_storage(&storage).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(
+            errors[0],
+            InputManifestValidationError::MissingManifest(aid) if aid == missing_aid
+        ));
+    }
+}
@@ -1,7 +1,14 @@
+use crate::elf::is_elf;
+use crate::embedding::read_embedded_aid_from_elf;
 use crate::embedding::EmbeddingMode;
 use crate::embedding_mode::Mode;
 use crate::hashes::SupportedHash;
+#[cfg(feature = "jupyter-embed")]
+use crate::jupyter::insert_notebook_metadata;
 use crate::storage::Storage;
+use crate::text_embedding::find_embedded_aid_after_prefix;
+use crate::text_embedding::insert_comment;
+use crate::text_embedding::xml_processing_instruction;
 use crate::ArtifactId;
 use crate::Error;
 use crate::InputManifest;
@@ -9,13 +16,25 @@ use crate::IntoArtifactId;
 use crate::Relation;
 use crate::Result;
 use std::collections::BTreeSet;
+use std::collections::HashMap;
+#[cfg(feature = "env-inputs")]
+use std::env;
+#[cfg(feature = "env-inputs")]
+use std::env::VarError;
 use std::fmt::Debug;
 use std::fmt::Formatter;
 use std::fmt::Result as FmtResult;
 use std::fs::File;
 use std::fs::OpenOptions;
+use std::io::Read as _;
+use std::io::Seek as _;
+use std::io::SeekFrom;
+use std::io::Write as _;
 use std::marker::PhantomData;
+use std::ops::Not as _;
 use std::path::Path;
+#[cfg(feature = "env-inputs")]
+use std::result::Result as StdResult;
 
 /// An [`InputManifest`] builder.
 pub struct InputManifestBuilder<H: SupportedHash, M: EmbeddingMode, S: Storage<H>> {
@@ -25,6 +44,9 @@ pub struct InputManifestBuilder<H: SupportedHash, M: EmbeddingMode, S: Storage<H
     /// Indicates whether manifests should be embedded in the artifact or not.
     mode: PhantomData<M>,
 
+    /// Comment prefixes for file extensions without a built-in file type.
+    custom_prefixes: HashMap<String, String>,
+
     /// The storage system used to store manifests.
     storage: S,
 }
@@ -43,6 +65,7 @@ impl<H: SupportedHash, M: EmbeddingMode, S: Storage<H>> Debug for InputManifestB
         f.debug_struct("InputManifestBuilder")
             .field("mode", &M::mode())
             .field("relations", &self.relations)
+            .field("custom_prefixes", &self.custom_prefixes)
             .finish_non_exhaustive()
     }
 }
@@ -53,18 +76,58 @@ impl<H: SupportedHash, M: EmbeddingMode, S: Storage<H>> InputManifestBuilder<H,
         Self {
             relations: BTreeSet::new(),
             mode: PhantomData,
+            custom_prefixes: HashMap::new(),
             storage,
         }
     }
 
+    /// Embed in files with the given extension using a prefix comment.
+    ///
+    /// This lets manifests be embedded in files written in languages without
+    /// a built-in file type, like domain-specific languages. Built-in file
+    /// types take precedence. Extensions are given without the leading `.`,
+    /// and compared case-insensitively.
+    pub fn with_custom_comment_prefix(&mut self, extension: &str, prefix: &str) -> &mut Self {
+        self.custom_prefixes
+            .insert(extension.to_lowercase(), prefix.to_owned());
+        self
+    }
+
     /// Add a relation to an artifact to the transaction.
     pub fn add_relation(&mut self, artifact: impl IntoArtifactId<H>) -> Result<&mut Self> {
         let artifact = artifact.into_artifact_id()?;
-        let manifest = self.storage.get_manifest_id_for_artifact(artifact)?;
-        self.relations.insert(Relation::new(artifact, manifest));
+        let relation = Relation::from_storage(artifact, &self.storage)?;
+        self.relations.insert(relation);
         Ok(self)
     }
 
+    #[cfg(feature = "env-inputs")]
+    /// Add a relation to the value of an environment variable.
+    ///
+    /// Build output often depends on environment variables like `PATH`,
+    /// `CC`, or `CFLAGS`. This reads the variable's value and adds a relation
+    /// to the [`ArtifactId`] of its UTF-8 bytes.
+    ///
+    /// Only the value is identified, not the variable's name, so two
+    /// variables with the same value produce the same relation. To tell
+    /// them apart, add a relation to bytes which include the name instead,
+    /// like `CC=clang`.
+    ///
+    /// Fails if the variable isn't set or its value isn't valid UTF-8.
+    pub fn add_env_var(&mut self, name: &str) -> Result<&mut Self> {
+        self.add_env_var_with(name, |name| env::var(name))
+    }
+
+    #[cfg(feature = "env-inputs")]
+    /// Add a relation to the value of a variable read with `lookup`.
+    fn add_env_var_with<F>(&mut self, name: &str, lookup: F) -> Result<&mut Self>
+    where
+        F: FnOnce(&str) -> StdResult<String, VarError>,
+    {
+        let value = lookup(name).map_err(|e| Error::CantReadEnvVar(name.to_owned(), e))?;
+        self.add_relation(ArtifactId::id_str(value))
+    }
+
     /// Complete the transaction without updating the target artifact.
     pub fn finish(
         &mut self,
@@ -98,15 +161,20 @@ impl<H: SupportedHash, M: EmbeddingMode, S: Storage<H>> InputManifestBuilder<H,
 
         // Get the ArtifactID of the target, possibly embedding the
         // manifest ArtifactID into the target first.
-        let target_aid = match embed_mode {
+        let (target_aid, embed_result) = match embed_mode {
             Mode::Embed => {
                 let mut file = OpenOptions::new().read(true).write(true).open(target)?;
-                embed_manifest_in_target(target, &mut file, manifest_aid)?;
-                ArtifactId::id_reader(file)?
+                let (target_aid, embed_result) = embed_manifest_in_target(
+                    target,
+                    &mut file,
+                    manifest_aid,
+                    &self.custom_prefixes,
+                )?;
+                (target_aid, Some(embed_result))
             }
             Mode::NoEmbed => {
                 let file = File::open(target)?;
-                ArtifactId::id_reader(file)?
+                (ArtifactId::id_reader(file)?, None)
             }
         };
 
@@ -124,6 +192,7 @@ impl<H: SupportedHash, M: EmbeddingMode, S: Storage<H>> InputManifestBuilder<H,
             target_aid,
             manifest_aid,
             manifest,
+            embed_result,
         })
     }
 
@@ -143,6 +212,9 @@ pub struct LinkedInputManifest<H: SupportedHash> {
 
     /// The manifest.
     manifest: InputManifest<H>,
+
+    /// The outcome of embedding the manifest's ID, if in embedding mode.
+    embed_result: Option<EmbedResult<H>>,
 }
 
 impl<H: SupportedHash> Debug for LinkedInputManifest<H> {
@@ -151,6 +223,7 @@ impl<H: SupportedHash> Debug for LinkedInputManifest<H> {
             .field("target_aid", &self.target_aid)
             .field("manifest_aid", &self.manifest_aid)
             .field("manifest", &self.manifest)
+            .field("embed_result", &self.embed_result)
             .finish()
     }
 }
@@ -170,53 +243,241 @@ impl<H: SupportedHash> LinkedInputManifest<H> {
     pub fn manifest(&self) -> &InputManifest<H> {
         &self.manifest
     }
+
+    /// Get the outcome of embedding the manifest's ID in the target.
+    ///
+    /// This is `None` if the builder wasn't in embedding mode.
+    pub fn embed_result(&self) -> Option<EmbedResult<H>> {
+        self.embed_result
+    }
+}
+
+/// The outcome of embedding a manifest's [`ArtifactId`] in its target.
+pub enum EmbedResult<H: SupportedHash> {
+    /// The manifest's ID was written to the target.
+    Embedded(ArtifactId<H>),
+
+    /// The target already had the manifest's ID embedded, so it wasn't changed.
+    AlreadyEmbedded(ArtifactId<H>),
+}
+
+impl<H: SupportedHash> Clone for EmbedResult<H> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<H: SupportedHash> Copy for EmbedResult<H> {}
+
+impl<H: SupportedHash> PartialEq for EmbedResult<H> {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (EmbedResult::Embedded(a), EmbedResult::Embedded(b)) => a == b,
+            (EmbedResult::AlreadyEmbedded(a), EmbedResult::AlreadyEmbedded(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl<H: SupportedHash> Eq for EmbedResult<H> {}
+
+impl<H: SupportedHash> Debug for EmbedResult<H> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            EmbedResult::Embedded(aid) => f.debug_tuple("Embedded").field(aid).finish(),
+            EmbedResult::AlreadyEmbedded(aid) => {
+                f.debug_tuple("AlreadyEmbedded").field(aid).finish()
+            }
+        }
+    }
 }
 
 /// Embed the manifest's [`ArtifactId`] into the target file.
+///
+/// Returns the [`ArtifactId`] of the target after embedding.
 fn embed_manifest_in_target<H: SupportedHash>(
     path: &Path,
     file: &mut File,
     manifest_aid: ArtifactId<H>,
-) -> Result<ArtifactId<H>> {
-    match TargetType::infer(path, file) {
+    custom_prefixes: &HashMap<String, String>,
+) -> Result<(ArtifactId<H>, EmbedResult<H>)> {
+    match TargetType::infer(path, file, custom_prefixes) {
         TargetType::KnownBinaryType(BinaryType::ElfFile) => {
             embed_in_elf_file(path, file, manifest_aid)
         }
         TargetType::KnownTextType(TextType::PrefixComments { prefix }) => {
-            embed_in_text_file_with_prefix_comment(path, file, manifest_aid, &prefix)
+            embed_in_text_file_with_comment(file, manifest_aid, &prefix, "")
         }
         TargetType::KnownTextType(TextType::WrappedComments { prefix, suffix }) => {
-            embed_in_text_file_with_wrapped_comment(path, file, manifest_aid, &prefix, &suffix)
+            embed_in_text_file_with_comment(file, manifest_aid, &prefix, &suffix)
+        }
+        TargetType::KnownTextType(TextType::XmlProcessingInstruction) => {
+            embed_in_xml_file(path, file, manifest_aid)
+        }
+        #[cfg(feature = "jupyter-embed")]
+        TargetType::KnownTextType(TextType::JupyterNotebookMetadata) => {
+            embed_in_jupyter_notebook(path, file, manifest_aid)
         }
         TargetType::Unknown => Err(Error::UnknownEmbeddingTarget),
     }
 }
 
+/// Check for the manifest's [`ArtifactId`] in an ELF file.
+///
+/// Only files which already have the ID embedded are supported, since
+/// writing a new `.note.omnibor` section isn't implemented yet.
 fn embed_in_elf_file<H: SupportedHash>(
-    _path: &Path,
-    _file: &mut File,
-    _manifest_aid: ArtifactId<H>,
-) -> Result<ArtifactId<H>> {
-    todo!("embedding mode for ELF files is not yet implemented")
+    path: &Path,
+    file: &mut File,
+    manifest_aid: ArtifactId<H>,
+) -> Result<(ArtifactId<H>, EmbedResult<H>)> {
+    // Embedding the same manifest twice is a no-op, so builds can be re-run.
+    if read_embedded_aid_from_elf(path)? == Some(manifest_aid) {
+        file.rewind()?;
+        let target_aid = ArtifactId::id_reader(&mut *file)?;
+        return Ok((target_aid, EmbedResult::AlreadyEmbedded(manifest_aid)));
+    }
+
+    Err(Error::UnsupportedEmbeddingTarget("ELF"))
+}
+
+/// Embed the manifest's [`ArtifactId`] in a text file, in a comment on its
+/// own line at the end.
+///
+/// The file is rewritten in place and left positioned at the start. If the
+/// ID is already embedded after the same prefix, the file isn't changed.
+/// The `suffix` is only given for languages whose comments are wrapped.
+fn embed_in_text_file_with_comment<H: SupportedHash>(
+    file: &mut File,
+    manifest_aid: ArtifactId<H>,
+    prefix: &str,
+    suffix: &str,
+) -> Result<(ArtifactId<H>, EmbedResult<H>)> {
+    let mut content = String::new();
+    file.rewind()?;
+    file.read_to_string(&mut content)?;
+    file.rewind()?;
+
+    if find_embedded_aid_after_prefix(&content, prefix)? == Some(manifest_aid) {
+        return Ok((
+            ArtifactId::id_bytes(content),
+            EmbedResult::AlreadyEmbedded(manifest_aid),
+        ));
+    }
+
+    let embedded = insert_comment(&content, prefix, suffix, manifest_aid);
+
+    file.set_len(0)?;
+    file.write_all(embedded.as_bytes())?;
+    file.rewind()?;
+
+    Ok((
+        ArtifactId::id_bytes(embedded),
+        EmbedResult::Embedded(manifest_aid),
+    ))
+}
+
+/// Embed the manifest's [`ArtifactId`] in an XML file as a processing instruction.
+///
+/// The file is rewritten in place and left positioned at the start.
+fn embed_in_xml_file<H: SupportedHash>(
+    path: &Path,
+    file: &mut File,
+    manifest_aid: ArtifactId<H>,
+) -> Result<(ArtifactId<H>, EmbedResult<H>)> {
+    let mut content = String::new();
+    file.rewind()?;
+    file.read_to_string(&mut content)?;
+
+    let embedded = insert_xml_processing_instruction(&content, manifest_aid)
+        .ok_or_else(|| Error::NoXmlRootClosingTag(path.display().to_string()))?;
+
+    file.rewind()?;
+    file.set_len(0)?;
+    file.write_all(embedded.as_bytes())?;
+    file.rewind()?;
+
+    Ok((
+        ArtifactId::id_bytes(embedded),
+        EmbedResult::Embedded(manifest_aid),
+    ))
+}
+
+/// Embed the manifest's [`ArtifactId`] in a Jupyter notebook's metadata.
+///
+/// The file is rewritten in place and left positioned at the start.
+#[cfg(feature = "jupyter-embed")]
+fn embed_in_jupyter_notebook<H: SupportedHash>(
+    path: &Path,
+    file: &mut File,
+    manifest_aid: ArtifactId<H>,
+) -> Result<(ArtifactId<H>, EmbedResult<H>)> {
+    let mut content = String::new();
+    file.rewind()?;
+    file.read_to_string(&mut content)?;
+
+    let embedded = insert_notebook_metadata(&content, manifest_aid)
+        .map_err(|reason| Error::InvalidJupyterNotebook(path.display().to_string(), reason))?;
+
+    file.rewind()?;
+    file.set_len(0)?;
+    file.write_all(embedded.as_bytes())?;
+    file.rewind()?;
+
+    Ok((
+        ArtifactId::id_bytes(embedded),
+        EmbedResult::Embedded(manifest_aid),
+    ))
 }
 
-fn embed_in_text_file_with_prefix_comment<H: SupportedHash>(
-    _path: &Path,
-    _file: &mut File,
-    _manifest_aid: ArtifactId<H>,
-    _prefix: &str,
-) -> Result<ArtifactId<H>> {
-    todo!("embedding mode for text files is not yet implemented")
+/// Insert an `omnibor-input-manifest` processing instruction into an XML
+/// document, just before the root element's closing tag.
+///
+/// Inserting inside the root element, rather than at the end of the file,
+/// keeps the document well-formed. Returns `None` if the root element has
+/// no closing tag, for example if it's self-closing.
+fn insert_xml_processing_instruction<H: SupportedHash>(
+    content: &str,
+    manifest_aid: ArtifactId<H>,
+) -> Option<String> {
+    let index = find_xml_root_closing_tag(content)?;
+    let instruction = xml_processing_instruction(manifest_aid);
+
+    let mut embedded = String::with_capacity(content.len() + instruction.len());
+    embedded.push_str(&content[..index]);
+    embedded.push_str(&instruction);
+    embedded.push_str(&content[index..]);
+    Some(embedded)
 }
 
-fn embed_in_text_file_with_wrapped_comment<H: SupportedHash>(
-    _path: &Path,
-    _file: &mut File,
-    _manifest_aid: ArtifactId<H>,
-    _prefix: &str,
-    _suffix: &str,
-) -> Result<ArtifactId<H>> {
-    todo!("embedding mode for text files is not yet implemented")
+/// Find the start of the root element's closing tag, skipping any trailing
+/// whitespace, comments, and processing instructions.
+fn find_xml_root_closing_tag(content: &str) -> Option<usize> {
+    let mut end = content.trim_end().len();
+
+    loop {
+        let rest = &content[..end];
+
+        let start = if rest.ends_with("-->") {
+            rest.rfind("<!--")?
+        } else if rest.ends_with("?>") {
+            rest.rfind("<?")?
+        } else {
+            break;
+        };
+
+        end = content[..start].trim_end().len();
+    }
+
+    let rest = &content[..end];
+
+    if rest.ends_with('>').not() {
+        return None;
+    }
+
+    // The closing tag must run right up to the end, with no other tags after it.
+    let start = rest.rfind("</")?;
+    rest[start..end - 1].contains('>').not().then_some(start)
 }
 
 #[allow(unused)]
@@ -228,9 +489,131 @@ enum TargetType {
 }
 
 impl TargetType {
-    fn infer(_path: &Path, _file: &File) -> Self {
-        todo!("inferring target file type is not yet implemented")
+    fn infer(path: &Path, file: &File, custom_prefixes: &HashMap<String, String>) -> Self {
+        // Magic numbers are tried first, since they're unambiguous. Shebang
+        // lines are tried last, so scripts with an extension are typed by that.
+        TargetType::infer_from_magic(file)
+            .or_else(|| TargetType::infer_from_file_name(path))
+            .or_else(|| TargetType::infer_from_extension(path))
+            .or_else(|| TargetType::infer_from_custom_prefixes(path, custom_prefixes))
+            .or_else(|| TargetType::infer_from_shebang(file))
+            .unwrap_or(TargetType::Unknown)
     }
+
+    /// Infer the type of binary files from the magic number at their start.
+    ///
+    /// The file is left at the position it was in before.
+    fn infer_from_magic(mut file: &File) -> Option<Self> {
+        let start = file.stream_position().ok()?;
+        file.rewind().ok()?;
+
+        let mut magic = [0; 4];
+        let read = file.read_exact(&mut magic);
+        file.seek(SeekFrom::Start(start)).ok()?;
+        read.ok()?;
+
+        is_elf(&magic).then_some(TargetType::KnownBinaryType(BinaryType::ElfFile))
+    }
+
+    /// Infer the type of scripts from the interpreter in their shebang line,
+    /// for scripts without an extension.
+    ///
+    /// The file is left at the position it was in before.
+    fn infer_from_shebang(mut file: &File) -> Option<Self> {
+        let start = file.stream_position().ok()?;
+        file.rewind().ok()?;
+
+        // Shebang lines are limited in length by the kernel, so only read
+        // the start of the file.
+        let mut head = Vec::with_capacity(SHEBANG_MAX_LEN);
+        let read = file.take(SHEBANG_MAX_LEN as u64).read_to_end(&mut head);
+        file.seek(SeekFrom::Start(start)).ok()?;
+        read.ok()?;
+
+        let line = head.split(|b| *b == b'\n').next()?;
+        let interpreter = shebang_interpreter(std::str::from_utf8(line).ok()?)?;
+
+        // Ignore versions, like `python3` or `python3.12`.
+        let prefix = match interpreter.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.') {
+            "sh" | "bash" | "dash" | "ksh" | "mksh" | "zsh" | "fish" => "#",
+            "python" | "pypy" | "ruby" | "perl" | "tclsh" | "Rscript" | "julia" => "#",
+            "awk" | "gawk" | "mawk" => "#",
+            "node" | "deno" | "bun" => "//",
+            "lua" | "luajit" => "--",
+            _ => return None,
+        };
+
+        Some(TargetType::KnownTextType(TextType::PrefixComments {
+            prefix: prefix.to_owned(),
+        }))
+    }
+
+    /// Infer the type of files with an extension registered by the user.
+    fn infer_from_custom_prefixes(
+        path: &Path,
+        custom_prefixes: &HashMap<String, String>,
+    ) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        let prefix = custom_prefixes.get(&extension)?;
+
+        Some(TargetType::KnownTextType(TextType::PrefixComments {
+            prefix: prefix.clone(),
+        }))
+    }
+
+    /// Infer the type of files from their extension.
+    ///
+    /// Extensions are compared case-insensitively.
+    fn infer_from_extension(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_lowercase();
+
+        match extension.as_str() {
+            // XML, and XML-based formats like SVG, Maven POMs, and XSLT.
+            "xml" | "svg" | "pom" | "xsl" | "xslt" | "xsd" => Some(TargetType::KnownTextType(
+                TextType::XmlProcessingInstruction,
+            )),
+            // JSON has no comments, so notebooks record the ID in their metadata.
+            #[cfg(feature = "jupyter-embed")]
+            "ipynb" => Some(TargetType::KnownTextType(TextType::JupyterNotebookMetadata)),
+            _ => None,
+        }
+    }
+
+    /// Infer the type of well-known files which are identified by their name
+    /// rather than their extension, like `Makefile` or `CMakeLists.txt`.
+    ///
+    /// Names are compared case-insensitively, without any extension.
+    fn infer_from_file_name(path: &Path) -> Option<Self> {
+        let name = path.file_stem()?.to_str()?.to_lowercase();
+
+        let prefix = match name.as_str() {
+            // Make, CMake, Docker, Just, Ruby, and Vagrant all use `#` comments.
+            "makefile" | "gnumakefile" | "cmakelists" | "dockerfile" | "containerfile"
+            | "justfile" | "gemfile" | "rakefile" | "vagrantfile" => "#",
+            _ => return None,
+        };
+
+        Some(TargetType::KnownTextType(TextType::PrefixComments {
+            prefix: prefix.to_owned(),
+        }))
+    }
+}
+
+/// The most bytes read from the start of a file to find a shebang line.
+const SHEBANG_MAX_LEN: usize = 256;
+
+/// Get the name of the interpreter from a shebang line, like `python3` from
+/// `#!/usr/bin/env python3` or `bash` from `#!/bin/bash -e`.
+fn shebang_interpreter(line: &str) -> Option<&str> {
+    let mut words = line.strip_prefix("#!")?.split_whitespace();
+    let program = words.next()?.rsplit('/').next()?;
+
+    if program != "env" {
+        return Some(program);
+    }
+
+    // Skip any options and variables given to `env`, like `-S` or `A=1`.
+    words.find(|word| word.starts_with('-').not() && word.contains('=').not())
 }
 
 #[allow(unused)]
@@ -242,17 +625,32 @@ enum BinaryType {
 #[allow(unused)]
 #[derive(Debug)]
 enum TextType {
-    PrefixComments { prefix: String },
-    WrappedComments { prefix: String, suffix: String },
+    PrefixComments {
+        prefix: String,
+    },
+    WrappedComments {
+        prefix: String,
+        suffix: String,
+    },
+    XmlProcessingInstruction,
+    #[cfg(feature = "jupyter-embed")]
+    JupyterNotebookMetadata,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::elf::elf_with_notes;
+    use crate::elf::NOTE_NAME;
+    use crate::elf::NT_GITOID_SHA256;
+    use crate::embedding_mode::Embed;
     use crate::embedding_mode::NoEmbed;
     use crate::hashes::Sha256;
     use crate::storage::{FileSystemStorage, InMemoryStorage};
+    use crate::text_embedding;
+    use crate::text_embedding::read_embedded_aid_from_text;
     use pathbuf::pathbuf;
+    use std::fs;
     use std::str::FromStr;
 
     /// A basic builder test that creates a single manifest and validates it.
@@ -303,6 +701,8 @@ mod tests {
             first_input_aid.as_hex()
         );
 
+        assert_eq!(ids.embed_result(), None);
+
         // Make sure we update the target in the manifest.
         assert_eq!(
             ids.manifest.target().map(|target| target.as_hex()),
@@ -323,4 +723,325 @@ mod tests {
         basic_builder_test(&mut storage);
         storage.cleanup().unwrap();
     }
+
+    #[test]
+    fn infer_from_file_name_fallback() {
+        for name in [
+            "Makefile",
+            "CMakeLists.txt",
+            "Dockerfile",
+            "justfile",
+            "GNUmakefile",
+        ] {
+            assert!(
+                matches!(
+                    TargetType::infer_from_file_name(Path::new(name)),
+                    Some(TargetType::KnownTextType(TextType::PrefixComments { ref prefix })) if prefix == "#"
+                ),
+                "failed to infer type of '{}'",
+                name
+            );
+        }
+
+        assert!(TargetType::infer_from_file_name(Path::new("main.c")).is_none());
+    }
+
+    #[test]
+    fn embed_in_well_known_file() {
+        let dir = pathbuf![env!("CARGO_MANIFEST_DIR"), "test", "embed_makefile"];
+        fs::create_dir_all(&dir).unwrap();
+        let target = pathbuf![&dir, "Makefile"];
+        fs::write(&target, "all:\n\tcc main.c\n").unwrap();
+
+        let ids = InputManifestBuilder::<Sha256, Embed, _>::with_storage(InMemoryStorage::new())
+            .add_relation(ArtifactId::id_str("input"))
+            .unwrap()
+            .finish(&target, ShouldStore::Yes)
+            .unwrap();
+
+        let embedded = read_embedded_aid_from_text::<Sha256>(&target).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(embedded, Some(ids.manifest_aid));
+    }
+
+    #[test]
+    fn infer_xml_from_extension() {
+        for name in ["pom.xml", "logo.SVG", "transform.xslt", "project.pom"] {
+            assert!(
+                matches!(
+                    TargetType::infer(
+                        Path::new(name),
+                        &File::open("Cargo.toml").unwrap(),
+                        &HashMap::new()
+                    ),
+                    TargetType::KnownTextType(TextType::XmlProcessingInstruction)
+                ),
+                "failed to infer type of '{}'",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn parses_shebang_interpreter() {
+        for (line, interpreter) in [
+            ("#!/bin/sh", Some("sh")),
+            ("#!/bin/bash -e", Some("bash")),
+            ("#! /usr/bin/python3", Some("python3")),
+            ("#!/usr/bin/env ruby", Some("ruby")),
+            ("#!/usr/bin/env -S LANG=C perl -w", Some("perl")),
+            ("#!/usr/bin/env", None),
+            ("# just a comment", None),
+        ] {
+            assert_eq!(shebang_interpreter(line), interpreter, "{}", line);
+        }
+    }
+
+    #[test]
+    fn infer_from_shebang_without_extension() {
+        let path = pathbuf![env!("CARGO_MANIFEST_DIR"), "test", "shebang_script"];
+
+        for (content, expected) in [
+            ("#!/usr/bin/env python3.12\nprint('hi')\n", Some("#")),
+            ("#!/bin/bash\necho hi\n", Some("#")),
+            ("#!/usr/bin/env node\nconsole.log('hi')\n", Some("//")),
+            ("#!/usr/bin/unknown\n", None),
+            ("echo hi\n", None),
+        ] {
+            fs::write(&path, content).unwrap();
+            let mut file = File::open(&path).unwrap();
+            file.seek(SeekFrom::Start(2)).unwrap();
+
+            let prefix = match TargetType::infer(&path, &file, &HashMap::new()) {
+                TargetType::KnownTextType(TextType::PrefixComments { prefix }) => Some(prefix),
+                _ => None,
+            };
+
+            assert_eq!(prefix.as_deref(), expected, "{}", content);
+            assert_eq!(file.stream_position().unwrap(), 2);
+        }
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn embed_in_shebang_script() {
+        let target = pathbuf![env!("CARGO_MANIFEST_DIR"), "test", "embed_script"];
+        fs::write(&target, "#!/usr/bin/env python3\nprint('hi')\n").unwrap();
+
+        let ids = InputManifestBuilder::<Sha256, Embed, _>::with_storage(InMemoryStorage::new())
+            .add_relation(ArtifactId::id_str("input"))
+            .unwrap()
+            .finish(&target, ShouldStore::Yes)
+            .unwrap();
+
+        let content = fs::read_to_string(&target).unwrap();
+        fs::remove_file(&target).unwrap();
+
+        // The shebang line has to stay first for the script to run.
+        assert!(content.starts_with("#!/usr/bin/env python3\n"));
+        assert_eq!(
+            text_embedding::find_embedded_aid(&content).unwrap(),
+            Some(ids.manifest_aid)
+        );
+    }
+
+    #[test]
+    fn infer_from_custom_prefix() {
+        let mut builder =
+            InputManifestBuilder::<Sha256, Embed, _>::with_storage(InMemoryStorage::new());
+        builder
+            .with_custom_comment_prefix("DSL", "!!")
+            .with_custom_comment_prefix("xml", "!!");
+        let file = File::open("Cargo.toml").unwrap();
+
+        assert!(matches!(
+            TargetType::infer(Path::new("rules.dsl"), &file, &builder.custom_prefixes),
+            TargetType::KnownTextType(TextType::PrefixComments { ref prefix }) if prefix == "!!"
+        ));
+
+        // Built-in types win over custom prefixes.
+        assert!(matches!(
+            TargetType::infer(Path::new("pom.xml"), &file, &builder.custom_prefixes),
+            TargetType::KnownTextType(TextType::XmlProcessingInstruction)
+        ));
+
+        assert!(matches!(
+            TargetType::infer(Path::new("main.c"), &file, &builder.custom_prefixes),
+            TargetType::Unknown
+        ));
+    }
+
+    #[test]
+    fn embed_with_custom_comment_prefix() {
+        let target = pathbuf![env!("CARGO_MANIFEST_DIR"), "test", "embed_target.dsl"];
+        fs::write(&target, "rule a\n").unwrap();
+
+        let mut builder =
+            InputManifestBuilder::<Sha256, Embed, _>::with_storage(InMemoryStorage::new());
+        builder.with_custom_comment_prefix("dsl", "!!");
+        let mut build = || {
+            builder
+                .add_relation(ArtifactId::id_str("input"))
+                .unwrap()
+                .finish(&target, ShouldStore::Yes)
+                .unwrap()
+        };
+
+        let ids = build();
+        let content = fs::read_to_string(&target).unwrap();
+        assert_eq!(
+            content,
+            format!("rule a\n!! OmniBOR-Input-Manifest: {}\n", ids.manifest_aid)
+        );
+        assert_eq!(ids.target_aid, ArtifactId::id_str(&content));
+        assert_eq!(
+            ids.embed_result(),
+            Some(EmbedResult::Embedded(ids.manifest_aid))
+        );
+
+        // Embedding the same manifest again leaves the file as it was.
+        let again = build();
+        fs::remove_file(&target).unwrap();
+        assert_eq!(
+            again.embed_result(),
+            Some(EmbedResult::AlreadyEmbedded(ids.manifest_aid))
+        );
+        assert_eq!(again.target_aid, ids.target_aid);
+    }
+
+    #[test]
+    fn xml_instruction_goes_before_root_closing_tag() {
+        let aid = ArtifactId::<Sha256>::id_str("manifest");
+        let instruction = format!("<?omnibor-input-manifest aid=\"{}\"?>", aid);
+
+        let content =
+            "<?xml version=\"1.0\"?>\n<project>\n  <a></a>\n</project>\n<!-- </done> -->\n";
+        let embedded = insert_xml_processing_instruction(content, aid).unwrap();
+        assert_eq!(
+            embedded,
+            format!(
+                "<?xml version=\"1.0\"?>\n<project>\n  <a></a>\n{}</project>\n<!-- </done> -->\n",
+                instruction
+            )
+        );
+
+        assert!(insert_xml_processing_instruction("<root/>", aid).is_none());
+        assert!(insert_xml_processing_instruction("not xml", aid).is_none());
+    }
+
+    #[test]
+    fn embed_in_xml_target() {
+        let target = pathbuf![env!("CARGO_MANIFEST_DIR"), "test", "embed_target.xml"];
+        std::fs::write(&target, "<project>\n</project>\n").unwrap();
+
+        let ids = InputManifestBuilder::<Sha256, Embed, _>::with_storage(InMemoryStorage::new())
+            .add_relation(ArtifactId::id_str("input"))
+            .unwrap()
+            .finish(&target, ShouldStore::Yes)
+            .unwrap();
+
+        let content = std::fs::read_to_string(&target).unwrap();
+        std::fs::remove_file(&target).unwrap();
+
+        assert_eq!(
+            content,
+            format!(
+                "<project>\n<?omnibor-input-manifest aid=\"{}\"?></project>\n",
+                ids.manifest_aid
+            )
+        );
+        assert_eq!(ids.target_aid, ArtifactId::id_str(&content));
+        assert_eq!(
+            ids.embed_result(),
+            Some(EmbedResult::Embedded(ids.manifest_aid))
+        );
+        assert_eq!(
+            text_embedding::find_embedded_aid(&content).unwrap(),
+            Some(ids.manifest_aid)
+        );
+    }
+
+    #[test]
+    fn embed_in_elf_target() {
+        let target = pathbuf![env!("CARGO_MANIFEST_DIR"), "test", "embed_target.elf"];
+        let input = ArtifactId::<Sha256>::id_str("input");
+        let manifest = InputManifest::with_relations([Relation::from_artifact(input)].into_iter());
+        let manifest_aid = ArtifactId::id_manifest(&manifest).unwrap();
+
+        let build = || {
+            InputManifestBuilder::<Sha256, Embed, _>::with_storage(InMemoryStorage::new())
+                .add_relation(input)
+                .unwrap()
+                .finish(&target, ShouldStore::Yes)
+        };
+
+        let elf = elf_with_notes(&[(NOTE_NAME, NT_GITOID_SHA256, manifest_aid.as_bytes())]);
+        fs::write(&target, &elf).unwrap();
+        let ids = build().unwrap();
+        assert_eq!(
+            ids.embed_result(),
+            Some(EmbedResult::AlreadyEmbedded(manifest_aid))
+        );
+        assert_eq!(ids.target_aid(), ArtifactId::id_bytes(&elf));
+        assert_eq!(fs::read(&target).unwrap(), elf);
+
+        fs::write(&target, elf_with_notes(&[])).unwrap();
+        let result = build();
+        fs::remove_file(&target).unwrap();
+        assert!(matches!(
+            result,
+            Err(Error::UnsupportedEmbeddingTarget("ELF"))
+        ));
+    }
+
+    #[cfg(feature = "jupyter-embed")]
+    #[test]
+    fn embed_in_jupyter_target() {
+        let target = pathbuf![env!("CARGO_MANIFEST_DIR"), "test", "embed_target.ipynb"];
+        std::fs::write(&target, "{\"cells\": [], \"nbformat\": 4}").unwrap();
+
+        let ids = InputManifestBuilder::<Sha256, Embed, _>::with_storage(InMemoryStorage::new())
+            .add_relation(ArtifactId::id_str("input"))
+            .unwrap()
+            .finish(&target, ShouldStore::Yes)
+            .unwrap();
+
+        let content = std::fs::read_to_string(&target).unwrap();
+        std::fs::remove_file(&target).unwrap();
+
+        let notebook: serde_json::Value = serde_json::from_str(&content).unwrap();
+        assert_eq!(
+            notebook["metadata"]["omnibor_input_manifest"],
+            ids.manifest_aid.to_string()
+        );
+        assert_eq!(ids.target_aid, ArtifactId::id_str(&content));
+    }
+
+    #[cfg(feature = "env-inputs")]
+    #[test]
+    fn add_env_var_identifies_value() {
+        // Look variables up in a fixed map, since setting real ones races
+        // with other tests.
+        let lookup = |name: &str| match name {
+            "CFLAGS" => Ok(String::from("-O2 -g")),
+            _ => Err(VarError::NotPresent),
+        };
+
+        let mut builder =
+            InputManifestBuilder::<Sha256, NoEmbed, _>::with_storage(InMemoryStorage::new());
+        builder.add_env_var_with("CFLAGS", lookup).unwrap();
+
+        let artifacts: Vec<_> = builder.relations.iter().map(Relation::artifact).collect();
+        assert_eq!(artifacts, vec![ArtifactId::id_str("-O2 -g")]);
+
+        assert!(matches!(
+            builder.add_env_var_with("UNSET", lookup),
+            Err(Error::CantReadEnvVar(..))
+        ));
+        assert!(matches!(
+            builder.add_env_var("OMNIBOR_TEST_UNSET_ENV_VAR"),
+            Err(Error::CantReadEnvVar(..))
+        ));
+    }
 }
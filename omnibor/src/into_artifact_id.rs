@@ -1,11 +1,62 @@
 use crate::supported_hash::SupportedHash;
 use crate::ArtifactId;
-#[cfg(doc)]
+#[cfg(feature = "std")]
+use crate::Error;
+#[cfg(all(doc, feature = "std"))]
 use crate::InputManifestBuilder;
 use crate::Result;
+#[cfg(feature = "map-identify")]
+use alloc::collections::BTreeMap;
+#[cfg(feature = "map-identify")]
+use alloc::string::String;
+#[cfg(feature = "semver")]
+use alloc::string::ToString as _;
+#[cfg(feature = "map-identify")]
+use alloc::vec::Vec;
+#[cfg(feature = "bytes")]
+use bytes::Bytes;
+#[cfg(feature = "bzip2")]
+use bzip2::read::BzDecoder;
+#[cfg(feature = "gzip")]
+use flate2::read::GzDecoder;
+#[cfg(feature = "gzip")]
+use flate2::read::ZlibDecoder;
+#[cfg(feature = "gzip")]
+use flate2::write::GzEncoder;
+#[cfg(feature = "http")]
+use http::Response;
+#[cfg(feature = "ndarray")]
+use ndarray::ArrayD;
+#[cfg(feature = "ndarray")]
+use ndarray::ArrayViewD;
+#[cfg(feature = "semver")]
+use semver::Version;
+#[cfg(feature = "semver")]
+use semver::VersionReq;
+#[cfg(all(feature = "map-identify", feature = "std"))]
+use std::collections::HashMap;
+#[cfg(feature = "std")]
+use std::fs::DirEntry;
+#[cfg(feature = "std")]
 use std::fs::File;
+#[cfg(feature = "std")]
 use std::io::BufReader;
+#[cfg(feature = "std")]
+use std::io::Cursor;
+#[cfg(any(feature = "gzip", feature = "bzip2"))]
+use std::io::Read;
+#[cfg(feature = "process-identify")]
+use std::ops::Not as _;
+#[cfg(feature = "std")]
 use std::path::Path;
+#[cfg(feature = "cmd-identify")]
+use std::process::Command;
+#[cfg(feature = "process-identify")]
+use std::process::Output;
+#[cfg(feature = "tempfile")]
+use tempfile::NamedTempFile;
+#[cfg(feature = "std")]
+use walkdir::DirEntry as WalkDirEntry;
 
 /// Types that can produce an [`ArtifactId`].
 ///
@@ -22,12 +73,60 @@ impl<H: SupportedHash> IntoArtifactId<H> for ArtifactId<H> {
     }
 }
 
+#[cfg(feature = "std")]
 impl<H: SupportedHash> IntoArtifactId<H> for &Path {
     fn into_artifact_id(self) -> Result<ArtifactId<H>> {
         File::open(self)?.into_artifact_id()
     }
 }
 
+/// Identify the file a directory entry refers to, following symlinks.
+///
+/// Directories can't be identified, and give an [`Error`].
+#[cfg(feature = "std")]
+impl<H: SupportedHash> IntoArtifactId<H> for &DirEntry {
+    fn into_artifact_id(self) -> Result<ArtifactId<H>> {
+        let path = self.path();
+
+        if path.is_dir() {
+            return Err(Error::CannotIdentifyDirectory(path.display().to_string()));
+        }
+
+        path.as_path().into_artifact_id()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<H: SupportedHash> IntoArtifactId<H> for DirEntry {
+    fn into_artifact_id(self) -> Result<ArtifactId<H>> {
+        (&self).into_artifact_id()
+    }
+}
+
+/// Identify the file a [`walkdir`] entry refers to, following symlinks.
+///
+/// Directories can't be identified, and give an [`Error`].
+#[cfg(feature = "std")]
+impl<H: SupportedHash> IntoArtifactId<H> for &WalkDirEntry {
+    fn into_artifact_id(self) -> Result<ArtifactId<H>> {
+        let path = self.path();
+
+        if path.is_dir() {
+            return Err(Error::CannotIdentifyDirectory(path.display().to_string()));
+        }
+
+        path.into_artifact_id()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<H: SupportedHash> IntoArtifactId<H> for WalkDirEntry {
+    fn into_artifact_id(self) -> Result<ArtifactId<H>> {
+        (&self).into_artifact_id()
+    }
+}
+
+#[cfg(feature = "std")]
 impl<H: SupportedHash> IntoArtifactId<H> for File {
     fn into_artifact_id(self) -> Result<ArtifactId<H>> {
         let file = BufReader::new(self);
@@ -35,8 +134,340 @@ impl<H: SupportedHash> IntoArtifactId<H> for File {
     }
 }
 
+#[cfg(feature = "std")]
+impl<H: SupportedHash, T: AsRef<[u8]>> IntoArtifactId<H> for Cursor<T> {
+    fn into_artifact_id(self) -> Result<ArtifactId<H>> {
+        ArtifactId::id_reader(self)
+    }
+}
+
 impl<H: SupportedHash> IntoArtifactId<H> for &[u8] {
     fn into_artifact_id(self) -> Result<ArtifactId<H>> {
         Ok(ArtifactId::id_bytes(self))
     }
 }
+
+impl<H: SupportedHash, const N: usize> IntoArtifactId<H> for [u8; N] {
+    fn into_artifact_id(self) -> Result<ArtifactId<H>> {
+        Ok(ArtifactId::id_bytes(self))
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl<H: SupportedHash> IntoArtifactId<H> for Bytes {
+    fn into_artifact_id(self) -> Result<ArtifactId<H>> {
+        (&self).into_artifact_id()
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl<H: SupportedHash> IntoArtifactId<H> for &Bytes {
+    fn into_artifact_id(self) -> Result<ArtifactId<H>> {
+        self.as_ref().into_artifact_id()
+    }
+}
+
+/// Identifies the response body; headers are not included.
+#[cfg(feature = "http")]
+impl<H: SupportedHash> IntoArtifactId<H> for Response<Bytes> {
+    fn into_artifact_id(self) -> Result<ArtifactId<H>> {
+        self.into_body().into_artifact_id()
+    }
+}
+
+/// Identifies the response body; headers are not included.
+#[cfg(feature = "http")]
+impl<H: SupportedHash> IntoArtifactId<H> for &Response<Bytes> {
+    fn into_artifact_id(self) -> Result<ArtifactId<H>> {
+        self.body().into_artifact_id()
+    }
+}
+
+/// Identifies the response body; headers are not included.
+///
+/// The whole body is read into memory before being identified.
+#[cfg(feature = "reqwest")]
+impl<H: SupportedHash> IntoArtifactId<H> for reqwest::blocking::Response {
+    fn into_artifact_id(self) -> Result<ArtifactId<H>> {
+        let body = self.bytes().map_err(Error::CantReadResponseBody)?;
+        Ok(ArtifactId::id_bytes(body))
+    }
+}
+
+/// Identifies the canonical string form of the version, like `1.2.3`.
+#[cfg(feature = "semver")]
+impl<H: SupportedHash> IntoArtifactId<H> for &Version {
+    fn into_artifact_id(self) -> Result<ArtifactId<H>> {
+        Ok(ArtifactId::id_str(self.to_string()))
+    }
+}
+
+/// Identifies the canonical string form of the version, like `1.2.3`.
+#[cfg(feature = "semver")]
+impl<H: SupportedHash> IntoArtifactId<H> for Version {
+    fn into_artifact_id(self) -> Result<ArtifactId<H>> {
+        (&self).into_artifact_id()
+    }
+}
+
+/// Identifies the canonical string form of the requirement, like `^1.2.3`.
+///
+/// Requirements are normalized when parsed, so `>= 1.2` and `>=1.2` give
+/// the same [`ArtifactId`].
+#[cfg(feature = "semver")]
+impl<H: SupportedHash> IntoArtifactId<H> for &VersionReq {
+    fn into_artifact_id(self) -> Result<ArtifactId<H>> {
+        Ok(ArtifactId::id_str(self.to_string()))
+    }
+}
+
+/// Identifies the canonical string form of the requirement, like `^1.2.3`.
+#[cfg(feature = "semver")]
+impl<H: SupportedHash> IntoArtifactId<H> for VersionReq {
+    fn into_artifact_id(self) -> Result<ArtifactId<H>> {
+        (&self).into_artifact_id()
+    }
+}
+
+/// Identifies a map of named binary artifacts.
+///
+/// The map is serialized as each key followed by a NUL byte, then its value
+/// followed by a NUL byte (`key\0value\0`), for every entry in ascending
+/// key order, all concatenated. The [`ArtifactId`] is that of the result, so
+/// two maps with the same entries always have the same ID, regardless of how
+/// they were built.
+///
+/// Keys or values which themselves contain NUL bytes can make two different
+/// maps serialize the same way, so avoid them where that matters.
+#[cfg(feature = "map-identify")]
+impl<H: SupportedHash> IntoArtifactId<H> for &BTreeMap<String, Vec<u8>> {
+    fn into_artifact_id(self) -> Result<ArtifactId<H>> {
+        Ok(id_map(self.iter()))
+    }
+}
+
+/// Identifies a map of named binary artifacts.
+///
+/// See the implementation for `&BTreeMap<String, Vec<u8>>` for the format.
+#[cfg(feature = "map-identify")]
+impl<H: SupportedHash> IntoArtifactId<H> for BTreeMap<String, Vec<u8>> {
+    fn into_artifact_id(self) -> Result<ArtifactId<H>> {
+        (&self).into_artifact_id()
+    }
+}
+
+/// Identifies a map of named binary artifacts.
+///
+/// Entries are sorted by key first, so this gives the same [`ArtifactId`] as
+/// a `BTreeMap` with the same entries. See the implementation for
+/// `&BTreeMap<String, Vec<u8>>` for the format.
+#[cfg(all(feature = "map-identify", feature = "std"))]
+impl<H: SupportedHash> IntoArtifactId<H> for &HashMap<String, Vec<u8>> {
+    fn into_artifact_id(self) -> Result<ArtifactId<H>> {
+        let mut entries = self.iter().collect::<Vec<_>>();
+        entries.sort_unstable_by_key(|(key, _)| *key);
+        Ok(id_map(entries.into_iter()))
+    }
+}
+
+/// Identifies a map of named binary artifacts.
+///
+/// See the implementation for `&HashMap<String, Vec<u8>>`.
+#[cfg(all(feature = "map-identify", feature = "std"))]
+impl<H: SupportedHash> IntoArtifactId<H> for HashMap<String, Vec<u8>> {
+    fn into_artifact_id(self) -> Result<ArtifactId<H>> {
+        (&self).into_artifact_id()
+    }
+}
+
+/// Identify map entries, which must already be sorted by key.
+#[cfg(feature = "map-identify")]
+fn id_map<'a, H: SupportedHash>(
+    entries: impl Iterator<Item = (&'a String, &'a Vec<u8>)>,
+) -> ArtifactId<H> {
+    let mut content = Vec::new();
+
+    for (key, value) in entries {
+        content.extend_from_slice(key.as_bytes());
+        content.push(0);
+        content.extend_from_slice(value);
+        content.push(0);
+    }
+
+    ArtifactId::id_bytes(content)
+}
+
+/// Identifies the program and arguments of a command.
+///
+/// The command is serialized as the program followed by a NUL byte, then
+/// each argument followed by a NUL byte (`program\0arg1\0arg2\0`), all
+/// concatenated. The [`ArtifactId`] is that of the result, so changing the
+/// program or any argument changes the ID. The environment and working
+/// directory are not included.
+///
+/// The program and arguments are identified as their platform encoding,
+/// which is UTF-8 when they're valid Unicode. Arguments which themselves
+/// contain NUL bytes can't be passed to a process, so the format isn't
+/// ambiguous.
+#[cfg(feature = "cmd-identify")]
+impl<H: SupportedHash> IntoArtifactId<H> for &Command {
+    fn into_artifact_id(self) -> Result<ArtifactId<H>> {
+        let mut content = Vec::new();
+
+        for part in std::iter::once(self.get_program()).chain(self.get_args()) {
+            content.extend_from_slice(part.as_encoded_bytes());
+            content.push(0);
+        }
+
+        Ok(ArtifactId::id_bytes(content))
+    }
+}
+
+/// Identifies the standard output of a finished command.
+///
+/// Standard error isn't included. Commands which didn't exit successfully
+/// give an [`Error`] instead, since their output may be incomplete.
+#[cfg(feature = "process-identify")]
+impl<H: SupportedHash> IntoArtifactId<H> for &Output {
+    fn into_artifact_id(self) -> Result<ArtifactId<H>> {
+        if self.status.success().not() {
+            return Err(Error::NonZeroExitStatus(self.status));
+        }
+
+        Ok(ArtifactId::id_bytes(&self.stdout))
+    }
+}
+
+#[cfg(feature = "process-identify")]
+impl<H: SupportedHash> IntoArtifactId<H> for Output {
+    fn into_artifact_id(self) -> Result<ArtifactId<H>> {
+        (&self).into_artifact_id()
+    }
+}
+
+/// Identifies an array of bytes along with its shape.
+///
+/// The array is serialized as its number of dimensions, then the length of
+/// each dimension, all as little-endian `u64`s, followed by the elements in
+/// row-major (C) order. The [`ArtifactId`] is that of the result, so arrays
+/// with the same elements but different shapes have different IDs, while the
+/// memory layout of the array doesn't matter.
+#[cfg(feature = "ndarray")]
+impl<H: SupportedHash> IntoArtifactId<H> for ArrayViewD<'_, u8> {
+    fn into_artifact_id(self) -> Result<ArtifactId<H>> {
+        let mut content = Vec::with_capacity(8 * (self.ndim() + 1) + self.len());
+
+        content.extend_from_slice(&(self.ndim() as u64).to_le_bytes());
+
+        for dim in self.shape() {
+            content.extend_from_slice(&(*dim as u64).to_le_bytes());
+        }
+
+        match self.as_slice() {
+            Some(elements) => content.extend_from_slice(elements),
+            // Not contiguous in row-major order, so copy element by element.
+            None => content.extend(self.iter()),
+        }
+
+        Ok(ArtifactId::id_bytes(content))
+    }
+}
+
+/// Identifies an array of bytes along with its shape.
+///
+/// See the implementation for `ArrayViewD<'_, u8>` for the format.
+#[cfg(feature = "ndarray")]
+impl<H: SupportedHash> IntoArtifactId<H> for &ArrayD<u8> {
+    fn into_artifact_id(self) -> Result<ArtifactId<H>> {
+        self.view().into_artifact_id()
+    }
+}
+
+/// Identifies an array of bytes along with its shape.
+///
+/// See the implementation for `ArrayViewD<'_, u8>` for the format.
+#[cfg(feature = "ndarray")]
+impl<H: SupportedHash> IntoArtifactId<H> for ArrayD<u8> {
+    fn into_artifact_id(self) -> Result<ArtifactId<H>> {
+        (&self).into_artifact_id()
+    }
+}
+
+/// Identifies the current contents of the file, read from the start.
+#[cfg(feature = "tempfile")]
+impl<H: SupportedHash> IntoArtifactId<H> for NamedTempFile {
+    fn into_artifact_id(self) -> Result<ArtifactId<H>> {
+        (&self).into_artifact_id()
+    }
+}
+
+/// Identifies the current contents of the file, read from the start.
+#[cfg(feature = "tempfile")]
+impl<H: SupportedHash> IntoArtifactId<H> for &NamedTempFile {
+    fn into_artifact_id(self) -> Result<ArtifactId<H>> {
+        // Reopen so we read from the start regardless of the write position.
+        self.reopen()?.into_artifact_id()
+    }
+}
+
+/// Identifies the decompressed contents of a gzip stream.
+///
+/// The whole stream is decompressed into memory, so the caller must ensure
+/// the full compressed content is available to the reader; a truncated
+/// stream produces an error.
+#[cfg(feature = "gzip")]
+impl<H: SupportedHash, R: Read> IntoArtifactId<H> for GzDecoder<R> {
+    fn into_artifact_id(self) -> Result<ArtifactId<H>> {
+        id_decompressed(self)
+    }
+}
+
+/// Identifies the decompressed contents of a zlib stream.
+///
+/// The whole stream is decompressed into memory, so the caller must ensure
+/// the full compressed content is available to the reader; a truncated
+/// stream produces an error.
+#[cfg(feature = "gzip")]
+impl<H: SupportedHash, R: Read> IntoArtifactId<H> for ZlibDecoder<R> {
+    fn into_artifact_id(self) -> Result<ArtifactId<H>> {
+        id_decompressed(self)
+    }
+}
+
+/// Identifies the compressed bytes of a gzip stream, for artifacts whose
+/// canonical form is compressed.
+///
+/// The encoder is finished, writing the gzip trailer, before the bytes are
+/// identified. The compressed bytes depend on the compression level and on
+/// the `flate2` backend, so the same content compressed with different
+/// settings gets a different [`ArtifactId`]. Use a [`GzDecoder`] to
+/// identify the uncompressed content instead.
+#[cfg(feature = "gzip")]
+impl<H: SupportedHash> IntoArtifactId<H> for GzEncoder<Vec<u8>> {
+    fn into_artifact_id(self) -> Result<ArtifactId<H>> {
+        Ok(ArtifactId::id_bytes(self.finish()?))
+    }
+}
+
+/// Identifies the decompressed contents of a bzip2 stream.
+///
+/// The whole stream is decompressed into memory, so the caller must ensure
+/// the full compressed content is available to the reader; a truncated
+/// stream produces an error.
+#[cfg(feature = "bzip2")]
+impl<H: SupportedHash, R: Read> IntoArtifactId<H> for BzDecoder<R> {
+    fn into_artifact_id(self) -> Result<ArtifactId<H>> {
+        id_decompressed(self)
+    }
+}
+
+/// Read a decompressor to its end and identify the decompressed bytes.
+///
+/// The decompressed length isn't known up front, so the content is
+/// buffered rather than streamed through the hasher.
+#[cfg(any(feature = "gzip", feature = "bzip2"))]
+fn id_decompressed<H: SupportedHash, R: Read>(mut reader: R) -> Result<ArtifactId<H>> {
+    let mut content = Vec::new();
+    reader.read_to_end(&mut content)?;
+    Ok(ArtifactId::id_bytes(content))
+}
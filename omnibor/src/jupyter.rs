@@ -0,0 +1,160 @@
+//! Embed and read manifest [`ArtifactId`]s in Jupyter notebooks.
+//!
+//! Jupyter notebooks are JSON documents, which have no comments to embed an
+//! [`ArtifactId`] in. Instead, it's recorded in the notebook's top-level
+//! `metadata` object, which Jupyter preserves when saving:
+//!
+//! ```json
+//! {
+//!  "metadata": {
+//!   "omnibor_input_manifest": "gitoid:blob:sha256:..."
+//!  },
+//!  "nbformat": 4
+//! }
+//! ```
+
+use crate::hashes::SupportedHash;
+use crate::ArtifactId;
+use gitoid::HashAlgorithm;
+use serde::Serialize as _;
+use serde_json::ser::PrettyFormatter;
+use serde_json::Map;
+use serde_json::Serializer;
+use serde_json::Value;
+use std::ops::Not as _;
+use std::path::Path;
+use std::result::Result as StdResult;
+use std::str::FromStr;
+
+/// The `metadata` key holding the manifest [`ArtifactId`].
+pub(crate) const METADATA_KEY: &str = "omnibor_input_manifest";
+
+/// Record the manifest [`ArtifactId`] in a notebook's top-level `metadata`.
+///
+/// Any existing ID is replaced. The notebook is written back the way Jupyter
+/// writes it, with sorted keys, one-space indents, and a trailing newline, so
+/// embedding doesn't reformat the rest of the file. Returns an error if the
+/// content isn't a notebook, meaning a JSON object with an `nbformat` key.
+pub(crate) fn insert_notebook_metadata<H: SupportedHash>(
+    content: &str,
+    manifest_aid: ArtifactId<H>,
+) -> StdResult<String, &'static str> {
+    let mut notebook: Map<String, Value> =
+        serde_json::from_str(content).map_err(|_| "not a JSON object")?;
+
+    if notebook.contains_key("nbformat").not() {
+        return Err("missing 'nbformat' key");
+    }
+
+    let metadata = notebook
+        .entry("metadata")
+        .or_insert_with(|| Value::Object(Map::new()));
+
+    let Value::Object(metadata) = metadata else {
+        return Err("'metadata' isn't an object");
+    };
+
+    metadata.insert(
+        METADATA_KEY.to_owned(),
+        Value::String(manifest_aid.to_string()),
+    );
+
+    let mut embedded = Vec::with_capacity(content.len());
+    let mut serializer =
+        Serializer::with_formatter(&mut embedded, PrettyFormatter::with_indent(b" "));
+    notebook
+        .serialize(&mut serializer)
+        .map_err(|_| "can't serialize notebook")?;
+    embedded.push(b'\n');
+
+    String::from_utf8(embedded).map_err(|_| "can't serialize notebook")
+}
+
+/// Read the manifest [`ArtifactId`] for `H` from a notebook's top-level
+/// `metadata`, if it has one.
+///
+/// An ID for a different hash algorithm is treated as missing. Returns an
+/// error if the content isn't a notebook, or the ID is malformed.
+pub(crate) fn find_notebook_metadata<H: SupportedHash>(
+    content: &str,
+) -> StdResult<Option<ArtifactId<H>>, &'static str> {
+    let notebook: Map<String, Value> =
+        serde_json::from_str(content).map_err(|_| "not a JSON object")?;
+
+    if notebook.contains_key("nbformat").not() {
+        return Err("missing 'nbformat' key");
+    }
+
+    let Some(aid) = notebook
+        .get("metadata")
+        .and_then(|metadata| metadata.get(METADATA_KEY))
+        .and_then(Value::as_str)
+    else {
+        return Ok(None);
+    };
+
+    let scheme = format!("gitoid:blob:{}:", H::HashAlgorithm::NAME);
+    if aid.starts_with(&scheme).not() {
+        return Ok(None);
+    }
+
+    ArtifactId::from_str(aid)
+        .map(Some)
+        .map_err(|_| "invalid manifest ID in metadata")
+}
+
+/// Check if a path is for a Jupyter notebook, by its extension.
+pub(crate) fn is_notebook_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("ipynb"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hashes::Sha256;
+
+    #[test]
+    fn inserts_into_existing_metadata() {
+        let aid = ArtifactId::<Sha256>::id_str("manifest");
+        let content = r#"{"cells": [], "metadata": {"kernelspec": {}}, "nbformat": 4}"#;
+        let embedded = insert_notebook_metadata(content, aid).unwrap();
+
+        assert_eq!(
+            embedded,
+            format!(
+                "{{\n \"cells\": [],\n \"metadata\": {{\n  \"kernelspec\": {{}},\n  \"{}\": \"{}\"\n }},\n \"nbformat\": 4\n}}\n",
+                METADATA_KEY, aid
+            )
+        );
+    }
+
+    #[test]
+    fn creates_missing_metadata() {
+        let aid = ArtifactId::<Sha256>::id_str("manifest");
+        let embedded = insert_notebook_metadata(r#"{"nbformat": 4}"#, aid).unwrap();
+        let notebook: Value = serde_json::from_str(&embedded).unwrap();
+        assert_eq!(notebook["metadata"][METADATA_KEY], aid.to_string());
+    }
+
+    #[test]
+    fn finds_inserted_metadata() {
+        let aid = ArtifactId::<Sha256>::id_str("manifest");
+        let embedded = insert_notebook_metadata(r#"{"nbformat": 4}"#, aid).unwrap();
+        assert_eq!(find_notebook_metadata(&embedded), Ok(Some(aid)));
+        assert_eq!(
+            find_notebook_metadata::<Sha256>(r#"{"nbformat": 4}"#),
+            Ok(None)
+        );
+        assert!(find_notebook_metadata::<Sha256>(r#"{"cells": []}"#).is_err());
+    }
+
+    #[test]
+    fn rejects_non_notebooks() {
+        let aid = ArtifactId::<Sha256>::id_str("manifest");
+        assert!(insert_notebook_metadata("[1, 2]", aid).is_err());
+        assert!(insert_notebook_metadata(r#"{"cells": []}"#, aid).is_err());
+        assert!(insert_notebook_metadata(r#"{"nbformat": 4, "metadata": 1}"#, aid).is_err());
+    }
+}
@@ -30,6 +30,22 @@
 //! in its 2023 report ["Software Identification Ecosystem Option
 //! Analysis."][cisa_report]
 //!
+//! ## `no_std` Support
+//!
+//! The `std` feature is enabled by default. When it's disabled, the crate
+//! is `no_std`-compatible and provides only pure-compute [`ArtifactId`]
+//! construction (from `&[u8]`, `[u8; N]`, and strings) and inspection.
+//! The following require `std`:
+//!
+//! - Constructing [`ArtifactId`]s from files, readers, and async readers.
+//! - Converting [`ArtifactId`]s to and from URLs and hexadecimal strings.
+//! - `InputManifest`s, `InputManifestBuilder`, and all storage.
+//! - The `serde`, `timing`, and `tracing` features.
+//!
+//! The `staticlib` and `cdylib` artifacts for use over FFI are built by the
+//! separate `omnibor-ffi` crate, so this crate only builds a Rust library
+//! and `cargo build --no-default-features` works as-is.
+//!
 //! [contributing]: CONTRIBUTING.md
 //! [cbindgen]: https://github.com/eqrion/cbindgen
 //! [cisa_report]: https://www.cisa.gov/sites/default/files/2023-10/Software-Identification-Ecosystem-Option-Analysis-508c.pdf
@@ -41,26 +57,73 @@
 //! [omnibor_spec]: https://github.com/omnibor/spec
 //! [purl]: https://github.com/package-url/purl-spec
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
+// Used for newline normalization, which needs to allocate even without `std`.
+extern crate alloc;
+
 // Make this public within the crate to aid with writing sealed
 // traits, a pattern we use repeatedly.
 pub(crate) mod sealed;
 
 // This is hidden for now, as we are not yet ready to commit to any
 // stability guarantees for FFI.
+#[cfg(feature = "std")]
 #[doc(hidden)]
 pub mod ffi;
 
 // Keep modules private and just re-export the symbols we care about.
 mod artifact_id;
+#[cfg(feature = "serde")]
+mod artifact_id_bytes;
+#[cfg(feature = "std")]
+mod artifact_id_hasher;
+#[cfg(feature = "std")]
+mod artifact_id_index;
+#[cfg(feature = "std")]
+mod artifact_id_with_timestamp;
+#[cfg(feature = "cbor")]
+mod cbor;
+#[cfg(feature = "timing")]
+mod computed_artifact_id;
+#[cfg(feature = "diesel")]
+mod diesel_pg;
+#[cfg(feature = "std")]
+mod elf;
 mod embedding_mode;
 mod error;
+#[cfg(feature = "std")]
 mod input_manifest;
+#[cfg(feature = "std")]
 mod input_manifest_builder;
 mod into_artifact_id;
+#[cfg(feature = "jupyter-embed")]
+mod jupyter;
+mod newline;
+#[cfg(feature = "nix-compat")]
+mod nix;
+#[cfg(feature = "opentelemetry")]
+mod otel;
+#[cfg(feature = "parallel-hashing")]
+mod parallel;
+#[cfg(feature = "std")]
+mod pe;
+#[cfg(feature = "postgres")]
+mod postgres;
+mod similarity;
+#[cfg(feature = "rusqlite")]
+mod sqlite;
+#[cfg(feature = "std")]
 pub mod storage;
 mod supported_hash;
+#[cfg(feature = "std")]
+mod text_embedding;
+#[cfg(feature = "tracing")]
+mod traced_artifact_id;
+#[cfg(feature = "std")]
+mod verify_embedding;
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod test;
 
 // Only make this public within the crate, for convenience
@@ -70,21 +133,63 @@ pub(crate) use crate::error::Result;
 
 /// Defines whether data for an [`InputManifest`] is embedded in the artifact itself.
 pub mod embedding {
+    #[cfg(feature = "std")]
+    pub use crate::elf::read_embedded_aid_from_elf;
     pub use crate::embedding_mode::Embed;
     pub use crate::embedding_mode::EmbeddingMode;
     pub use crate::embedding_mode::NoEmbed;
+    #[cfg(feature = "std")]
+    pub use crate::input_manifest_builder::EmbedResult;
+    #[cfg(feature = "std")]
+    pub use crate::pe::read_embedded_aid_from_pe;
+    #[cfg(feature = "std")]
+    pub use crate::text_embedding::read_embedded_aid_from_text;
+    #[cfg(feature = "std")]
+    pub use crate::verify_embedding::verify_target;
+    #[cfg(feature = "std")]
+    pub use crate::verify_embedding::EmbedVerifyResult;
 }
 
 /// Defines the hash algorithms supported for [`ArtifactId`]s.
 pub mod hashes {
+    #[cfg(feature = "hash-blake3")]
+    pub use crate::supported_hash::Blake3;
     pub use crate::supported_hash::Sha256;
+    #[cfg(feature = "hash-sha3-256")]
+    pub use crate::supported_hash::Sha3_256;
     pub use crate::supported_hash::SupportedHash;
 }
 
 pub use crate::artifact_id::ArtifactId;
+#[cfg(feature = "serde")]
+pub use crate::artifact_id_bytes::ArtifactIdBytes;
+#[cfg(feature = "std")]
+pub use crate::artifact_id_hasher::ArtifactIdHasher;
+#[cfg(feature = "std")]
+pub use crate::artifact_id_index::ArtifactIdIndex;
+#[cfg(feature = "std")]
+pub use crate::artifact_id_index::IndexEntry;
+#[cfg(feature = "std")]
+pub use crate::artifact_id_with_timestamp::ArtifactIdWithTimestamp;
+#[cfg(feature = "cbor")]
+pub use crate::cbor::ARTIFACT_ID_CBOR_TAG;
+#[cfg(feature = "timing")]
+pub use crate::computed_artifact_id::ComputedArtifactId;
 pub use crate::error::Error;
+#[cfg(feature = "std")]
 pub use crate::input_manifest::InputManifest;
+#[cfg(feature = "std")]
+pub use crate::input_manifest::InputManifestValidationError;
+#[cfg(feature = "std")]
 pub use crate::input_manifest::Relation;
+#[cfg(feature = "std")]
 pub use crate::input_manifest_builder::InputManifestBuilder;
+#[cfg(feature = "std")]
 pub use crate::input_manifest_builder::ShouldStore;
 pub use crate::into_artifact_id::IntoArtifactId;
+#[cfg(feature = "opentelemetry")]
+pub use crate::otel::add_artifact_id_attribute;
+#[cfg(feature = "postgres")]
+pub use crate::postgres::ArtifactIdPgBinary;
+#[cfg(feature = "rusqlite")]
+pub use crate::sqlite::ArtifactIdSqliteBlob;
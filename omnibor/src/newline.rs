@@ -0,0 +1,250 @@
+//! Newline normalization applied to artifact contents before hashing.
+//!
+//! Per the OmniBOR specification, CRLF (`\r\n`) newlines are converted to
+//! LF (`\n`) before an artifact is hashed, so the same text file produces
+//! the same [`ArtifactId`] regardless of the platform it was written on.
+//! Lone `\r` bytes are left as-is.
+
+#[cfg(doc)]
+use crate::ArtifactId;
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::io::Read;
+#[cfg(feature = "std")]
+use std::io::Result as IoResult;
+#[cfg(feature = "std")]
+use std::pin::Pin;
+#[cfg(feature = "std")]
+use std::task::ready;
+#[cfg(feature = "std")]
+use std::task::Context;
+#[cfg(feature = "std")]
+use std::task::Poll;
+#[cfg(feature = "std")]
+use tokio::io::AsyncRead;
+#[cfg(feature = "std")]
+use tokio::io::ReadBuf;
+
+/// Normalize CRLF newlines in the bytes to LF.
+///
+/// This only allocates if there are newlines to convert.
+pub(crate) fn normalize_newlines(bytes: &[u8]) -> Cow<'_, [u8]> {
+    if !bytes.windows(2).any(|pair| pair == b"\r\n") {
+        return Cow::Borrowed(bytes);
+    }
+
+    let mut normalized = Vec::with_capacity(bytes.len());
+    let mut pending_cr = false;
+
+    for &byte in bytes {
+        push_normalized(&mut normalized, &mut pending_cr, byte);
+    }
+
+    if pending_cr {
+        normalized.push(b'\r');
+    }
+
+    Cow::Owned(normalized)
+}
+
+/// Push a byte to the output, holding back a `\r` until we know whether
+/// it's followed by a `\n`.
+pub(crate) fn push_normalized(out: &mut Vec<u8>, pending_cr: &mut bool, byte: u8) {
+    if *pending_cr {
+        *pending_cr = false;
+
+        if byte != b'\n' {
+            out.push(b'\r');
+        }
+    }
+
+    if byte == b'\r' {
+        *pending_cr = true;
+    } else {
+        out.push(byte);
+    }
+}
+
+/// The size of the chunks read from the underlying reader.
+#[cfg(feature = "std")]
+const CHUNK_SIZE: usize = 8 * 1024;
+
+/// A reader which normalizes CRLF newlines to LF as it reads.
+///
+/// This works with both synchronous and Tokio readers.
+#[cfg(feature = "std")]
+pub(crate) struct NormalizeNewlines<R> {
+    /// The underlying reader.
+    inner: R,
+
+    /// Raw bytes read from the underlying reader.
+    raw: Vec<u8>,
+
+    /// Normalized bytes not yet handed out.
+    normalized: Vec<u8>,
+
+    /// How many of the normalized bytes have been handed out.
+    pos: usize,
+
+    /// Whether the last raw byte was a `\r` we haven't emitted yet.
+    pending_cr: bool,
+
+    /// Whether the underlying reader is exhausted.
+    eof: bool,
+}
+
+#[cfg(feature = "std")]
+impl<R> NormalizeNewlines<R> {
+    /// Wrap a reader to normalize its newlines.
+    pub(crate) fn new(inner: R) -> Self {
+        NormalizeNewlines {
+            inner,
+            raw: vec![0; CHUNK_SIZE],
+            normalized: Vec::with_capacity(CHUNK_SIZE),
+            pos: 0,
+            pending_cr: false,
+            eof: false,
+        }
+    }
+
+    /// Normalize the first `n` raw bytes, where zero means the underlying
+    /// reader is exhausted.
+    fn normalize_raw(&mut self, n: usize) {
+        self.normalized.clear();
+        self.pos = 0;
+
+        if n == 0 {
+            self.eof = true;
+
+            if self.pending_cr {
+                self.pending_cr = false;
+                self.normalized.push(b'\r');
+            }
+
+            return;
+        }
+
+        for &byte in &self.raw[..n] {
+            push_normalized(&mut self.normalized, &mut self.pending_cr, byte);
+        }
+    }
+
+    /// Hand out as many normalized bytes as fit in `buf`.
+    fn drain_into(&mut self, buf: &mut [u8]) -> usize {
+        let available = &self.normalized[self.pos..];
+        let n = available.len().min(buf.len());
+        buf[..n].copy_from_slice(&available[..n]);
+        self.pos += n;
+        n
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: Read> NormalizeNewlines<R> {
+    /// Read and normalize the next chunk from the underlying reader.
+    fn fill(&mut self) -> IoResult<()> {
+        let n = self.inner.read(&mut self.raw)?;
+        self.normalize_raw(n);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: Read> Read for NormalizeNewlines<R> {
+    fn read(&mut self, buf: &mut [u8]) -> IoResult<usize> {
+        while self.pos == self.normalized.len() {
+            if self.eof {
+                return Ok(0);
+            }
+
+            self.fill()?;
+        }
+
+        Ok(self.drain_into(buf))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: AsyncRead + Unpin> AsyncRead for NormalizeNewlines<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<IoResult<()>> {
+        let this = self.get_mut();
+
+        while this.pos == this.normalized.len() {
+            if this.eof {
+                return Poll::Ready(Ok(()));
+            }
+
+            let mut raw = ReadBuf::new(&mut this.raw);
+            ready!(Pin::new(&mut this.inner).poll_read(cx, &mut raw))?;
+            let n = raw.filled().len();
+            this.normalize_raw(n);
+        }
+
+        let n = this.drain_into(buf.initialize_unfilled());
+        buf.advance(n);
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn reader_matches_slice_normalization() {
+        let inputs: [&[u8]; 6] = [
+            b"hello\r\n",
+            b"a\r\nb\r\nc\r\n",
+            b"\r\n\r\n",
+            b"\r\r\n\r",
+            b"lone\rcr",
+            b"no newlines",
+        ];
+
+        for input in inputs {
+            let mut normalized = Vec::new();
+            NormalizeNewlines::new(Cursor::new(input))
+                .read_to_end(&mut normalized)
+                .unwrap();
+            assert_eq!(normalized, normalize_newlines(input).as_ref());
+        }
+    }
+
+    #[test]
+    fn async_reader_matches_sync_reader() {
+        use tokio::io::AsyncReadExt;
+
+        let mut input = vec![b'x'; CHUNK_SIZE - 1];
+        input.extend_from_slice(b"\r\nend\r");
+
+        let mut sync = Vec::new();
+        Read::read_to_end(&mut NormalizeNewlines::new(&input[..]), &mut sync).unwrap();
+
+        let mut async_ = Vec::new();
+        let mut reader = NormalizeNewlines::new(&input[..]);
+        tokio_test::block_on(AsyncReadExt::read_to_end(&mut reader, &mut async_)).unwrap();
+
+        assert_eq!(async_, sync);
+    }
+
+    #[test]
+    fn crlf_split_across_chunks() {
+        let mut input = vec![b'x'; CHUNK_SIZE - 1];
+        input.extend_from_slice(b"\r\nend");
+
+        let mut normalized = Vec::new();
+        NormalizeNewlines::new(Cursor::new(&input))
+            .read_to_end(&mut normalized)
+            .unwrap();
+
+        let mut expected = vec![b'x'; CHUNK_SIZE - 1];
+        expected.extend_from_slice(b"\nend");
+        assert_eq!(normalized, expected);
+    }
+}
@@ -0,0 +1,68 @@
+//! Nix's base32 encoding of hashes.
+//!
+//! This backs [`ArtifactId::to_nix_hash`] and [`ArtifactId::from_nix_hash`].
+//! Nix's base32 isn't RFC 4648 base32: it uses its own alphabet, which
+//! leaves out `e`, `o`, `u`, and `t`, and it encodes the hash starting from
+//! the _last_ five bits, so the first character holds the end of the hash.
+
+#[cfg(doc)]
+use crate::ArtifactId;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// The characters of Nix's base32 alphabet, in order of value.
+const ALPHABET: &[u8; 32] = b"0123456789abcdfghijklmnpqrsvwxyz";
+
+/// Encode bytes with Nix's base32 encoding.
+pub(crate) fn encode(bytes: &[u8]) -> String {
+    let len = encoded_len(bytes.len());
+    let mut encoded = String::with_capacity(len);
+
+    for n in (0..len).rev() {
+        let bit = n * 5;
+        let (index, shift) = (bit / 8, bit % 8);
+
+        let low = bytes[index] >> shift;
+        let high = match bytes.get(index + 1) {
+            Some(next) if shift > 3 => next << (8 - shift),
+            _ => 0,
+        };
+
+        encoded.push(ALPHABET[((low | high) & 0x1f) as usize] as char);
+    }
+
+    encoded
+}
+
+/// Decode a string in Nix's base32 encoding.
+///
+/// Returns `None` if the string has characters outside the alphabet, or
+/// encodes bits beyond the end of the decoded bytes.
+pub(crate) fn decode(s: &str) -> Option<Vec<u8>> {
+    let mut bytes = vec![0; s.len() * 5 / 8];
+
+    for (n, c) in s.bytes().rev().enumerate() {
+        let digit = ALPHABET.iter().position(|a| *a == c)? as u16;
+        let bit = n * 5;
+        let (index, shift) = (bit / 8, bit % 8);
+        let shifted = digit << shift;
+
+        *bytes.get_mut(index)? |= shifted as u8;
+
+        let carry = (shifted >> 8) as u8;
+
+        match bytes.get_mut(index + 1) {
+            Some(next) => *next |= carry,
+            None if carry != 0 => return None,
+            None => {}
+        }
+    }
+
+    Some(bytes)
+}
+
+/// Get the number of characters needed to encode `n` bytes.
+fn encoded_len(n: usize) -> usize {
+    (n * 8).div_ceil(5)
+}
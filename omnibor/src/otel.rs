@@ -0,0 +1,45 @@
+//! Attach [`ArtifactId`]s to OpenTelemetry spans.
+//!
+//! [`ArtifactId`]s convert to OpenTelemetry attribute values as their
+//! `gitoid` URL strings.
+
+use crate::hashes::SupportedHash;
+use crate::ArtifactId;
+use opentelemetry::trace::Span;
+use opentelemetry::Key;
+use opentelemetry::KeyValue;
+use opentelemetry::StringValue;
+use opentelemetry::Value;
+
+impl<H: SupportedHash> From<ArtifactId<H>> for StringValue {
+    fn from(aid: ArtifactId<H>) -> Self {
+        StringValue::from(aid.to_string())
+    }
+}
+
+impl<H: SupportedHash> From<ArtifactId<H>> for Value {
+    fn from(aid: ArtifactId<H>) -> Self {
+        Value::String(aid.into())
+    }
+}
+
+/// Set an attribute on a span to the `gitoid` URL of an [`ArtifactId`].
+///
+/// # Example
+///
+/// ```rust
+/// # use omnibor::add_artifact_id_attribute;
+/// # use omnibor::ArtifactId;
+/// # use omnibor::hashes::Sha256;
+/// # use opentelemetry::trace::noop::NoopSpan;
+/// let mut span = NoopSpan::DEFAULT;
+/// let aid: ArtifactId<Sha256> = ArtifactId::id_str("hello, world");
+/// add_artifact_id_attribute(&mut span, "omnibor.artifact_id", aid);
+/// ```
+pub fn add_artifact_id_attribute<S, H>(span: &mut S, key: impl Into<Key>, aid: ArtifactId<H>)
+where
+    S: Span,
+    H: SupportedHash,
+{
+    span.set_attribute(KeyValue::new(key, aid));
+}
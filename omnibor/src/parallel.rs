@@ -0,0 +1,144 @@
+//! Reading files in parallel chunks for computing Artifact IDs.
+//!
+//! The GitOID construction hashes content as a single stream, so hashing
+//! itself stays sequential. What can run in parallel is reading the file:
+//! chunks are read concurrently, then handed to the hasher in order.
+
+use rayon::prelude::*;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::Read;
+use std::io::Result as IoResult;
+use std::io::Seek as _;
+use std::io::SeekFrom;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// Read `size` bytes of the file starting at `offset`.
+fn read_chunk(path: &Path, offset: u64, size: usize) -> IoResult<Vec<u8>> {
+    let mut file = File::open(path)?;
+    file.seek(SeekFrom::Start(offset))?;
+
+    let mut chunk = Vec::with_capacity(size);
+    file.take(size as u64).read_to_end(&mut chunk)?;
+    Ok(chunk)
+}
+
+/// Get the starting offsets of each chunk of the file.
+fn chunk_offsets(file_len: u64, chunk_size: usize) -> Vec<u64> {
+    (0..file_len).step_by(chunk_size).collect()
+}
+
+/// Find the length of the file's content after newline normalization.
+///
+/// Each chunk is scanned in parallel, and CRLF pairs split across chunk
+/// boundaries are accounted for when combining the results.
+pub(crate) fn normalized_len(path: &Path, file_len: u64, chunk_size: usize) -> IoResult<u64> {
+    let summaries = chunk_offsets(file_len, chunk_size)
+        .into_par_iter()
+        .map(|offset| {
+            let chunk = read_chunk(path, offset, chunk_size)?;
+            let crlf_count = chunk.windows(2).filter(|pair| pair == b"\r\n").count();
+
+            Ok(ChunkSummary {
+                len: chunk.len() as u64,
+                crlf_count: crlf_count as u64,
+                starts_with_lf: chunk.first() == Some(&b'\n'),
+                ends_with_cr: chunk.last() == Some(&b'\r'),
+            })
+        })
+        .collect::<IoResult<Vec<_>>>()?;
+
+    let total = summaries.iter().map(|s| s.len - s.crlf_count).sum::<u64>();
+    let split_crlf_count = summaries
+        .windows(2)
+        .filter(|pair| pair[0].ends_with_cr && pair[1].starts_with_lf)
+        .count() as u64;
+
+    Ok(total - split_crlf_count)
+}
+
+/// What we need to know about a chunk to find the normalized length.
+struct ChunkSummary {
+    /// The length of the chunk in bytes.
+    len: u64,
+
+    /// The number of CRLF pairs entirely within the chunk.
+    crlf_count: u64,
+
+    /// Whether the chunk starts with a `\n`.
+    starts_with_lf: bool,
+
+    /// Whether the chunk ends with a `\r`.
+    ends_with_cr: bool,
+}
+
+/// A reader which reads batches of chunks of a file in parallel.
+///
+/// Chunks are returned in file order. At most one batch, sized to the
+/// number of Rayon threads, is held in memory at once.
+pub(crate) struct ParallelChunkReader {
+    /// The path of the file being read.
+    path: PathBuf,
+
+    /// The size of each chunk in bytes.
+    chunk_size: usize,
+
+    /// The offsets of chunks not yet read.
+    offsets: VecDeque<u64>,
+
+    /// Chunks read but not yet handed out.
+    chunks: VecDeque<Vec<u8>>,
+
+    /// How much of the front chunk has been handed out.
+    pos: usize,
+}
+
+impl ParallelChunkReader {
+    /// Create a reader over the file in chunks of `chunk_size` bytes.
+    pub(crate) fn new(path: &Path, file_len: u64, chunk_size: usize) -> Self {
+        ParallelChunkReader {
+            path: path.to_owned(),
+            chunk_size,
+            offsets: chunk_offsets(file_len, chunk_size).into(),
+            chunks: VecDeque::new(),
+            pos: 0,
+        }
+    }
+
+    /// Read the next batch of chunks in parallel.
+    fn fill(&mut self) -> IoResult<()> {
+        let batch_len = rayon::current_num_threads().min(self.offsets.len());
+        let batch = self.offsets.drain(..batch_len).collect::<Vec<_>>();
+
+        let chunks = batch
+            .into_par_iter()
+            .map(|offset| read_chunk(&self.path, offset, self.chunk_size))
+            .collect::<IoResult<Vec<_>>>()?;
+
+        self.chunks.extend(chunks);
+        Ok(())
+    }
+}
+
+impl Read for ParallelChunkReader {
+    fn read(&mut self, buf: &mut [u8]) -> IoResult<usize> {
+        loop {
+            match self.chunks.front() {
+                Some(chunk) if self.pos < chunk.len() => {
+                    let available = &chunk[self.pos..];
+                    let n = available.len().min(buf.len());
+                    buf[..n].copy_from_slice(&available[..n]);
+                    self.pos += n;
+                    return Ok(n);
+                }
+                Some(_) => {
+                    self.chunks.pop_front();
+                    self.pos = 0;
+                }
+                None if self.offsets.is_empty() => return Ok(0),
+                None => self.fill()?,
+            }
+        }
+    }
+}
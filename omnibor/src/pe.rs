@@ -0,0 +1,203 @@
+//! Read embedded manifest [`ArtifactId`]s from PE files.
+//!
+//! OmniBOR embeds the [`ArtifactId`] of a Portable Executable file's input
+//! manifest in an `.omnibor` section. The section holds one or more `gitoid`
+//! URLs, separated by NUL bytes or whitespace, and padded with NUL bytes to
+//! the file alignment.
+
+use crate::hashes::SupportedHash;
+use crate::ArtifactId;
+use crate::Error;
+use crate::Result;
+use gitoid::HashAlgorithm;
+use std::fs;
+use std::ops::Not as _;
+use std::path::Path;
+use std::result::Result as StdResult;
+use std::str;
+use std::str::FromStr;
+
+/// The name of the section holding OmniBOR IDs, padded to eight bytes.
+const SECTION_NAME: &[u8; 8] = b".omnibor";
+
+/// The size of the COFF file header which follows the PE signature.
+const COFF_HEADER_SIZE: usize = 20;
+
+/// The size of each entry in the section table.
+const SECTION_HEADER_SIZE: usize = 40;
+
+/// Read the manifest [`ArtifactId`] embedded in a PE file, if any.
+///
+/// Returns `Ok(None)` if the file has no `.omnibor` section, or the section
+/// has no ID for the hash algorithm `H`. IDs for other hash algorithms are
+/// skipped. Returns an [`Error`] if the file can't be read, isn't a valid PE
+/// file, or the `.omnibor` section is malformed.
+pub fn read_embedded_aid_from_pe<H: SupportedHash>(path: &Path) -> Result<Option<ArtifactId<H>>> {
+    let data = fs::read(path)?;
+    let invalid = |reason| Error::InvalidPeFile(path.display().to_string(), reason);
+
+    let Some(section) = find_omnibor_section(&data).map_err(invalid)? else {
+        return Ok(None);
+    };
+
+    let text = str::from_utf8(section).map_err(|_| invalid("'.omnibor' section isn't UTF-8"))?;
+    let scheme = format!("gitoid:blob:{}:", H::HashAlgorithm::NAME);
+
+    text.split(|c: char| c == '\0' || c.is_whitespace())
+        .find(|aid| aid.starts_with(&scheme))
+        .map(ArtifactId::from_str)
+        .transpose()
+}
+
+/// Check if some data starts with the DOS header of a PE file.
+pub(crate) fn is_pe(data: &[u8]) -> bool {
+    data.starts_with(b"MZ")
+}
+
+/// Find the contents of the `.omnibor` section, if present.
+fn find_omnibor_section(data: &[u8]) -> StdResult<Option<&[u8]>, &'static str> {
+    if is_pe(data).not() {
+        return Err("missing DOS header");
+    }
+
+    let pe_offset = read_u32(data, 0x3C)? as usize;
+
+    if data.get(pe_offset..pe_offset + 4) != Some(b"PE\0\0") {
+        return Err("missing PE signature");
+    }
+
+    let coff = pe_offset + 4;
+    let section_count = read_u16(data, coff + 2)? as usize;
+    let optional_header_size = read_u16(data, coff + 16)? as usize;
+    let sections = coff + COFF_HEADER_SIZE + optional_header_size;
+
+    for index in 0..section_count {
+        let header = sections + index * SECTION_HEADER_SIZE;
+        let name = data.get(header..header + 8).ok_or("truncated PE file")?;
+
+        if name != SECTION_NAME {
+            continue;
+        }
+
+        let virtual_size = read_u32(data, header + 8)? as usize;
+        let raw_size = read_u32(data, header + 16)? as usize;
+        let raw_offset = read_u32(data, header + 20)? as usize;
+
+        // The raw data is padded to the file alignment, while the virtual
+        // size is the real size, if it's set.
+        let size = match virtual_size {
+            0 => raw_size,
+            _ => virtual_size.min(raw_size),
+        };
+
+        return data
+            .get(raw_offset..raw_offset.saturating_add(size))
+            .map(Some)
+            .ok_or("'.omnibor' section out of bounds");
+    }
+
+    Ok(None)
+}
+
+fn read_u16(data: &[u8], at: usize) -> StdResult<u16, &'static str> {
+    read_array(data, at).map(u16::from_le_bytes)
+}
+
+fn read_u32(data: &[u8], at: usize) -> StdResult<u32, &'static str> {
+    read_array(data, at).map(u32::from_le_bytes)
+}
+
+/// Read a fixed number of bytes at an offset.
+fn read_array<const N: usize>(data: &[u8], at: usize) -> StdResult<[u8; N], &'static str> {
+    data.get(at..at + N)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or("truncated PE file")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hashes::Sha256;
+    use pathbuf::pathbuf;
+
+    /// Build a minimal PE file with an `.omnibor` section holding `content`.
+    fn pe_with_section(content: &[u8]) -> Vec<u8> {
+        let pe_offset = 0x40;
+        let sections = pe_offset + 4 + COFF_HEADER_SIZE;
+        let raw_offset = sections + SECTION_HEADER_SIZE;
+        let raw_size = content.len().next_multiple_of(0x20);
+
+        let mut pe = vec![0; raw_offset];
+        pe[..2].copy_from_slice(b"MZ");
+        pe[0x3C..0x40].copy_from_slice(&(pe_offset as u32).to_le_bytes());
+        pe[pe_offset..pe_offset + 4].copy_from_slice(b"PE\0\0");
+        pe[pe_offset + 6..pe_offset + 8].copy_from_slice(&1u16.to_le_bytes());
+
+        let header = &mut pe[sections..raw_offset];
+        header[..8].copy_from_slice(SECTION_NAME);
+        header[8..12].copy_from_slice(&(content.len() as u32).to_le_bytes());
+        header[16..20].copy_from_slice(&(raw_size as u32).to_le_bytes());
+        header[20..24].copy_from_slice(&(raw_offset as u32).to_le_bytes());
+
+        pe.extend(content);
+        pe.resize(raw_offset + raw_size, 0);
+        pe
+    }
+
+    /// Write a PE file to a test path and read the embedded ID back.
+    fn read_back(name: &str, pe: &[u8]) -> Result<Option<ArtifactId<Sha256>>> {
+        let path = pathbuf![env!("CARGO_MANIFEST_DIR"), "test", name];
+        fs::write(&path, pe).unwrap();
+        let result = read_embedded_aid_from_pe(&path);
+        fs::remove_file(&path).unwrap();
+        result
+    }
+
+    #[test]
+    fn finds_sha256_id() {
+        let aid = ArtifactId::<Sha256>::id_str("manifest");
+        let content = format!(
+            "gitoid:blob:sha1:95d09f2b10159347eece71399a7e2e907ea3df4f\0{}\0",
+            aid
+        );
+        let pe = pe_with_section(content.as_bytes());
+        assert_eq!(read_back("pe_finds_sha256.exe", &pe).unwrap(), Some(aid));
+    }
+
+    #[test]
+    fn missing_section_is_none() {
+        let mut pe = pe_with_section(b"");
+        let sections = 0x40 + 4 + COFF_HEADER_SIZE;
+        pe[sections..sections + 8].copy_from_slice(b".text\0\0\0");
+        assert_eq!(find_omnibor_section(&pe).unwrap(), None);
+    }
+
+    #[test]
+    fn malformed_section_fails() {
+        let pe = pe_with_section(b"gitoid:blob:sha256:not-hex\0");
+        assert!(read_back("pe_malformed.exe", &pe).is_err());
+
+        let pe = pe_with_section(&[0xFF; 8]);
+        assert!(matches!(
+            read_back("pe_non_utf8.exe", &pe),
+            Err(Error::InvalidPeFile(..))
+        ));
+    }
+
+    #[test]
+    fn truncated_pe_fails() {
+        let pe = pe_with_section(b"");
+        assert!(find_omnibor_section(&pe[..0x50]).is_err());
+    }
+
+    #[test]
+    fn non_pe_file_fails() {
+        let path = pathbuf![
+            env!("CARGO_MANIFEST_DIR"),
+            "test",
+            "data",
+            "hello_world.txt"
+        ];
+        assert!(read_embedded_aid_from_pe::<Sha256>(&path).is_err());
+    }
+}
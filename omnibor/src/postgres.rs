@@ -0,0 +1,113 @@
+//! Store [`ArtifactId`]s in PostgreSQL.
+//!
+//! By default an [`ArtifactId`] is stored as `TEXT`, holding its `gitoid`
+//! URL, so it stays readable when inspecting the database by hand. Wrap it
+//! in [`ArtifactIdPgBinary`] to store it as `BYTEA` holding just the hash
+//! bytes instead.
+
+use crate::hashes::SupportedHash;
+use crate::ArtifactId;
+use bytes::BytesMut;
+use postgres_types::to_sql_checked;
+use postgres_types::FromSql;
+use postgres_types::IsNull;
+use postgres_types::ToSql;
+use postgres_types::Type;
+use std::error::Error as StdError;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+use std::str::FromStr;
+
+/// The error type `postgres-types` expects from conversions.
+type PgError = Box<dyn StdError + Sync + Send>;
+
+impl<H: SupportedHash> ToSql for ArtifactId<H> {
+    fn to_sql(&self, ty: &Type, out: &mut BytesMut) -> Result<IsNull, PgError> {
+        self.to_string().to_sql(ty, out)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        is_text(ty)
+    }
+
+    to_sql_checked!();
+}
+
+impl<'a, H: SupportedHash> FromSql<'a> for ArtifactId<H> {
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Self, PgError> {
+        let s = <&str>::from_sql(ty, raw)?;
+        Ok(ArtifactId::from_str(s)?)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        is_text(ty)
+    }
+}
+
+/// Check if a PostgreSQL type holds an [`ArtifactId`] as text.
+fn is_text(ty: &Type) -> bool {
+    matches!(*ty, Type::TEXT | Type::VARCHAR)
+}
+
+/// An [`ArtifactId`] stored in PostgreSQL as the `BYTEA` of its hash.
+///
+/// This is more compact than the default `TEXT` form, but loses the hash
+/// algorithm, so the column type must fix the algorithm in use.
+pub struct ArtifactIdPgBinary<H: SupportedHash>(pub ArtifactId<H>);
+
+impl<H: SupportedHash> ArtifactIdPgBinary<H> {
+    /// Get the wrapped [`ArtifactId`].
+    pub fn artifact_id(&self) -> ArtifactId<H> {
+        self.0
+    }
+}
+
+impl<H: SupportedHash> From<ArtifactId<H>> for ArtifactIdPgBinary<H> {
+    fn from(artifact_id: ArtifactId<H>) -> Self {
+        ArtifactIdPgBinary(artifact_id)
+    }
+}
+
+impl<H: SupportedHash> From<ArtifactIdPgBinary<H>> for ArtifactId<H> {
+    fn from(binary: ArtifactIdPgBinary<H>) -> Self {
+        binary.0
+    }
+}
+
+impl<H: SupportedHash> Clone for ArtifactIdPgBinary<H> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<H: SupportedHash> Copy for ArtifactIdPgBinary<H> {}
+
+impl<H: SupportedHash> Debug for ArtifactIdPgBinary<H> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.debug_tuple("ArtifactIdPgBinary").field(&self.0).finish()
+    }
+}
+
+impl<H: SupportedHash> ToSql for ArtifactIdPgBinary<H> {
+    fn to_sql(&self, ty: &Type, out: &mut BytesMut) -> Result<IsNull, PgError> {
+        self.0.as_bytes().to_sql(ty, out)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        <&[u8] as ToSql>::accepts(ty)
+    }
+
+    to_sql_checked!();
+}
+
+impl<'a, H: SupportedHash> FromSql<'a> for ArtifactIdPgBinary<H> {
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Self, PgError> {
+        let bytes = <&[u8]>::from_sql(ty, raw)?;
+        Ok(ArtifactIdPgBinary(ArtifactId::try_from(bytes)?))
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        <&[u8] as FromSql>::accepts(ty)
+    }
+}
@@ -0,0 +1,19 @@
+//! Bitwise and bytewise comparison of hashes.
+//!
+//! These back [`ArtifactId::hamming_distance`] and
+//! [`ArtifactId::bytes_differ`]. They measure how different two hashes
+//! are, not how similar the artifacts behind them are; a one-byte change to
+//! an artifact produces an unrelated hash.
+
+#[cfg(doc)]
+use crate::ArtifactId;
+
+/// Count the bits which differ between two equal-length hashes.
+pub(crate) fn hamming_distance(a: &[u8], b: &[u8]) -> u32 {
+    a.iter().zip(b).map(|(a, b)| (a ^ b).count_ones()).sum()
+}
+
+/// Count the bytes which differ between two equal-length hashes.
+pub(crate) fn bytes_differ(a: &[u8], b: &[u8]) -> u32 {
+    a.iter().zip(b).filter(|(a, b)| a != b).count() as u32
+}
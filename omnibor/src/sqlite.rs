@@ -0,0 +1,104 @@
+//! Store [`ArtifactId`]s in SQLite.
+//!
+//! By default an [`ArtifactId`] is stored as `TEXT`, holding its `gitoid`
+//! URL, so it stays readable when inspecting the database by hand. Wrap it
+//! in [`ArtifactIdSqliteBlob`] to store it as a `BLOB` holding just the hash
+//! bytes instead.
+
+use crate::hashes::SupportedHash;
+use crate::ArtifactId;
+use crate::Error;
+use rusqlite::types::FromSql;
+use rusqlite::types::FromSqlError;
+use rusqlite::types::FromSqlResult;
+use rusqlite::types::ToSql;
+use rusqlite::types::ToSqlOutput;
+use rusqlite::types::Value;
+use rusqlite::types::ValueRef;
+use rusqlite::Result as SqlResult;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+use std::str::FromStr;
+
+impl<'a, H: SupportedHash> From<&'a ArtifactId<H>> for ToSqlOutput<'a> {
+    fn from(artifact_id: &'a ArtifactId<H>) -> Self {
+        ToSqlOutput::Owned(Value::Text(artifact_id.to_string()))
+    }
+}
+
+impl<H: SupportedHash> ToSql for ArtifactId<H> {
+    fn to_sql(&self) -> SqlResult<ToSqlOutput<'_>> {
+        Ok(self.into())
+    }
+}
+
+impl<H: SupportedHash> FromSql for ArtifactId<H> {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        let s = value.as_str()?;
+        ArtifactId::from_str(s).map_err(|e| FromSqlError::Other(Box::new(e)))
+    }
+}
+
+/// An [`ArtifactId`] stored in SQLite as the `BLOB` of its hash.
+///
+/// This is more compact than the default `TEXT` form, but loses the hash
+/// algorithm, so the column must only hold IDs for one algorithm.
+pub struct ArtifactIdSqliteBlob<H: SupportedHash>(pub ArtifactId<H>);
+
+impl<H: SupportedHash> ArtifactIdSqliteBlob<H> {
+    /// Get the wrapped [`ArtifactId`].
+    pub fn artifact_id(&self) -> ArtifactId<H> {
+        self.0
+    }
+}
+
+impl<H: SupportedHash> From<ArtifactId<H>> for ArtifactIdSqliteBlob<H> {
+    fn from(artifact_id: ArtifactId<H>) -> Self {
+        ArtifactIdSqliteBlob(artifact_id)
+    }
+}
+
+impl<H: SupportedHash> From<ArtifactIdSqliteBlob<H>> for ArtifactId<H> {
+    fn from(blob: ArtifactIdSqliteBlob<H>) -> Self {
+        blob.0
+    }
+}
+
+impl<H: SupportedHash> Clone for ArtifactIdSqliteBlob<H> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<H: SupportedHash> Copy for ArtifactIdSqliteBlob<H> {}
+
+impl<H: SupportedHash> Debug for ArtifactIdSqliteBlob<H> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.debug_tuple("ArtifactIdSqliteBlob")
+            .field(&self.0)
+            .finish()
+    }
+}
+
+impl<H: SupportedHash> ToSql for ArtifactIdSqliteBlob<H> {
+    fn to_sql(&self) -> SqlResult<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::Borrowed(ValueRef::Blob(self.0.as_bytes())))
+    }
+}
+
+impl<H: SupportedHash> FromSql for ArtifactIdSqliteBlob<H> {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        let bytes = value.as_blob()?;
+
+        ArtifactId::try_from(bytes)
+            .map(ArtifactIdSqliteBlob)
+            .map_err(|e| match e {
+                Error::WrongByteSliceLength { expected, got } => FromSqlError::InvalidBlobSize {
+                    expected_size: expected,
+                    blob_size: got,
+                },
+                e => FromSqlError::Other(Box::new(e)),
+            })
+    }
+}
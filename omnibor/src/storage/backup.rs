@@ -0,0 +1,152 @@
+//! Back up and restore manifest stores.
+
+use crate::hashes::SupportedHash;
+use crate::storage::FileSystemStorage;
+use crate::storage::Storage;
+use crate::Error;
+use crate::Result;
+use pathbuf::pathbuf;
+use std::fs;
+use std::fs::create_dir_all;
+use std::io::Error as IoError;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::time::Duration;
+use std::time::Instant;
+use walkdir::WalkDir;
+
+/// A report of what was copied by [`Storage::backup_to`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BackupStats {
+    /// The number of manifests copied.
+    pub manifest_count: usize,
+
+    /// The total size in bytes of the files written to the backup.
+    pub bytes_copied: u64,
+
+    /// How long the backup took.
+    pub elapsed: Duration,
+}
+
+/// Back up any storage by writing its manifests to a new [`FileSystemStorage`].
+///
+/// This is the default implementation of [`Storage::backup_to`].
+pub(crate) fn backup<H, S>(storage: &S, destination: &Path) -> Result<BackupStats>
+where
+    H: SupportedHash,
+    S: Storage<H> + ?Sized,
+{
+    let start = Instant::now();
+    let manifests = storage.get_manifests()?;
+
+    let bytes_copied = write_new_dir(destination, |staging| {
+        FileSystemStorage::new(staging)?.batch_write_manifests(&manifests)?;
+
+        Ok(WalkDir::new(staging)
+            .into_iter()
+            .filter_map(|entry| entry.ok()?.metadata().ok())
+            .filter(|meta| meta.is_file())
+            .map(|meta| meta.len())
+            .sum())
+    })?;
+
+    Ok(BackupStats {
+        manifest_count: manifests.len(),
+        bytes_copied,
+        elapsed: start.elapsed(),
+    })
+}
+
+/// Copy the manifest files and target index of a [`FileSystemStorage`] to
+/// a new directory.
+///
+/// Callers are responsible for locking `storage` while it's copied.
+pub(crate) fn copy_store(storage: &FileSystemStorage, destination: &Path) -> Result<BackupStats> {
+    let start = Instant::now();
+    let manifest_paths = storage.manifest_paths();
+    let target_file = storage.target_file_path();
+
+    let bytes_copied = write_new_dir(destination, |staging| {
+        let write_err = |e| Error::CantWriteBackup(destination.display().to_string(), e);
+        let mut bytes_copied = 0;
+
+        let files = manifest_paths
+            .iter()
+            .chain(target_file.exists().then_some(&target_file));
+
+        for path in files {
+            // Every file is found under the root, so this can't fail.
+            let relative = path.strip_prefix(&storage.root).unwrap();
+            let copy = pathbuf![staging, relative];
+
+            if let Some(parent) = copy.parent() {
+                create_dir_all(parent).map_err(write_err)?;
+            }
+
+            bytes_copied += fs::copy(path, &copy).map_err(write_err)?;
+        }
+
+        Ok(bytes_copied)
+    })?;
+
+    Ok(BackupStats {
+        manifest_count: manifest_paths.len(),
+        bytes_copied,
+        elapsed: start.elapsed(),
+    })
+}
+
+/// Create a new store directory at `destination`, filled in by `fill`.
+///
+/// The store is filled in a staging directory next to `destination`, which
+/// is only moved into place once `fill` succeeds, so `destination` never
+/// holds a partial store. `destination` must be missing or an empty
+/// directory.
+fn write_new_dir<F>(destination: &Path, fill: F) -> Result<u64>
+where
+    F: FnOnce(&Path) -> Result<u64>,
+{
+    let display = destination.display().to_string();
+    let write_err = |e| Error::CantWriteBackup(display.clone(), e);
+
+    let is_empty_dir = match fs::read_dir(destination) {
+        Ok(mut entries) => match entries.next() {
+            None => true,
+            Some(_) => return Err(Error::BackupDestinationNotEmpty(display.clone())),
+        },
+        Err(e) if e.kind() == ErrorKind::NotFound => false,
+        Err(e) if e.kind() == ErrorKind::NotADirectory => {
+            return Err(Error::BackupDestinationNotEmpty(display.clone()))
+        }
+        Err(e) => return Err(write_err(e)),
+    };
+
+    let name = destination
+        .file_name()
+        .ok_or_else(|| write_err(IoError::from(ErrorKind::InvalidInput)))?;
+    let staging = destination.with_file_name(format!(".{}.partial", name.to_string_lossy()));
+
+    // Clear out anything left by an earlier backup which didn't finish.
+    if staging.exists() {
+        fs::remove_dir_all(&staging).map_err(write_err)?;
+    }
+
+    // Always create the manifests directory, so a backup of an empty
+    // store is still a valid store.
+    create_dir_all(pathbuf![&staging, "manifests"]).map_err(write_err)?;
+
+    let bytes_copied = match fill(&staging) {
+        Ok(bytes_copied) => bytes_copied,
+        Err(e) => {
+            let _ = fs::remove_dir_all(&staging);
+            return Err(e);
+        }
+    };
+
+    if is_empty_dir {
+        fs::remove_dir(destination).map_err(write_err)?;
+    }
+
+    fs::rename(&staging, destination).map_err(write_err)?;
+    Ok(bytes_copied)
+}
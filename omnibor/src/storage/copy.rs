@@ -0,0 +1,115 @@
+//! Copy manifests between [`Storage`] instances.
+//!
+//! These are building blocks for migrating, backing up, or synchronizing
+//! manifest stores.
+
+use crate::hashes::SupportedHash;
+use crate::storage::Storage;
+use crate::ArtifactId;
+use crate::Error;
+use crate::Result;
+
+/// Copy a single manifest, and its target if it has one, from `src` to `dst`.
+///
+/// Returns [`Error::ManifestNotFound`] if `src` has no manifest with the
+/// given ID.
+pub fn copy_manifest<H, S1, S2>(src: &S1, manifest_aid: ArtifactId<H>, dst: &mut S2) -> Result<()>
+where
+    H: SupportedHash,
+    S1: Storage<H>,
+    S2: Storage<H>,
+{
+    let mut found = None;
+
+    for manifest in src.get_manifests()? {
+        if ArtifactId::id_manifest(&manifest)? == manifest_aid {
+            found = Some(manifest);
+            break;
+        }
+    }
+
+    let manifest = found.ok_or_else(|| Error::ManifestNotFound(manifest_aid.to_string()))?;
+    dst.batch_write_manifests(&[manifest])?;
+    Ok(())
+}
+
+/// Copy every manifest, and their targets, from `src` to `dst`.
+///
+/// Returns the number of manifests copied.
+pub fn copy_all_manifests<H, S1, S2>(src: &S1, dst: &mut S2) -> Result<usize>
+where
+    H: SupportedHash,
+    S1: Storage<H>,
+    S2: Storage<H>,
+{
+    let manifests = src.get_manifests()?;
+    dst.batch_write_manifests(&manifests)?;
+    Ok(manifests.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::copy_all_manifests;
+    use super::copy_manifest;
+    use crate::hashes::Sha256;
+    use crate::storage::manifest_for;
+    use crate::storage::FileSystemStorage;
+    use crate::storage::InMemoryStorage;
+    use crate::storage::Storage;
+    use crate::ArtifactId;
+    use crate::Error;
+    use crate::InputManifest;
+    use pathbuf::pathbuf;
+
+    #[test]
+    fn copy_single_manifest_with_target() {
+        let mut src = InMemoryStorage::new();
+        let manifest = manifest_for("input", Some("target"));
+        let manifest_aid = src.write_manifest(&manifest).unwrap();
+        src.update_target_for_manifest(manifest_aid, ArtifactId::id_str("target"))
+            .unwrap();
+        let _ = src.write_manifest(&manifest_for("other", None)).unwrap();
+
+        let root = pathbuf![env!("CARGO_MANIFEST_DIR"), "test", "fs_storage_copy_one"];
+        let mut dst = FileSystemStorage::new(&root).unwrap();
+        Storage::<Sha256>::remove_all_manifests(&mut dst).unwrap();
+
+        copy_manifest(&src, manifest_aid, &mut dst).unwrap();
+
+        let copied: Vec<InputManifest<Sha256>> = dst.get_manifests().unwrap();
+        assert_eq!(copied.len(), 1);
+        assert_eq!(
+            dst.get_manifest_for_artifact(ArtifactId::id_str("target"))
+                .unwrap()
+                .map(|manifest| ArtifactId::id_manifest(&manifest).unwrap()),
+            Some(manifest_aid)
+        );
+
+        dst.cleanup().unwrap();
+    }
+
+    #[test]
+    fn copy_missing_manifest_fails() {
+        let src = InMemoryStorage::new();
+        let mut dst = InMemoryStorage::new();
+
+        let result = copy_manifest(&src, ArtifactId::<Sha256>::id_str("missing"), &mut dst);
+        assert!(matches!(result, Err(Error::ManifestNotFound(_))));
+    }
+
+    #[test]
+    fn copy_all_between_storages() {
+        let root = pathbuf![env!("CARGO_MANIFEST_DIR"), "test", "fs_storage_copy_all"];
+        let mut src = FileSystemStorage::new(&root).unwrap();
+        Storage::<Sha256>::remove_all_manifests(&mut src).unwrap();
+        src.batch_write_manifests(&[manifest_for("a", Some("target_a")), manifest_for("b", None)])
+            .unwrap();
+
+        let mut dst = InMemoryStorage::new();
+        assert_eq!(copy_all_manifests(&src, &mut dst).unwrap(), 2);
+        assert_eq!(dst.get_manifests().unwrap().len(), 2);
+        assert!(dst.has_manifest_for_artifact(ArtifactId::id_str("target_a")));
+
+        src.cleanup().unwrap();
+    }
+}
@@ -0,0 +1,67 @@
+//! Garbage collection of unreachable manifests.
+
+use crate::hashes::SupportedHash;
+#[cfg(doc)]
+use crate::storage::FileSystemStorage;
+use crate::ArtifactId;
+use crate::InputManifest;
+use crate::Result;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::ops::Not as _;
+
+/// A report of what was removed by [`FileSystemStorage::gc`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GcStats {
+    /// The number of manifests removed.
+    pub freed_manifests: usize,
+
+    /// The total size in bytes of the removed manifest files.
+    pub freed_bytes: u64,
+}
+
+/// Find the IDs of all manifests reachable from `roots`.
+///
+/// This is the implementation of [`FileSystemStorage::reachable_manifests`].
+pub(crate) fn reachable<H: SupportedHash>(
+    manifests: Vec<InputManifest<H>>,
+    roots: &[ArtifactId<H>],
+) -> Result<HashSet<ArtifactId<H>>> {
+    let mut by_id = HashMap::new();
+    let mut by_target = HashMap::new();
+
+    for manifest in manifests {
+        let manifest_aid = ArtifactId::id_manifest(&manifest)?;
+
+        if let Some(target_aid) = manifest.target() {
+            by_target.insert(target_aid, manifest_aid);
+        }
+
+        by_id.insert(manifest_aid, manifest);
+    }
+
+    let mut reachable = HashSet::new();
+    let mut pending = roots.to_vec();
+
+    while let Some(aid) = pending.pop() {
+        // An ID may name a manifest directly, or an artifact with a manifest.
+        let manifest_aid = if by_id.contains_key(&aid) {
+            aid
+        } else if let Some(manifest_aid) = by_target.get(&aid) {
+            *manifest_aid
+        } else {
+            continue;
+        };
+
+        if reachable.insert(manifest_aid).not() {
+            continue;
+        }
+
+        for relation in by_id[&manifest_aid].relations() {
+            pending.push(relation.artifact());
+            pending.extend(relation.manifest());
+        }
+    }
+
+    Ok(reachable)
+}
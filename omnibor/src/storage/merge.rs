@@ -0,0 +1,195 @@
+//! Merge the manifests of one [`Storage`] into another.
+
+use crate::hashes::SupportedHash;
+use crate::storage::Storage;
+use crate::ArtifactId;
+use crate::Error;
+use crate::Result;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+
+/// What to do when a manifest being merged conflicts with one already stored.
+///
+/// A manifest conflicts when both stores have it, but record different
+/// targets for it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OnConflict {
+    /// Keep the target already stored.
+    #[default]
+    Keep,
+
+    /// Replace the stored target with the one being merged.
+    Overwrite,
+
+    /// Stop merging and return [`Error::MergeConflict`].
+    Error,
+}
+
+/// A report of what happened during [`Storage::merge`].
+pub struct MergeStats<H: SupportedHash> {
+    /// The number of manifests which were new to the store.
+    pub added: usize,
+
+    /// The number of manifests which were already stored.
+    pub skipped_existing: usize,
+
+    /// The IDs of manifests whose targets conflicted.
+    pub conflicts: Vec<ArtifactId<H>>,
+}
+
+impl<H: SupportedHash> Default for MergeStats<H> {
+    fn default() -> Self {
+        MergeStats {
+            added: 0,
+            skipped_existing: 0,
+            conflicts: Vec::new(),
+        }
+    }
+}
+
+impl<H: SupportedHash> Debug for MergeStats<H> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.debug_struct("MergeStats")
+            .field("added", &self.added)
+            .field("skipped_existing", &self.skipped_existing)
+            .field("conflicts", &self.conflicts)
+            .finish()
+    }
+}
+
+/// Merge every manifest from `other` into `storage`.
+///
+/// This is the default implementation of [`Storage::merge`].
+pub(crate) fn merge<H, S1, S2>(
+    storage: &mut S1,
+    other: &S2,
+    on_conflict: OnConflict,
+) -> Result<MergeStats<H>>
+where
+    H: SupportedHash,
+    S1: Storage<H>,
+    S2: Storage<H>,
+{
+    let mut existing = HashMap::new();
+
+    for manifest in storage.get_manifests()? {
+        existing.insert(ArtifactId::id_manifest(&manifest)?, manifest.target());
+    }
+
+    let mut stats = MergeStats::default();
+
+    for manifest in other.get_manifests()? {
+        let manifest_aid = ArtifactId::id_manifest(&manifest)?;
+
+        let Some(stored_target) = existing.get(&manifest_aid).copied() else {
+            storage.batch_write_manifests(&[manifest])?;
+            stats.added += 1;
+            continue;
+        };
+
+        stats.skipped_existing += 1;
+
+        let Some(target_aid) = manifest.target() else {
+            continue;
+        };
+
+        match stored_target {
+            None => storage.update_target_for_manifest(manifest_aid, target_aid)?,
+            Some(stored) if stored == target_aid => {}
+            Some(_) => {
+                stats.conflicts.push(manifest_aid);
+
+                match on_conflict {
+                    OnConflict::Keep => {}
+                    OnConflict::Overwrite => {
+                        storage.update_target_for_manifest(manifest_aid, target_aid)?
+                    }
+                    OnConflict::Error => {
+                        return Err(Error::MergeConflict(manifest_aid.to_string()))
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OnConflict;
+    use crate::hashes::Sha256;
+    use crate::storage::manifest_for;
+    use crate::storage::InMemoryStorage;
+    use crate::storage::Storage;
+    use crate::ArtifactId;
+    use crate::Error;
+
+    /// Build two stores which share one manifest, disagree on the target
+    /// of another, and where `other` has one manifest the first lacks.
+    fn stores() -> (InMemoryStorage, InMemoryStorage) {
+        let mut storage = InMemoryStorage::new();
+        let mut other = InMemoryStorage::new();
+
+        storage
+            .batch_write_manifests(&[
+                manifest_for("shared", Some("shared")),
+                manifest_for("conflict", Some("mine")),
+            ])
+            .unwrap();
+
+        other
+            .batch_write_manifests(&[
+                manifest_for("shared", Some("shared")),
+                manifest_for("conflict", Some("theirs")),
+                manifest_for("new", Some("new")),
+            ])
+            .unwrap();
+
+        (storage, other)
+    }
+
+    fn conflict_target(storage: &InMemoryStorage) -> Option<ArtifactId<Sha256>> {
+        let conflict_aid = ArtifactId::id_manifest(&manifest_for("conflict", None)).unwrap();
+        storage
+            .get_manifests()
+            .unwrap()
+            .into_iter()
+            .find(|manifest| ArtifactId::id_manifest(manifest).unwrap() == conflict_aid)
+            .and_then(|manifest| manifest.target())
+    }
+
+    #[test]
+    fn merge_keeps_existing_targets() {
+        let (mut storage, other) = stores();
+        let stats = storage.merge(&other, OnConflict::Keep).unwrap();
+
+        assert_eq!(stats.added, 1);
+        assert_eq!(stats.skipped_existing, 2);
+        assert_eq!(stats.conflicts.len(), 1);
+        assert_eq!(storage.get_manifests().unwrap().len(), 3);
+        assert!(storage.has_manifest_for_artifact(ArtifactId::id_str("new")));
+        assert_eq!(conflict_target(&storage), Some(ArtifactId::id_str("mine")));
+    }
+
+    #[test]
+    fn merge_overwrites_conflicting_targets() {
+        let (mut storage, other) = stores();
+        let stats = storage.merge(&other, OnConflict::Overwrite).unwrap();
+
+        assert_eq!(stats.conflicts.len(), 1);
+        assert_eq!(
+            conflict_target(&storage),
+            Some(ArtifactId::id_str("theirs"))
+        );
+    }
+
+    #[test]
+    fn merge_fails_on_conflict() {
+        let (mut storage, other) = stores();
+        let result = storage.merge(&other, OnConflict::Error);
+        assert!(matches!(result, Err(Error::MergeConflict(_))));
+    }
+}
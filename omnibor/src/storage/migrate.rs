@@ -0,0 +1,168 @@
+//! Migrate manifests between [`FileSystemStorage`] layouts.
+//!
+//! The on-disk layout of a [`FileSystemStorage`] may change in the future,
+//! for example to use a different sharding scheme. Each layout change gets a
+//! migration here which copies manifests from a store in the old layout to
+//! a store in the new one.
+
+use crate::hashes::Sha256;
+use crate::storage::FileSystemStorage;
+use crate::storage::Storage;
+use crate::ArtifactId;
+use crate::Result;
+use std::ops::Not as _;
+use std::path::Path;
+use std::path::PathBuf;
+use tracing::info;
+use tracing::warn;
+
+/// Migrates a store from the version 1 layout to the version 2 layout.
+///
+/// The version 2 layout is currently identical to version 1, so this copies
+/// every manifest, and its target, from one store to another. It sets up the
+/// infrastructure for future layout changes.
+#[derive(Debug, Clone)]
+pub struct MigrationV1ToV2 {
+    /// The root of the store to migrate from.
+    pub source: PathBuf,
+
+    /// The root of the store to migrate to.
+    pub destination: PathBuf,
+}
+
+impl MigrationV1ToV2 {
+    /// Construct a new migration between two store roots.
+    pub fn new(source: impl AsRef<Path>, destination: impl AsRef<Path>) -> Self {
+        MigrationV1ToV2 {
+            source: source.as_ref().to_owned(),
+            destination: destination.as_ref().to_owned(),
+        }
+    }
+
+    /// Run the migration.
+    ///
+    /// Manifests already in the destination aren't copied again, but their
+    /// targets are still recorded in the destination. Manifests which
+    /// can't be read from the source or written to the destination are
+    /// counted as errors, and the migration carries on with the rest. An
+    /// error is only returned if either store can't be opened, or if the
+    /// destination's target index can't be updated.
+    pub fn run(&self) -> Result<MigrationStats> {
+        let source = FileSystemStorage::new(&self.source)?;
+        let mut destination = FileSystemStorage::new(&self.destination)?;
+
+        let mut stats = MigrationStats::default();
+        let mut upsert = destination.target_index()?.upsert();
+
+        for entry in source.manifests::<Sha256>() {
+            let manifest = match entry.manifest() {
+                Ok(manifest) => manifest,
+                Err(error) => {
+                    warn!(path = %entry.manifest_path.display(), %error, "can't read manifest");
+                    stats.error_count += 1;
+                    continue;
+                }
+            };
+
+            let manifest_aid = ArtifactId::id_manifest(&manifest)?;
+
+            if destination.manifest_path(manifest_aid).exists() {
+                stats.skipped_count += 1;
+            } else if let Err(error) = destination.write_manifest(&manifest) {
+                warn!(%manifest_aid, %error, "can't write manifest");
+                stats.error_count += 1;
+                continue;
+            } else {
+                stats.migrated_count += 1;
+            }
+
+            if let Some(target_aid) = manifest.target() {
+                upsert = upsert.entry(manifest_aid, target_aid);
+            }
+        }
+
+        if upsert.is_empty().not() {
+            upsert.run()?;
+        }
+
+        info!(?stats, "migrated store");
+
+        Ok(stats)
+    }
+}
+
+/// The outcome of a migration.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MigrationStats {
+    /// The number of manifests copied to the destination.
+    pub migrated_count: usize,
+
+    /// The number of manifests already in the destination.
+    pub skipped_count: usize,
+
+    /// The number of manifests which couldn't be migrated.
+    pub error_count: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MigrationStats;
+    use super::MigrationV1ToV2;
+    use crate::hashes::Sha256;
+    use crate::storage::manifest_for;
+    use crate::storage::FileSystemStorage;
+    use crate::storage::Storage;
+    use crate::ArtifactId;
+    use pathbuf::pathbuf;
+    use std::fs;
+
+    #[test]
+    fn migrate_counts_migrated_skipped_and_errors() {
+        let source_root = pathbuf![env!("CARGO_MANIFEST_DIR"), "test", "fs_storage_migrate_v1"];
+        let destination_root =
+            pathbuf![env!("CARGO_MANIFEST_DIR"), "test", "fs_storage_migrate_v2"];
+
+        let mut source = FileSystemStorage::new(&source_root).unwrap();
+        let mut destination = FileSystemStorage::new(&destination_root).unwrap();
+
+        let manifests = (0..3)
+            .map(|n| manifest_for(&format!("input_{}", n), Some(&format!("target_{}", n))))
+            .collect::<Vec<_>>();
+
+        source.batch_write_manifests(&manifests).unwrap();
+
+        // The skipped manifest is in the destination, but without its target.
+        let _ = destination.write_manifest(&manifests[0]).unwrap();
+        assert!(!destination.has_manifest_for_artifact(manifests[0].target().unwrap()));
+
+        // A corrupt manifest in the source is counted as an error.
+        let corrupt = source.manifest_path(ArtifactId::<Sha256>::id_str("corrupt"));
+        fs::create_dir_all(corrupt.parent().unwrap()).unwrap();
+        fs::write(&corrupt, "not a manifest").unwrap();
+
+        let stats = MigrationV1ToV2::new(&source_root, &destination_root)
+            .run()
+            .unwrap();
+
+        assert_eq!(
+            stats,
+            MigrationStats {
+                migrated_count: 2,
+                skipped_count: 1,
+                error_count: 1,
+            }
+        );
+        assert_eq!(
+            Storage::<Sha256>::get_manifests(&destination)
+                .unwrap()
+                .len(),
+            3
+        );
+        for manifest in &manifests {
+            assert!(destination.has_manifest_for_artifact(manifest.target().unwrap()));
+        }
+
+        source.cleanup().unwrap();
+        destination.cleanup().unwrap();
+    }
+}
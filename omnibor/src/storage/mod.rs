@@ -0,0 +1,2640 @@
+//! Defines how manifests are stored and accessed.
+
+pub mod backup;
+pub mod copy;
+pub mod gc;
+pub mod merge;
+pub mod migrate;
+pub mod query;
+
+use crate::hashes::SupportedHash;
+use crate::storage::backup::BackupStats;
+use crate::storage::gc::GcStats;
+use crate::storage::merge::MergeStats;
+use crate::storage::merge::OnConflict;
+use crate::storage::query::ManifestQuery;
+use crate::supported_hash::Sha256;
+use crate::ArtifactId;
+use crate::Error;
+use crate::InputManifest;
+use crate::Result;
+use pathbuf::pathbuf;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::env::var_os;
+use std::fmt::Debug;
+use std::fs;
+use std::fs::create_dir_all;
+use std::fs::write;
+use std::fs::File;
+use std::io::BufRead as _;
+use std::io::BufReader;
+use std::io::BufWriter;
+use std::io::ErrorKind;
+use std::io::Write as _;
+use std::mem;
+use std::ops::Not as _;
+use std::path::Path;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::RwLock;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+use tracing::debug;
+use tracing::info;
+use walkdir::DirEntry;
+use walkdir::WalkDir;
+
+/// Represents the interface for storing and querying manifests.
+pub trait Storage<H: SupportedHash> {
+    /// Check if we have the manifest for a specific artifact.
+    fn has_manifest_for_artifact(&self, target_aid: ArtifactId<H>) -> bool;
+
+    /// Check if we have the manifest with a specific [`ArtifactId`].
+    ///
+    /// Unlike [`Storage::has_manifest_for_artifact`], this takes the ID of the
+    /// manifest itself, not of its target. Returns `false` if the storage
+    /// can't be read.
+    ///
+    /// The default implementation loads every manifest with
+    /// [`Storage::get_manifests`] and identifies each in turn.
+    fn contains_manifest(&self, manifest_aid: ArtifactId<H>) -> bool {
+        self.get_manifests()
+            .map(|manifests| {
+                manifests
+                    .iter()
+                    .any(|manifest| ArtifactId::id_manifest(manifest).ok() == Some(manifest_aid))
+            })
+            .unwrap_or(false)
+    }
+
+    /// Get the manifest for a specific artifact.
+    fn get_manifest_for_artifact(
+        &self,
+        target_aid: ArtifactId<H>,
+    ) -> Result<Option<InputManifest<H>>>;
+
+    /// Get the ID of the manifest for the artifact.
+    fn get_manifest_id_for_artifact(
+        &self,
+        target_aid: ArtifactId<H>,
+    ) -> Result<Option<ArtifactId<H>>>;
+
+    /// Write a manifest to the storage.
+    fn write_manifest(&mut self, manifest: &InputManifest<H>) -> Result<ArtifactId<H>>;
+
+    /// Update the manifest file to reflect the target ID.
+    fn update_target_for_manifest(
+        &mut self,
+        manifest_aid: ArtifactId<H>,
+        target_aid: ArtifactId<H>,
+    ) -> Result<()>;
+
+    /// Get all manifests from the storage.
+    fn get_manifests(&self) -> Result<Vec<InputManifest<H>>>;
+
+    /// List the targets of every manifest in the storage.
+    ///
+    /// Manifests without a target are skipped.
+    ///
+    /// The default implementation loads every manifest with
+    /// [`Storage::get_manifests`] and collects their targets.
+    fn list_targets(&self) -> Result<Vec<ArtifactId<H>>> {
+        Ok(self
+            .get_manifests()?
+            .into_iter()
+            .filter_map(|manifest| manifest.target())
+            .collect())
+    }
+
+    /// Write multiple manifests to the storage.
+    ///
+    /// Any manifest with a target set also has its target recorded.
+    ///
+    /// The default implementation calls [`Storage::write_manifest`] and
+    /// [`Storage::update_target_for_manifest`] for each manifest in turn.
+    /// Implementations may override this to batch the work.
+    fn batch_write_manifests(
+        &mut self,
+        manifests: &[InputManifest<H>],
+    ) -> Result<Vec<ArtifactId<H>>> {
+        let mut manifest_aids = Vec::with_capacity(manifests.len());
+
+        for manifest in manifests {
+            let manifest_aid = self.write_manifest(manifest)?;
+
+            if let Some(target_aid) = manifest.target() {
+                self.update_target_for_manifest(manifest_aid, target_aid)?;
+            }
+
+            manifest_aids.push(manifest_aid);
+        }
+
+        Ok(manifest_aids)
+    }
+
+    /// Move the target of a manifest from one artifact to another.
+    ///
+    /// This is for when the target artifact changes after its manifest was
+    /// recorded, like when a binary is modified after the build. The
+    /// manifest itself isn't changed. Fails with [`Error::TargetNotFound`]
+    /// if no manifest has `old_target_aid` as its target.
+    ///
+    /// The default implementation finds the manifest with
+    /// [`Storage::get_manifest_id_for_artifact`] and then calls
+    /// [`Storage::update_target_for_manifest`].
+    fn rename_target(
+        &mut self,
+        old_target_aid: ArtifactId<H>,
+        new_target_aid: ArtifactId<H>,
+    ) -> Result<()> {
+        let manifest_aid = self
+            .get_manifest_id_for_artifact(old_target_aid)?
+            .ok_or_else(|| Error::TargetNotFound(old_target_aid.to_string()))?;
+
+        self.update_target_for_manifest(manifest_aid, new_target_aid)
+    }
+
+    /// Remove every manifest and target record from the storage.
+    ///
+    /// Returns the number of manifests removed.
+    ///
+    /// The trait has no way to remove a single manifest, so the default
+    /// implementation only checks with [`Storage::get_manifests`] that the
+    /// storage is already empty, and otherwise fails with
+    /// [`Error::RemoveNotSupported`]. Implementations should override this.
+    fn remove_all_manifests(&mut self) -> Result<usize> {
+        if self.get_manifests()?.is_empty() {
+            Ok(0)
+        } else {
+            Err(Error::RemoveNotSupported)
+        }
+    }
+
+    /// Find all manifests which include the given artifact as an input.
+    ///
+    /// The default implementation loads every manifest with
+    /// [`Storage::get_manifests`] and filters them.
+    fn find_manifests_by_input(&self, input_aid: ArtifactId<H>) -> Result<Vec<InputManifest<H>>> {
+        Ok(self
+            .get_manifests()?
+            .into_iter()
+            .filter(|manifest| manifest_has_input(manifest, input_aid))
+            .collect())
+    }
+
+    /// Find the IDs of all manifests which include the given artifact as an input.
+    ///
+    /// The default implementation identifies each manifest returned by
+    /// [`Storage::find_manifests_by_input`].
+    fn find_manifest_ids_by_input(&self, input_aid: ArtifactId<H>) -> Result<Vec<ArtifactId<H>>> {
+        self.find_manifests_by_input(input_aid)?
+            .iter()
+            .map(ArtifactId::id_manifest)
+            .collect()
+    }
+
+    /// Find all manifests matching a [`ManifestQuery`].
+    ///
+    /// The default implementation loads every manifest with
+    /// [`Storage::get_manifests`] and filters them.
+    fn query(&self, query: ManifestQuery<H>) -> Result<Vec<InputManifest<H>>> {
+        query.apply(self.get_manifests()?)
+    }
+
+    /// Import every manifest from `other`, along with its target.
+    ///
+    /// Manifests already in the storage are skipped. If one of them has a
+    /// different target in `other`, `on_conflict` decides what happens.
+    ///
+    /// The default implementation compares the results of
+    /// [`Storage::get_manifests`] for both stores.
+    fn merge<S2: Storage<H>>(
+        &mut self,
+        other: &S2,
+        on_conflict: OnConflict,
+    ) -> Result<MergeStats<H>>
+    where
+        Self: Sized,
+    {
+        merge::merge(self, other, on_conflict)
+    }
+
+    /// Back up every manifest, and their targets, to a new
+    /// [`FileSystemStorage`] at `destination`.
+    ///
+    /// `destination` must not exist yet, or be an empty directory. The backup
+    /// is written to a temporary directory next to it and then moved into
+    /// place, so `destination` never holds a partial backup. Restore it with
+    /// [`FileSystemStorage::restore_from_backup`].
+    ///
+    /// The default implementation writes the results of
+    /// [`Storage::get_manifests`] to the new store.
+    fn backup_to(&self, destination: &Path) -> Result<BackupStats> {
+        backup::backup(self, destination)
+    }
+
+    /// Run several operations against the storage as a single transaction.
+    ///
+    /// If `f` returns an error, implementations which support it roll back
+    /// any changes `f` made before returning the error.
+    ///
+    /// The default implementation just calls `f`, with no atomicity.
+    fn transaction<F, R>(&mut self, f: F) -> Result<R>
+    where
+        Self: Sized,
+        F: FnOnce(&mut Self) -> Result<R>,
+    {
+        f(self)
+    }
+}
+
+/// Check if a manifest includes the given artifact as an input.
+fn manifest_has_input<H: SupportedHash>(
+    manifest: &InputManifest<H>,
+    input_aid: ArtifactId<H>,
+) -> bool {
+    manifest
+        .relations()
+        .iter()
+        .any(|relation| relation.artifact() == input_aid)
+}
+
+impl<H: SupportedHash, S: Storage<H>> Storage<H> for &mut S {
+    fn has_manifest_for_artifact(&self, target_aid: ArtifactId<H>) -> bool {
+        (**self).has_manifest_for_artifact(target_aid)
+    }
+
+    fn contains_manifest(&self, manifest_aid: ArtifactId<H>) -> bool {
+        (**self).contains_manifest(manifest_aid)
+    }
+
+    fn get_manifest_for_artifact(
+        &self,
+        target_aid: ArtifactId<H>,
+    ) -> Result<Option<InputManifest<H>>> {
+        (**self).get_manifest_for_artifact(target_aid)
+    }
+
+    fn get_manifest_id_for_artifact(
+        &self,
+        target_aid: ArtifactId<H>,
+    ) -> Result<Option<ArtifactId<H>>> {
+        (**self).get_manifest_id_for_artifact(target_aid)
+    }
+
+    fn write_manifest(&mut self, manifest: &InputManifest<H>) -> Result<ArtifactId<H>> {
+        (**self).write_manifest(manifest)
+    }
+
+    fn update_target_for_manifest(
+        &mut self,
+        manifest_aid: ArtifactId<H>,
+        target_aid: ArtifactId<H>,
+    ) -> Result<()> {
+        (**self).update_target_for_manifest(manifest_aid, target_aid)
+    }
+
+    fn get_manifests(&self) -> Result<Vec<InputManifest<H>>> {
+        (**self).get_manifests()
+    }
+
+    fn list_targets(&self) -> Result<Vec<ArtifactId<H>>> {
+        (**self).list_targets()
+    }
+
+    fn batch_write_manifests(
+        &mut self,
+        manifests: &[InputManifest<H>],
+    ) -> Result<Vec<ArtifactId<H>>> {
+        (**self).batch_write_manifests(manifests)
+    }
+
+    fn rename_target(
+        &mut self,
+        old_target_aid: ArtifactId<H>,
+        new_target_aid: ArtifactId<H>,
+    ) -> Result<()> {
+        (**self).rename_target(old_target_aid, new_target_aid)
+    }
+
+    fn remove_all_manifests(&mut self) -> Result<usize> {
+        (**self).remove_all_manifests()
+    }
+
+    fn find_manifests_by_input(&self, input_aid: ArtifactId<H>) -> Result<Vec<InputManifest<H>>> {
+        (**self).find_manifests_by_input(input_aid)
+    }
+
+    fn find_manifest_ids_by_input(&self, input_aid: ArtifactId<H>) -> Result<Vec<ArtifactId<H>>> {
+        (**self).find_manifest_ids_by_input(input_aid)
+    }
+
+    fn query(&self, query: ManifestQuery<H>) -> Result<Vec<InputManifest<H>>> {
+        (**self).query(query)
+    }
+
+    fn backup_to(&self, destination: &Path) -> Result<BackupStats> {
+        (**self).backup_to(destination)
+    }
+}
+
+/// Shares a [`Storage`] across threads, locking it for every operation.
+///
+/// If the lock was poisoned by a thread panicking while holding it, operations
+/// fail with [`Error::StorageLockPoisoned`], and
+/// [`Storage::has_manifest_for_artifact`] and [`Storage::contains_manifest`]
+/// return `false`.
+impl<H: SupportedHash, S: Storage<H>> Storage<H> for Arc<Mutex<S>> {
+    fn has_manifest_for_artifact(&self, target_aid: ArtifactId<H>) -> bool {
+        self.lock()
+            .map(|storage| storage.has_manifest_for_artifact(target_aid))
+            .unwrap_or(false)
+    }
+
+    fn contains_manifest(&self, manifest_aid: ArtifactId<H>) -> bool {
+        self.lock()
+            .map(|storage| storage.contains_manifest(manifest_aid))
+            .unwrap_or(false)
+    }
+
+    fn get_manifest_for_artifact(
+        &self,
+        target_aid: ArtifactId<H>,
+    ) -> Result<Option<InputManifest<H>>> {
+        self.lock()
+            .map_err(|_| Error::StorageLockPoisoned)?
+            .get_manifest_for_artifact(target_aid)
+    }
+
+    fn get_manifest_id_for_artifact(
+        &self,
+        target_aid: ArtifactId<H>,
+    ) -> Result<Option<ArtifactId<H>>> {
+        self.lock()
+            .map_err(|_| Error::StorageLockPoisoned)?
+            .get_manifest_id_for_artifact(target_aid)
+    }
+
+    fn write_manifest(&mut self, manifest: &InputManifest<H>) -> Result<ArtifactId<H>> {
+        self.lock()
+            .map_err(|_| Error::StorageLockPoisoned)?
+            .write_manifest(manifest)
+    }
+
+    fn update_target_for_manifest(
+        &mut self,
+        manifest_aid: ArtifactId<H>,
+        target_aid: ArtifactId<H>,
+    ) -> Result<()> {
+        self.lock()
+            .map_err(|_| Error::StorageLockPoisoned)?
+            .update_target_for_manifest(manifest_aid, target_aid)
+    }
+
+    fn get_manifests(&self) -> Result<Vec<InputManifest<H>>> {
+        self.lock()
+            .map_err(|_| Error::StorageLockPoisoned)?
+            .get_manifests()
+    }
+
+    fn list_targets(&self) -> Result<Vec<ArtifactId<H>>> {
+        self.lock()
+            .map_err(|_| Error::StorageLockPoisoned)?
+            .list_targets()
+    }
+
+    fn batch_write_manifests(
+        &mut self,
+        manifests: &[InputManifest<H>],
+    ) -> Result<Vec<ArtifactId<H>>> {
+        self.lock()
+            .map_err(|_| Error::StorageLockPoisoned)?
+            .batch_write_manifests(manifests)
+    }
+
+    fn rename_target(
+        &mut self,
+        old_target_aid: ArtifactId<H>,
+        new_target_aid: ArtifactId<H>,
+    ) -> Result<()> {
+        self.lock()
+            .map_err(|_| Error::StorageLockPoisoned)?
+            .rename_target(old_target_aid, new_target_aid)
+    }
+
+    fn remove_all_manifests(&mut self) -> Result<usize> {
+        self.lock()
+            .map_err(|_| Error::StorageLockPoisoned)?
+            .remove_all_manifests()
+    }
+
+    fn find_manifests_by_input(&self, input_aid: ArtifactId<H>) -> Result<Vec<InputManifest<H>>> {
+        self.lock()
+            .map_err(|_| Error::StorageLockPoisoned)?
+            .find_manifests_by_input(input_aid)
+    }
+
+    fn find_manifest_ids_by_input(&self, input_aid: ArtifactId<H>) -> Result<Vec<ArtifactId<H>>> {
+        self.lock()
+            .map_err(|_| Error::StorageLockPoisoned)?
+            .find_manifest_ids_by_input(input_aid)
+    }
+
+    fn query(&self, query: ManifestQuery<H>) -> Result<Vec<InputManifest<H>>> {
+        self.lock()
+            .map_err(|_| Error::StorageLockPoisoned)?
+            .query(query)
+    }
+
+    fn backup_to(&self, destination: &Path) -> Result<BackupStats> {
+        self.lock()
+            .map_err(|_| Error::StorageLockPoisoned)?
+            .backup_to(destination)
+    }
+}
+
+/// Shares a [`Storage`] across threads, taking a read lock for queries and
+/// a write lock for mutations.
+///
+/// If the lock was poisoned by a thread panicking while holding it, operations
+/// fail with [`Error::StorageLockPoisoned`], and
+/// [`Storage::has_manifest_for_artifact`] returns `false`.
+impl<H: SupportedHash, S: Storage<H>> Storage<H> for Arc<RwLock<S>> {
+    fn has_manifest_for_artifact(&self, target_aid: ArtifactId<H>) -> bool {
+        self.read()
+            .map(|storage| storage.has_manifest_for_artifact(target_aid))
+            .unwrap_or(false)
+    }
+
+    fn contains_manifest(&self, manifest_aid: ArtifactId<H>) -> bool {
+        self.read()
+            .map(|storage| storage.contains_manifest(manifest_aid))
+            .unwrap_or(false)
+    }
+
+    fn get_manifest_for_artifact(
+        &self,
+        target_aid: ArtifactId<H>,
+    ) -> Result<Option<InputManifest<H>>> {
+        self.read()
+            .map_err(|_| Error::StorageLockPoisoned)?
+            .get_manifest_for_artifact(target_aid)
+    }
+
+    fn get_manifest_id_for_artifact(
+        &self,
+        target_aid: ArtifactId<H>,
+    ) -> Result<Option<ArtifactId<H>>> {
+        self.read()
+            .map_err(|_| Error::StorageLockPoisoned)?
+            .get_manifest_id_for_artifact(target_aid)
+    }
+
+    fn write_manifest(&mut self, manifest: &InputManifest<H>) -> Result<ArtifactId<H>> {
+        self.write()
+            .map_err(|_| Error::StorageLockPoisoned)?
+            .write_manifest(manifest)
+    }
+
+    fn update_target_for_manifest(
+        &mut self,
+        manifest_aid: ArtifactId<H>,
+        target_aid: ArtifactId<H>,
+    ) -> Result<()> {
+        self.write()
+            .map_err(|_| Error::StorageLockPoisoned)?
+            .update_target_for_manifest(manifest_aid, target_aid)
+    }
+
+    fn get_manifests(&self) -> Result<Vec<InputManifest<H>>> {
+        self.read()
+            .map_err(|_| Error::StorageLockPoisoned)?
+            .get_manifests()
+    }
+
+    fn list_targets(&self) -> Result<Vec<ArtifactId<H>>> {
+        self.read()
+            .map_err(|_| Error::StorageLockPoisoned)?
+            .list_targets()
+    }
+
+    fn batch_write_manifests(
+        &mut self,
+        manifests: &[InputManifest<H>],
+    ) -> Result<Vec<ArtifactId<H>>> {
+        self.write()
+            .map_err(|_| Error::StorageLockPoisoned)?
+            .batch_write_manifests(manifests)
+    }
+
+    fn rename_target(
+        &mut self,
+        old_target_aid: ArtifactId<H>,
+        new_target_aid: ArtifactId<H>,
+    ) -> Result<()> {
+        self.write()
+            .map_err(|_| Error::StorageLockPoisoned)?
+            .rename_target(old_target_aid, new_target_aid)
+    }
+
+    fn remove_all_manifests(&mut self) -> Result<usize> {
+        self.write()
+            .map_err(|_| Error::StorageLockPoisoned)?
+            .remove_all_manifests()
+    }
+
+    fn find_manifests_by_input(&self, input_aid: ArtifactId<H>) -> Result<Vec<InputManifest<H>>> {
+        self.read()
+            .map_err(|_| Error::StorageLockPoisoned)?
+            .find_manifests_by_input(input_aid)
+    }
+
+    fn find_manifest_ids_by_input(&self, input_aid: ArtifactId<H>) -> Result<Vec<ArtifactId<H>>> {
+        self.read()
+            .map_err(|_| Error::StorageLockPoisoned)?
+            .find_manifest_ids_by_input(input_aid)
+    }
+
+    fn query(&self, query: ManifestQuery<H>) -> Result<Vec<InputManifest<H>>> {
+        self.read()
+            .map_err(|_| Error::StorageLockPoisoned)?
+            .query(query)
+    }
+
+    fn backup_to(&self, destination: &Path) -> Result<BackupStats> {
+        self.read()
+            .map_err(|_| Error::StorageLockPoisoned)?
+            .backup_to(destination)
+    }
+}
+
+/// File system storage for [`InputManifest`]s.
+#[derive(Debug)]
+pub struct FileSystemStorage {
+    root: PathBuf,
+
+    /// Whether a transaction's lock on the root is currently held.
+    in_transaction: bool,
+
+    /// How many manifest files to read in parallel.
+    #[cfg(feature = "rayon")]
+    prefetch_factor: usize,
+}
+
+impl FileSystemStorage {
+    /// Start building a new [`FileSystemStorage`].
+    pub fn new(root: impl AsRef<Path>) -> Result<FileSystemStorage> {
+        let root = root.as_ref().to_owned();
+
+        if root.exists() {
+            let meta = fs::metadata(&root)
+                .map_err(|e| Error::CantAccessRoot(root.display().to_string(), e))?;
+
+            if meta.is_dir().not() {
+                return Err(Error::ObjectStoreNotDir(root.display().to_string()));
+            }
+        } else {
+            create_dir_all(&root)
+                .map_err(|e| Error::CantCreateObjectStoreDir(root.display().to_string(), e))?;
+        }
+
+        Ok(FileSystemStorage {
+            root,
+            in_transaction: false,
+            #[cfg(feature = "rayon")]
+            prefetch_factor: 1,
+        })
+    }
+
+    /// Build a [`FileSystemStorage`] at the given root without checking or
+    /// creating it.
+    ///
+    /// Use [`FileSystemStorage::root_exists`] or
+    /// [`FileSystemStorage::is_valid_store`] to check the root later, for
+    /// example in a health check.
+    pub fn new_unchecked(root: impl AsRef<Path>) -> FileSystemStorage {
+        FileSystemStorage {
+            root: root.as_ref().to_owned(),
+            in_transaction: false,
+            #[cfg(feature = "rayon")]
+            prefetch_factor: 1,
+        }
+    }
+
+    /// Build a new [`FileSystemStorage`] at the given root.
+    ///
+    /// This is an alias for [`FileSystemStorage::new`].
+    pub fn with_root(root: impl AsRef<Path>) -> Result<FileSystemStorage> {
+        FileSystemStorage::new(root)
+    }
+
+    /// Change the root of the [`FileSystemStorage`].
+    ///
+    /// The new root is checked and created the same way as in
+    /// [`FileSystemStorage::new`]. On error, the storage is unchanged.
+    pub fn set_root(&mut self, root: impl AsRef<Path>) -> Result<&mut Self> {
+        self.root = FileSystemStorage::new(root)?.root;
+        Ok(self)
+    }
+
+    /// Set how many manifest files to read in parallel when loading all
+    /// manifests with [`Storage::get_manifests`].
+    ///
+    /// Files are read in batches of `n`, so no more than `n` manifest files
+    /// are open at once. A factor of `0` or `1` reads files one at a time,
+    /// which is the default.
+    #[cfg(feature = "rayon")]
+    pub fn with_prefetch_factor(mut self, n: usize) -> Self {
+        self.prefetch_factor = n;
+        self
+    }
+
+    /// Build a [`FileSystemStorage`] with a root set from
+    /// the `OMNIBOR_DIR` environment variable.
+    pub fn from_env() -> Result<FileSystemStorage> {
+        var_os("OMNIBOR_DIR")
+            .ok_or(Error::NoStorageRoot)
+            .map(|root| FileSystemStorage {
+                root: PathBuf::from(root),
+                in_transaction: false,
+                #[cfg(feature = "rayon")]
+                prefetch_factor: 1,
+            })
+    }
+
+    /// Build a [`FileSystemStorage`] with a root set from the `OMNIBOR_DIR`
+    /// environment variable, falling back to [`FileSystemStorage::default_root`].
+    pub fn from_env_or_default() -> Result<FileSystemStorage> {
+        FileSystemStorage::from_env().or_else(|_| FileSystemStorage::from_xdg_data_dir())
+    }
+
+    /// Build a [`FileSystemStorage`] in an `omnibor` directory in the
+    /// user's data directory, creating it if needed.
+    ///
+    /// This is the root given by [`FileSystemStorage::default_root`].
+    pub fn from_xdg_data_dir() -> Result<FileSystemStorage> {
+        FileSystemStorage::new(FileSystemStorage::default_root()?)
+    }
+
+    /// Build a [`FileSystemStorage`] in an `omnibor` directory in the
+    /// user's cache directory, creating it if needed.
+    ///
+    /// For example, on Linux this is `$XDG_CACHE_HOME/omnibor` or
+    /// `$HOME/.cache/omnibor`.
+    pub fn from_xdg_cache_dir() -> Result<FileSystemStorage> {
+        FileSystemStorage::in_cache_dir(dirs::cache_dir())
+    }
+
+    /// Build a [`FileSystemStorage`] in an `omnibor` directory in the given
+    /// cache directory, if there is one.
+    fn in_cache_dir(cache_dir: Option<PathBuf>) -> Result<FileSystemStorage> {
+        let root = cache_dir
+            .map(|dir| pathbuf![&dir, "omnibor"])
+            .ok_or(Error::NoStorageRoot)?;
+        FileSystemStorage::new(root)
+    }
+
+    /// Get the default storage root, an `omnibor` directory in the
+    /// platform's user data directory.
+    ///
+    /// For example, on Linux this is `$XDG_DATA_HOME/omnibor` or
+    /// `$HOME/.local/share/omnibor`.
+    pub fn default_root() -> Result<PathBuf> {
+        dirs::data_dir()
+            .map(|dir| pathbuf![&dir, "omnibor"])
+            .ok_or(Error::NoStorageRoot)
+    }
+
+    /// Check if the storage root exists.
+    ///
+    /// The root may be missing if the storage was built with
+    /// [`FileSystemStorage::from_env`] or [`FileSystemStorage::new_unchecked`],
+    /// or if it was removed after the storage was built.
+    pub fn root_exists(&self) -> bool {
+        self.root.exists()
+    }
+
+    /// Check if the storage root is a directory holding a manifest store.
+    ///
+    /// Returns `Ok(false)` if the root or its `manifests` directory is missing
+    /// or isn't a directory, and an [`Error`] if either can't be accessed.
+    /// Note the `manifests` directory is only created once a manifest is
+    /// written, so a new, empty store isn't valid yet.
+    pub fn is_valid_store(&self) -> Result<bool> {
+        for path in [&self.root, &self.manifests_path()] {
+            match fs::metadata(path) {
+                Ok(meta) if meta.is_dir() => {}
+                Ok(_) => return Ok(false),
+                Err(e) if e.kind() == ErrorKind::NotFound => return Ok(false),
+                Err(e) => return Err(Error::CantAccessRoot(path.display().to_string(), e)),
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Find the IDs of all manifests reachable from the given roots.
+    ///
+    /// Each root may be the ID of a stored manifest, or of an artifact with a
+    /// stored manifest. From each reachable manifest, the manifests of its
+    /// inputs are also reachable, whether recorded in the relation or found
+    /// through the input's target.
+    pub fn reachable_manifests<H: SupportedHash>(
+        &self,
+        roots: &[ArtifactId<H>],
+    ) -> Result<HashSet<ArtifactId<H>>> {
+        gc::reachable(Storage::<H>::get_manifests(self)?, roots)
+    }
+
+    /// Remove every manifest whose ID is not in `reachable`.
+    ///
+    /// Target index entries for removed manifests are also removed. Use
+    /// [`FileSystemStorage::reachable_manifests`] to find the set to keep.
+    /// Manifests for other hash algorithms are left alone.
+    pub fn gc<H: SupportedHash>(&mut self, reachable: &HashSet<ArtifactId<H>>) -> Result<GcStats> {
+        let mut stats = GcStats::default();
+        let mut upsert = self.target_index()?.upsert::<H>();
+
+        let entries = WalkDir::new(self.manifests_path())
+            .into_iter()
+            .filter_map(|result| result.ok())
+            .filter(|entry| entry.file_type().is_file());
+
+        for entry in entries {
+            let Some(manifest_aid) = artifact_id_from_dir_entry::<H>(&entry) else {
+                continue;
+            };
+
+            if reachable.contains(&manifest_aid) {
+                continue;
+            }
+
+            let path = entry.path();
+            let size = entry.metadata().map(|meta| meta.len()).unwrap_or(0);
+
+            fs::remove_file(path)
+                .map_err(|e| Error::CantRemoveManifest(path.display().to_string(), e))?;
+
+            upsert = upsert.remove(manifest_aid);
+            stats.freed_manifests += 1;
+            stats.freed_bytes += size;
+        }
+
+        if upsert.is_empty().not() {
+            upsert.run()?;
+        }
+
+        info!(
+            "removed {} unreachable manifests from store",
+            stats.freed_manifests
+        );
+
+        Ok(stats)
+    }
+
+    /// Rebuild the target index, dropping any entries which are corrupted
+    /// or refer to manifests no longer in the store.
+    ///
+    /// Manifest files don't record their targets, so targets can only be
+    /// recovered from the current index. Lines which can't be parsed are
+    /// skipped rather than failing, and if a manifest has several entries
+    /// the last one wins, as it would for an update. The current index is
+    /// first copied to `targets.bak` under the root. The new index is
+    /// written to a temporary file and moved into place, so it's never
+    /// left partially written.
+    ///
+    /// Returns the number of manifest-to-target entries kept.
+    pub fn rebuild_target_index<H: SupportedHash>(&mut self) -> Result<usize> {
+        let _lock = self.lock()?;
+        let target_file = self.target_file_path();
+
+        let manifest_aids = WalkDir::new(self.manifests_path())
+            .into_iter()
+            .filter_map(|result| result.ok())
+            .filter(|entry| entry.file_type().is_file())
+            .filter_map(|entry| artifact_id_from_dir_entry::<H>(&entry))
+            .collect::<HashSet<_>>();
+
+        let mut target_index = BTreeMap::new();
+
+        match fs::read(&target_file) {
+            Ok(contents) => {
+                let backup = self.target_backup_path();
+                fs::copy(&target_file, &backup)
+                    .map_err(|e| Error::CantBackUpTargetIndex(backup.display().to_string(), e))?;
+
+                // Corruption may have left bytes which aren't UTF-8, which
+                // just make their lines fail to parse.
+                for line in String::from_utf8_lossy(&contents).lines() {
+                    let Some((manifest_aid, target_aid)) = line.split_once(' ') else {
+                        continue;
+                    };
+
+                    let (Ok(manifest_aid), Ok(target_aid)) = (
+                        ArtifactId::<H>::from_str(manifest_aid),
+                        ArtifactId::<H>::from_str(target_aid),
+                    ) else {
+                        continue;
+                    };
+
+                    if manifest_aids.contains(&manifest_aid) {
+                        target_index.insert(manifest_aid, target_aid);
+                    }
+                }
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => {}
+            Err(e) => {
+                return Err(Error::CantOpenTargetIndex(
+                    target_file.display().to_string(),
+                    e,
+                ))
+            }
+        }
+
+        let contents =
+            target_index
+                .iter()
+                .fold(String::new(), |mut contents, (manifest_aid, target_aid)| {
+                    contents.push_str(&format!("{} {}\n", manifest_aid, target_aid));
+                    contents
+                });
+
+        let tempfile = self.target_temp_path();
+        write(&tempfile, contents)
+            .map_err(|e| Error::CantOpenTargetIndexTemp(tempfile.display().to_string(), e))?;
+
+        if let Err(e) = fs::rename(&tempfile, &target_file) {
+            let _ = fs::remove_file(&tempfile);
+            return Err(Error::CantReplaceTargetIndex(
+                target_file.display().to_string(),
+                e,
+            ));
+        }
+
+        info!("rebuilt target index with {} entries", target_index.len());
+
+        Ok(target_index.len())
+    }
+
+    /// Get the target recorded for a manifest, if it has one.
+    ///
+    /// This is a single lookup in the target index, without reading or
+    /// identifying any manifests.
+    pub fn get_target_for_manifest<H: SupportedHash>(
+        &self,
+        manifest_aid: ArtifactId<H>,
+    ) -> Result<Option<ArtifactId<H>>> {
+        if self.target_file_path().exists().not() {
+            return Ok(None);
+        }
+
+        self.target_index()?.find(manifest_aid)
+    }
+
+    /// Save the current state of the store as a checkpoint named `label`.
+    ///
+    /// The checkpoint is a new `checkpoints/<label>-<timestamp>` directory
+    /// under the root, holding a copy of the target index and the IDs of all
+    /// current manifests. The manifests themselves aren't copied. Returns the
+    /// path to the checkpoint directory.
+    ///
+    /// The timestamp is in nanoseconds since the Unix epoch. If a checkpoint
+    /// with the same label and timestamp already exists, the timestamp is
+    /// bumped until it's unique, so later checkpoints always sort last.
+    pub fn checkpoint(&self, label: &str) -> Result<PathBuf> {
+        check_checkpoint_label(label)?;
+
+        let checkpoints_path = self.checkpoints_path();
+        create_dir_all(&checkpoints_path)
+            .map_err(|e| Error::CantWriteCheckpoint(checkpoints_path.display().to_string(), e))?;
+
+        let mut timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_nanos())
+            .unwrap_or(0);
+        let dir = loop {
+            let dir = pathbuf![&checkpoints_path, &format!("{}-{}", label, timestamp)];
+
+            match fs::create_dir(&dir) {
+                Ok(()) => break dir,
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => timestamp += 1,
+                Err(e) => return Err(Error::CantWriteCheckpoint(dir.display().to_string(), e)),
+            }
+        };
+        let write_err = |e| Error::CantWriteCheckpoint(dir.display().to_string(), e);
+
+        let manifest_ids = self
+            .manifest_paths()
+            .iter()
+            .filter_map(|path| gitoid_url_from_manifest_path(path))
+            .map(|url| format!("{}\n", url))
+            .collect::<String>();
+        write(pathbuf![&dir, "manifests"], manifest_ids).map_err(write_err)?;
+
+        let target_file = self.target_file_path();
+        if target_file.exists() {
+            fs::copy(&target_file, pathbuf![&dir, "targets"]).map_err(write_err)?;
+        }
+
+        info!("created checkpoint '{}'", dir.display());
+
+        Ok(dir)
+    }
+
+    /// Roll the store back to the latest checkpoint named `label`.
+    ///
+    /// Manifests added since the checkpoint are removed, and the target
+    /// index is restored. Manifests removed since the checkpoint can't be
+    /// brought back, since checkpoints only record manifest IDs.
+    pub fn restore_checkpoint(&mut self, label: &str) -> Result<()> {
+        check_checkpoint_label(label)?;
+
+        let dir = self
+            .latest_checkpoint(label)
+            .ok_or_else(|| Error::CheckpointNotFound(label.to_owned()))?;
+        let read_err = |e| Error::CantReadCheckpoint(dir.display().to_string(), e);
+
+        let manifest_ids = fs::read_to_string(pathbuf![&dir, "manifests"]).map_err(read_err)?;
+        let manifest_ids = manifest_ids.lines().collect::<HashSet<_>>();
+
+        for path in self.manifest_paths() {
+            let Some(url) = gitoid_url_from_manifest_path(&path) else {
+                continue;
+            };
+
+            if manifest_ids.contains(url.as_str()).not() {
+                fs::remove_file(&path)
+                    .map_err(|e| Error::CantRemoveManifest(path.display().to_string(), e))?;
+            }
+        }
+
+        let target_file = self.target_file_path();
+        let checkpoint_targets = pathbuf![&dir, "targets"];
+        let restored = if checkpoint_targets.exists() {
+            fs::copy(&checkpoint_targets, &target_file).map(|_| ())
+        } else {
+            File::create(&target_file).map(|_| ())
+        };
+        restored.map_err(|e| Error::CantOpenTargetIndex(target_file.display().to_string(), e))?;
+
+        info!("restored checkpoint '{}'", dir.display());
+
+        Ok(())
+    }
+
+    /// Create a new store at `root` from a backup made with
+    /// [`Storage::backup_to`].
+    ///
+    /// `root` must not exist yet, or be an empty directory. The backup is
+    /// copied to a temporary directory next to `root` and then moved into
+    /// place, so `root` never holds a partial store. The backup itself is
+    /// left unchanged.
+    pub fn restore_from_backup(
+        backup: impl AsRef<Path>,
+        root: impl AsRef<Path>,
+    ) -> Result<FileSystemStorage> {
+        let backup = FileSystemStorage::new_unchecked(backup);
+
+        if backup.is_valid_store()?.not() {
+            return Err(Error::InvalidBackup(backup.root.display().to_string()));
+        }
+
+        let stats = backup::copy_store(&backup, root.as_ref())?;
+
+        info!(
+            "restored {} manifests from backup '{}'",
+            stats.manifest_count,
+            backup.root.display()
+        );
+
+        FileSystemStorage::new(root)
+    }
+
+    /// Fully delete the contents of the root dir.
+    ///
+    /// This is just used for tests to ensure idempotency.
+    #[cfg(test)]
+    pub fn cleanup(self) -> Result<()> {
+        fs::remove_dir_all(&self.root)?;
+        fs::create_dir_all(&self.root)?;
+        Ok(())
+    }
+
+    /// Get the path to the manifest store.
+    fn manifests_path(&self) -> PathBuf {
+        pathbuf![&self.root, "manifests"]
+    }
+
+    /// Get the path to the directory holding checkpoints.
+    fn checkpoints_path(&self) -> PathBuf {
+        pathbuf![&self.root, "checkpoints"]
+    }
+
+    /// Find the most recent checkpoint directory with the given label.
+    fn latest_checkpoint(&self, label: &str) -> Option<PathBuf> {
+        let prefix = format!("{}-", label);
+
+        fs::read_dir(self.checkpoints_path())
+            .ok()?
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let name = entry.file_name().into_string().ok()?;
+                let timestamp = name.strip_prefix(&prefix)?.parse::<u128>().ok()?;
+                Some((timestamp, entry.path()))
+            })
+            .max_by_key(|(timestamp, _)| *timestamp)
+            .map(|(_, path)| path)
+    }
+
+    /// Get the path to the lock file used for transactions.
+    fn lock_file_path(&self) -> PathBuf {
+        pathbuf![&self.root, "lock"]
+    }
+
+    /// Get the path to the target index file.
+    fn target_file_path(&self) -> PathBuf {
+        pathbuf![&self.root, "targets"]
+    }
+
+    /// Get the path to the temporary file used when writing the target index.
+    fn target_temp_path(&self) -> PathBuf {
+        pathbuf![&self.root, "targets.temp"]
+    }
+
+    /// Get the path to the copy of the target index made before rebuilding it.
+    fn target_backup_path(&self) -> PathBuf {
+        pathbuf![&self.root, "targets.bak"]
+    }
+
+    /// Open the target index file
+    fn target_index(&self) -> Result<TargetIndex> {
+        TargetIndex::new(self.target_file_path())
+    }
+
+    /// Get the path for storing a manifest with this [`ArtifactId`].
+    fn manifest_path<H: SupportedHash>(&self, aid: ArtifactId<H>) -> PathBuf {
+        let kind = format!("gitoid_{}_{}", aid.object_type(), aid.hash_algorithm());
+        let hash = aid.as_hex();
+        let (prefix, remainder) = hash.split_at(2);
+        pathbuf![&self.manifests_path(), &kind, prefix, remainder]
+    }
+
+    /// Take an exclusive lock on the storage, held until the file is dropped.
+    ///
+    /// Inside a transaction, its lock is already held, so no new lock is
+    /// taken; locking again through a new file would deadlock.
+    fn lock(&self) -> Result<Option<File>> {
+        if self.in_transaction {
+            return Ok(None);
+        }
+
+        let path = self.lock_file_path();
+        let file = File::create(&path)
+            .map_err(|e| Error::CantLockStorage(path.display().to_string(), e))?;
+        file.lock()
+            .map_err(|e| Error::CantLockStorage(path.display().to_string(), e))?;
+        Ok(Some(file))
+    }
+
+    /// Take a shared lock on the storage, held until the file is dropped.
+    ///
+    /// This excludes transactions, but not other shared locks. Inside a
+    /// transaction, its exclusive lock is already held, so no new lock is
+    /// taken; locking again through a new file would deadlock.
+    fn lock_shared(&self) -> Result<Option<File>> {
+        if self.in_transaction {
+            return Ok(None);
+        }
+
+        let path = self.lock_file_path();
+        let file = File::create(&path)
+            .map_err(|e| Error::CantLockStorage(path.display().to_string(), e))?;
+        file.lock_shared()
+            .map_err(|e| Error::CantLockStorage(path.display().to_string(), e))?;
+        Ok(Some(file))
+    }
+
+    /// Get the paths of all manifest files currently in the store.
+    fn manifest_paths(&self) -> Vec<PathBuf> {
+        WalkDir::new(self.manifests_path())
+            .into_iter()
+            .filter_map(|result| result.ok())
+            .filter(|entry| entry.file_type().is_file())
+            .map(|entry| entry.into_path())
+            .collect()
+    }
+
+    /// Save the current manifests and target index for rolling back.
+    fn snapshot(&self) -> Result<Snapshot> {
+        let manifests = self
+            .manifest_paths()
+            .into_iter()
+            .map(|path| {
+                let content = fs::read(&path)?;
+                Ok((path, content))
+            })
+            .collect::<Result<HashMap<_, _>>>()?;
+
+        let target_file = self.target_file_path();
+        let target_index = if target_file.exists() {
+            let content = fs::read(&target_file)
+                .map_err(|e| Error::CantOpenTargetIndex(target_file.display().to_string(), e))?;
+            Some(content)
+        } else {
+            None
+        };
+
+        Ok(Snapshot {
+            manifests,
+            target_index,
+        })
+    }
+
+    /// Restore the manifests and target index from a snapshot.
+    fn restore(&self, snapshot: Snapshot) -> Result<()> {
+        for path in self.manifest_paths() {
+            if snapshot.manifests.contains_key(&path).not() {
+                fs::remove_file(&path)
+                    .map_err(|e| Error::CantWriteManifest(path.display().to_string(), e))?;
+            }
+        }
+
+        for (path, content) in &snapshot.manifests {
+            if path.exists().not() {
+                if let Some(parent) = path.parent() {
+                    create_dir_all(parent).map_err(|e| {
+                        Error::CantWriteManifestDir(parent.display().to_string(), e)
+                    })?;
+                }
+
+                write(path, content)
+                    .map_err(|e| Error::CantWriteManifest(path.display().to_string(), e))?;
+            }
+        }
+
+        let target_file = self.target_file_path();
+        let restored = match snapshot.target_index {
+            Some(content) => write(&target_file, content),
+            None if target_file.exists() => fs::remove_file(&target_file),
+            None => Ok(()),
+        };
+        restored.map_err(|e| Error::CantOpenTargetIndex(target_file.display().to_string(), e))
+    }
+
+    /// Load all manifests, reading up to `prefetch_factor` files in parallel.
+    #[cfg(feature = "rayon")]
+    fn prefetch_manifests<H: SupportedHash>(&self) -> Result<Vec<InputManifest<H>>> {
+        let entries = self.manifests::<H>().collect::<Vec<_>>();
+        let mut manifests = Vec::with_capacity(entries.len());
+
+        for chunk in entries.chunks(self.prefetch_factor) {
+            let contents = chunk
+                .iter()
+                .map(|entry| entry.manifest_path.as_path())
+                .collect::<Vec<_>>()
+                .par_iter()
+                .map(fs::read)
+                .collect::<Vec<_>>();
+
+            for (entry, content) in chunk.iter().zip(contents) {
+                let mut manifest = InputManifest::from_reader(&content?[..])?;
+                manifest.set_target(entry.target_aid);
+                manifests.push(manifest);
+            }
+        }
+
+        Ok(manifests)
+    }
+
+    /// Iterate over the targets of manifests currently in the object store.
+    fn manifests<H: SupportedHash>(&self) -> impl Iterator<Item = ManifestsEntry<H>> + '_ {
+        WalkDir::new(self.manifests_path())
+            .into_iter()
+            .filter_map(|result| result.ok())
+            .filter(|entry| entry.file_type().is_file())
+            .filter(|entry| entry.file_name().to_str().is_some())
+            .filter_map(|entry| {
+                let manifest_aid = artifact_id_from_dir_entry(&entry)?;
+                let target_aid = self.target_index().ok()?.find(manifest_aid).ok().flatten();
+                let manifest_path = entry.path().to_owned();
+
+                Some(ManifestsEntry {
+                    target_aid,
+                    manifest_path,
+                })
+            })
+    }
+}
+
+impl<H: SupportedHash> Storage<H> for FileSystemStorage {
+    fn has_manifest_for_artifact(&self, target_aid: ArtifactId<H>) -> bool {
+        self.manifests()
+            .any(|entry| entry.target_aid == Some(target_aid))
+    }
+
+    /// Manifests are stored at a path derived from their ID, so this is a
+    /// single file-existence check, with no scan of the store.
+    fn contains_manifest(&self, manifest_aid: ArtifactId<H>) -> bool {
+        self.manifest_path(manifest_aid).is_file()
+    }
+
+    fn get_manifest_for_artifact(
+        &self,
+        target_aid: ArtifactId<H>,
+    ) -> Result<Option<InputManifest<H>>> {
+        match self
+            .manifests()
+            .find(|entry| entry.target_aid == Some(target_aid))
+        {
+            Some(entry) => entry.manifest().map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn get_manifest_id_for_artifact(
+        &self,
+        target_aid: ArtifactId<H>,
+    ) -> Result<Option<ArtifactId<H>>> {
+        match self.get_manifest_for_artifact(target_aid) {
+            Ok(Some(manifest)) => ArtifactId::id_manifest(&manifest).map(Some),
+            Ok(None) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn write_manifest(&mut self, manifest: &InputManifest<H>) -> Result<ArtifactId<H>> {
+        let manifest_aid = ArtifactId::<H>::id_manifest(manifest)?;
+        let path = self.manifest_path(manifest_aid);
+        let parent_dirs = path
+            .parent()
+            .ok_or_else(|| Error::InvalidObjectStorePath(path.display().to_string()))?;
+
+        create_dir_all(parent_dirs)
+            .map_err(|e| Error::CantWriteManifestDir(parent_dirs.display().to_string(), e))?;
+
+        write(&path, manifest.as_bytes()?)
+            .map_err(|e| Error::CantWriteManifest(path.display().to_string(), e))?;
+
+        info!("wrote manifest '{}' to store", manifest_aid);
+
+        Ok(manifest_aid)
+    }
+
+    fn update_target_for_manifest(
+        &mut self,
+        manifest_aid: ArtifactId<H>,
+        target_aid: ArtifactId<H>,
+    ) -> Result<()> {
+        self.target_index()?
+            .upsert()
+            .manifest_aid(manifest_aid)
+            .target_aid(target_aid)
+            .run()
+    }
+
+    fn get_manifests(&self) -> Result<Vec<InputManifest<H>>> {
+        #[cfg(feature = "rayon")]
+        if self.prefetch_factor > 1 {
+            return self.prefetch_manifests();
+        }
+
+        self.manifests()
+            .map(|entry: ManifestsEntry<H>| entry.manifest())
+            .collect()
+    }
+
+    /// Targets are read from the target index alone, without opening any
+    /// manifest files.
+    fn list_targets(&self) -> Result<Vec<ArtifactId<H>>> {
+        let path = self.target_file_path();
+
+        // No target has been recorded yet.
+        if path.exists().not() {
+            return Ok(Vec::new());
+        }
+
+        TargetIndex { path }.targets()
+    }
+
+    fn batch_write_manifests(
+        &mut self,
+        manifests: &[InputManifest<H>],
+    ) -> Result<Vec<ArtifactId<H>>> {
+        // Write all the manifest files first, then update the target index
+        // once, so we only replace the index file a single time.
+        let mut manifest_aids = Vec::with_capacity(manifests.len());
+        let mut upsert = self.target_index()?.upsert();
+
+        for manifest in manifests {
+            let manifest_aid = self.write_manifest(manifest)?;
+
+            if let Some(target_aid) = manifest.target() {
+                upsert = upsert.entry(manifest_aid, target_aid);
+            }
+
+            manifest_aids.push(manifest_aid);
+        }
+
+        if upsert.is_empty().not() {
+            upsert.run()?;
+        }
+
+        Ok(manifest_aids)
+    }
+
+    fn remove_all_manifests(&mut self) -> Result<usize> {
+        let manifests_path = self.manifests_path();
+
+        let count = WalkDir::new(&manifests_path)
+            .into_iter()
+            .filter_map(|result| result.ok())
+            .filter(|entry| entry.file_type().is_file())
+            .count();
+
+        if manifests_path.exists() {
+            fs::remove_dir_all(&manifests_path).map_err(|e| {
+                Error::CantRemoveManifestDir(manifests_path.display().to_string(), e)
+            })?;
+        }
+
+        // Truncate the target index, since none of its entries are valid anymore.
+        let target_file = self.target_file_path();
+        File::create(&target_file)
+            .map_err(|e| Error::CantOpenTargetIndex(target_file.display().to_string(), e))?;
+
+        info!("removed {} manifests from store", count);
+
+        Ok(count)
+    }
+
+    /// Copy the manifest files and target index to a new directory, while
+    /// holding a shared lock on the storage root.
+    ///
+    /// The lock keeps transactions, including in other processes, from
+    /// changing the store partway through the copy. Inside a transaction, the
+    /// transaction's own lock is used instead. Manifests for every hash
+    /// algorithm are copied.
+    fn backup_to(&self, destination: &Path) -> Result<BackupStats> {
+        let lock = self.lock_shared()?;
+        let stats = backup::copy_store(self, destination)?;
+        drop(lock);
+
+        info!(
+            "backed up {} manifests to '{}'",
+            stats.manifest_count,
+            destination.display()
+        );
+
+        Ok(stats)
+    }
+
+    /// Run `f` while holding an exclusive lock on the storage root.
+    ///
+    /// The lock is a file lock, so it also excludes transactions in other
+    /// processes. A nested transaction reuses the outer one's lock. If `f`
+    /// fails, the manifests and target index are restored to their state
+    /// before the transaction.
+    fn transaction<F, R>(&mut self, f: F) -> Result<R>
+    where
+        Self: Sized,
+        F: FnOnce(&mut Self) -> Result<R>,
+    {
+        let lock = self.lock()?;
+        let snapshot = self.snapshot()?;
+
+        let was_in_transaction = mem::replace(&mut self.in_transaction, true);
+        let result = f(self);
+        self.in_transaction = was_in_transaction;
+
+        if result.is_err() {
+            info!("rolling back failed storage transaction");
+            self.restore(snapshot)?;
+        }
+
+        drop(lock);
+        result
+    }
+}
+
+fn artifact_id_from_dir_entry<H: SupportedHash>(entry: &DirEntry) -> Option<ArtifactId<H>> {
+    let gitoid_url = gitoid_url_from_manifest_path(entry.path())?;
+    debug!(gitoid_url = %gitoid_url);
+    ArtifactId::<H>::from_str(&gitoid_url).ok()
+}
+
+/// Check that a checkpoint label is usable as part of a directory name.
+fn check_checkpoint_label(label: &str) -> Result<()> {
+    let valid = label.is_empty().not()
+        && label.starts_with('.').not()
+        && label
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+
+    if valid.not() {
+        return Err(Error::InvalidCheckpointLabel(label.to_owned()));
+    }
+
+    Ok(())
+}
+
+/// Rebuild the `gitoid` URL of a manifest from its path in the store.
+///
+/// This doesn't check the hash algorithm, so it works for manifests of
+/// any supported hash.
+fn gitoid_url_from_manifest_path(path: &Path) -> Option<String> {
+    let path_components = path
+        .components()
+        .map(|comp| comp.as_os_str().to_str())
+        .collect::<Option<Vec<_>>>()?;
+
+    let n_components = path_components.len();
+    let remainder = path_components.get(n_components.checked_sub(1)?)?;
+    let prefix = path_components.get(n_components.checked_sub(2)?)?;
+    let meta = path_components.get(n_components.checked_sub(3)?)?;
+    let hash = format!("{}{}", prefix, remainder);
+    let front = meta.replace('_', ":");
+    Some(format!("{}:{}", front, hash))
+}
+
+/// An entry when iterating over manifests in the manifest store.
+struct ManifestsEntry<H: SupportedHash> {
+    /// The [`ArtifactId`] of the target artifact.
+    target_aid: Option<ArtifactId<H>>,
+
+    /// The path to the manifest in the manifest store.
+    manifest_path: PathBuf,
+}
+
+impl<H: SupportedHash> ManifestsEntry<H> {
+    /// Load the [`InputManifest`] represented by this entry.
+    fn manifest(&self) -> Result<InputManifest<H>> {
+        let mut manifest = InputManifest::from_path(&self.manifest_path)?;
+        manifest.set_target(self.target_aid);
+        Ok(manifest)
+    }
+}
+
+/// Represents the target index file on disk.
+struct TargetIndex {
+    path: PathBuf,
+}
+
+impl TargetIndex {
+    /// Create a new [`TargetIndex`]
+    fn new(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+
+        if path.exists().not() {
+            File::create_new(path)?;
+        }
+
+        Ok(TargetIndex {
+            path: path.to_owned(),
+        })
+    }
+
+    /// Find an entry for a specific manifest [`ArtifactId`].
+    fn find<H: SupportedHash>(&self, manifest_aid: ArtifactId<H>) -> Result<Option<ArtifactId<H>>> {
+        let file = File::open(&self.path)
+            .map_err(|e| Error::CantOpenTargetIndex(self.path.display().to_string(), e))?;
+
+        let reader = BufReader::new(&file);
+
+        for line in reader.lines() {
+            let line = line.map_err(Error::CorruptedTargetIndexIoReason)?;
+
+            let (line_manifest_aid, line_target_aid) = match line.split_once(' ') {
+                Some(pair) => pair,
+                None => return Err(Error::CorruptedTargetIndex),
+            };
+
+            let line_manifest_aid = ArtifactId::from_str(line_manifest_aid)?;
+
+            if line_manifest_aid == manifest_aid {
+                return Ok(ArtifactId::from_str(line_target_aid).ok());
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Get the target [`ArtifactId`] of every entry.
+    fn targets<H: SupportedHash>(&self) -> Result<Vec<ArtifactId<H>>> {
+        let file = File::open(&self.path)
+            .map_err(|e| Error::CantOpenTargetIndex(self.path.display().to_string(), e))?;
+
+        BufReader::new(&file)
+            .lines()
+            .map(|line| {
+                let line = line.map_err(Error::CorruptedTargetIndexIoReason)?;
+
+                let (_, line_target_aid) =
+                    line.split_once(' ').ok_or(Error::CorruptedTargetIndex)?;
+
+                ArtifactId::from_str(line_target_aid)
+            })
+            .collect()
+    }
+
+    // Begin an "upsert" operation in the [`TargetIndex`].
+    //
+    // This either updates or inserts, as appropriate, into the index.
+    fn upsert<H: SupportedHash>(&self) -> TargetIndexUpsert<H> {
+        let root = self.path.parent().unwrap();
+        TargetIndexUpsert::new(root)
+    }
+}
+
+struct TargetIndexUpsert<H: SupportedHash> {
+    root: PathBuf,
+    manifest_aid: Option<ArtifactId<H>>,
+    target_aid: Option<ArtifactId<H>>,
+    entries: Vec<(ArtifactId<H>, ArtifactId<H>)>,
+    removals: Vec<ArtifactId<H>>,
+}
+
+impl<H: SupportedHash> TargetIndexUpsert<H> {
+    /// Start a new upsert operation.
+    fn new(root: impl AsRef<Path>) -> Self {
+        TargetIndexUpsert {
+            root: root.as_ref().to_owned(),
+            manifest_aid: None,
+            target_aid: None,
+            entries: Vec::new(),
+            removals: Vec::new(),
+        }
+    }
+
+    /// Set the manifest [`ArtifactId`] for the upsert.
+    fn manifest_aid(mut self, manifest_aid: ArtifactId<H>) -> Self {
+        self.manifest_aid = Some(manifest_aid);
+        self
+    }
+
+    /// Set the target [`ArtifactId`] for the upsert.
+    fn target_aid(mut self, target_aid: ArtifactId<H>) -> Self {
+        self.target_aid = Some(target_aid);
+        self
+    }
+
+    /// Add a complete manifest-to-target entry to the upsert.
+    ///
+    /// This allows a single upsert to update many entries at once.
+    fn entry(mut self, manifest_aid: ArtifactId<H>, target_aid: ArtifactId<H>) -> Self {
+        self.entries.push((manifest_aid, target_aid));
+        self
+    }
+
+    /// Remove the entry for a manifest [`ArtifactId`] as part of the upsert.
+    fn remove(mut self, manifest_aid: ArtifactId<H>) -> Self {
+        self.removals.push(manifest_aid);
+        self
+    }
+
+    /// Check if the upsert has no entries to write or remove.
+    fn is_empty(&self) -> bool {
+        self.manifest_aid.is_none()
+            && self.target_aid.is_none()
+            && self.entries.is_empty()
+            && self.removals.is_empty()
+    }
+
+    /// Get the path to a temporary file used during upserting.
+    fn tempfile(&self) -> PathBuf {
+        pathbuf![&self.root, "targets.temp"]
+    }
+
+    fn target_file(&self) -> PathBuf {
+        pathbuf![&self.root, "targets"]
+    }
+
+    /// Run the upsert operation.
+    fn run(mut self) -> Result<()> {
+        let mut entries = mem::take(&mut self.entries);
+
+        // A single entry may be set with the individual setters.
+        if self.manifest_aid.is_some()
+            || self.target_aid.is_some()
+            || (entries.is_empty() && self.removals.is_empty())
+        {
+            let manifest_aid = self.manifest_aid.ok_or(Error::InvalidTargetIndexUpsert)?;
+            let target_aid = self.target_aid.ok_or(Error::InvalidTargetIndexUpsert)?;
+            entries.push((manifest_aid, target_aid));
+        }
+
+        let file = File::open(self.target_file())
+            .map_err(|e| Error::CantOpenTargetIndex(self.target_file().display().to_string(), e))?;
+
+        // Read the current target index from disk.
+        let mut target_index = HashMap::new();
+
+        for line in BufReader::new(file).lines() {
+            let line = line.map_err(Error::CorruptedTargetIndexIoReason)?;
+
+            let (line_manifest_aid, line_target_aid) =
+                line.split_once(' ').ok_or(Error::CorruptedTargetIndex)?;
+
+            let line_manifest_aid = ArtifactId::from_str(line_manifest_aid)
+                .map_err(|e| Error::CorruptedTargetIndexOmniBorReason(Box::new(e)))?;
+
+            let line_target_aid = ArtifactId::from_str(line_target_aid)
+                .map_err(|e| Error::CorruptedTargetIndexOmniBorReason(Box::new(e)))?;
+
+            target_index.insert(line_manifest_aid, line_target_aid);
+        }
+
+        // Update the index in memory.
+        for (manifest_aid, target_aid) in entries {
+            target_index
+                .entry(manifest_aid)
+                .and_modify(|old_target_aid| *old_target_aid = target_aid)
+                .or_insert(target_aid);
+        }
+
+        for manifest_aid in &self.removals {
+            target_index.remove(manifest_aid);
+        }
+
+        // Write out updated index to a tempfile.
+        let mut tempfile = File::create(self.tempfile()).map_err(|e| {
+            Error::CantOpenTargetIndexTemp(self.tempfile().display().to_string(), e)
+        })?;
+
+        let mut writer = BufWriter::new(&mut tempfile);
+        for (manifest_aid, target_aid) in target_index {
+            if let Err(e) = writeln!(writer, "{} {}", manifest_aid, target_aid) {
+                fs::remove_file(self.tempfile()).map_err(|e| {
+                    Error::CantDeleteTargetIndexTemp(self.tempfile().display().to_string(), e)
+                })?;
+                return Err(e.into());
+            }
+        }
+
+        // Replace the prior index with the new one.
+        if let Err(e) = fs::rename(self.tempfile(), self.target_file()) {
+            fs::remove_dir(self.tempfile()).map_err(|e| {
+                Error::CantDeleteTargetIndexTemp(self.tempfile().display().to_string(), e)
+            })?;
+            return Err(e.into());
+        }
+
+        Ok(())
+    }
+}
+
+/// In-memory storage for [`InputManifest`]s.
+///
+/// Note that this "storage" doesn't persist anything. We use it for testing, and it
+/// may be useful in other applications where you only care about producing and using
+/// manifests in the short-term, and not in persisting them to a disk or some other
+/// durable location.
+#[derive(Debug, Default)]
+pub struct InMemoryStorage {
+    /// Stored SHA-256 [`InputManifest`]s.
+    sha256_manifests: Vec<ManifestEntry<Sha256>>,
+}
+
+impl InMemoryStorage {
+    /// Construct a new `InMemoryStorage` instance.
+    pub fn new() -> Self {
+        InMemoryStorage::default()
+    }
+
+    /// Consume the storage, returning all of its manifests.
+    ///
+    /// Manifests are returned in the order they were written.
+    pub fn into_vec(self) -> Vec<InputManifest<Sha256>> {
+        self.sha256_manifests
+            .into_iter()
+            .map(|entry| entry.manifest)
+            .collect()
+    }
+
+    /// Get a copy of all the manifests in the storage.
+    ///
+    /// This is the same as [`Storage::get_manifests`], which can't fail
+    /// for in-memory storage.
+    pub fn to_vec(&self) -> Vec<InputManifest<Sha256>> {
+        self.sha256_manifests
+            .iter()
+            .map(|entry| entry.manifest.clone())
+            .collect()
+    }
+
+    /// Find the manifest entry that matches the target [`ArtifactId`]
+    fn match_by_target_aid(
+        &self,
+        target_aid: ArtifactId<Sha256>,
+    ) -> Option<&ManifestEntry<Sha256>> {
+        self.sha256_manifests
+            .iter()
+            .find(|entry| entry.manifest.target() == Some(target_aid))
+    }
+}
+
+impl Storage<Sha256> for InMemoryStorage {
+    fn has_manifest_for_artifact(&self, target_aid: ArtifactId<Sha256>) -> bool {
+        self.match_by_target_aid(target_aid).is_some()
+    }
+
+    fn contains_manifest(&self, manifest_aid: ArtifactId<Sha256>) -> bool {
+        self.sha256_manifests
+            .iter()
+            .any(|entry| entry.manifest_aid == manifest_aid)
+    }
+
+    fn get_manifest_for_artifact(
+        &self,
+        target_aid: ArtifactId<Sha256>,
+    ) -> Result<Option<InputManifest<Sha256>>> {
+        Ok(self
+            .match_by_target_aid(target_aid)
+            .map(|entry| entry.manifest.clone()))
+    }
+
+    fn get_manifest_id_for_artifact(
+        &self,
+        target_aid: ArtifactId<Sha256>,
+    ) -> Result<Option<ArtifactId<Sha256>>> {
+        Ok(self
+            .match_by_target_aid(target_aid)
+            .map(|entry| entry.manifest_aid))
+    }
+
+    fn write_manifest(&mut self, manifest: &InputManifest<Sha256>) -> Result<ArtifactId<Sha256>> {
+        let manifest_aid = ArtifactId::<Sha256>::id_manifest(manifest)?;
+
+        self.sha256_manifests.push(ManifestEntry {
+            manifest_aid,
+            manifest: manifest.clone(),
+        });
+
+        Ok(manifest_aid)
+    }
+
+    fn update_target_for_manifest(
+        &mut self,
+        manifest_aid: ArtifactId<Sha256>,
+        target_aid: ArtifactId<Sha256>,
+    ) -> Result<()> {
+        self.sha256_manifests
+            .iter_mut()
+            .find(|entry| entry.manifest_aid == manifest_aid)
+            .map(|entry| entry.manifest.set_target(Some(target_aid)));
+
+        Ok(())
+    }
+
+    fn get_manifests(&self) -> Result<Vec<InputManifest<Sha256>>> {
+        Ok(self.to_vec())
+    }
+
+    fn remove_all_manifests(&mut self) -> Result<usize> {
+        let count = self.sha256_manifests.len();
+        self.sha256_manifests.clear();
+        Ok(count)
+    }
+
+    fn find_manifest_ids_by_input(
+        &self,
+        input_aid: ArtifactId<Sha256>,
+    ) -> Result<Vec<ArtifactId<Sha256>>> {
+        Ok(self
+            .sha256_manifests
+            .iter()
+            .filter(|entry| manifest_has_input(&entry.manifest, input_aid))
+            .map(|entry| entry.manifest_aid)
+            .collect())
+    }
+
+    /// Run `f`, restoring the stored manifests if it fails.
+    fn transaction<F, R>(&mut self, f: F) -> Result<R>
+    where
+        Self: Sized,
+        F: FnOnce(&mut Self) -> Result<R>,
+    {
+        let snapshot = self.sha256_manifests.clone();
+        let result = f(self);
+
+        if result.is_err() {
+            self.sha256_manifests = snapshot;
+        }
+
+        result
+    }
+}
+
+/// Manifests and target index saved to roll back a failed transaction.
+struct Snapshot {
+    /// The content of each manifest file, by path.
+    manifests: HashMap<PathBuf, Vec<u8>>,
+
+    /// The content of the target index file, if it existed.
+    target_index: Option<Vec<u8>>,
+}
+
+/// An entry in the in-memory manifest storage.
+struct ManifestEntry<H: SupportedHash> {
+    /// The [`ArtifactId`] of the manifest.
+    manifest_aid: ArtifactId<H>,
+
+    /// The manifest itself.
+    manifest: InputManifest<H>,
+}
+
+impl<H: SupportedHash> Debug for ManifestEntry<H> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ManifestEntry")
+            .field("manifest_aid", &self.manifest_aid)
+            .field("manifest", &self.manifest)
+            .finish()
+    }
+}
+
+impl<H: SupportedHash> Clone for ManifestEntry<H> {
+    fn clone(&self) -> Self {
+        ManifestEntry {
+            manifest_aid: self.manifest_aid,
+            manifest: self.manifest.clone(),
+        }
+    }
+}
+
+/// Build a manifest with a single input and an optional target, for tests.
+#[cfg(test)]
+fn manifest_for(input: &str, target: Option<&str>) -> InputManifest<Sha256> {
+    let relation = crate::Relation::new_without_manifest(ArtifactId::id_str(input));
+    let mut manifest = InputManifest::with_relations([relation].into_iter());
+    manifest.set_target(target.map(ArtifactId::id_str));
+    manifest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::manifest_for;
+    use super::query::ManifestQuery;
+    use super::FileSystemStorage;
+    use super::InMemoryStorage;
+    use super::Storage;
+    use crate::hashes::Sha256;
+    use crate::ArtifactId;
+    use crate::Error;
+    use crate::InputManifest;
+    use crate::Relation;
+    use pathbuf::pathbuf;
+    use std::str::FromStr;
+    use std::sync::Arc;
+    use std::sync::Mutex;
+    use std::sync::RwLock;
+    use std::thread;
+
+    #[test]
+    fn correct_aid_storage_path() {
+        let root = pathbuf![env!("CARGO_MANIFEST_DIR"), "test", "fs_storage"];
+        let storage = FileSystemStorage::new(&root).unwrap();
+
+        let aid = ArtifactId::<Sha256>::from_str(
+            "gitoid:blob:sha256:9d09789f20162dca6d80d2d884f46af22c824f6409d4f447332d079a2d1e364f",
+        )
+        .unwrap();
+
+        let path = storage.manifest_path(aid);
+        let path = path.strip_prefix(&root).unwrap();
+        let expected = pathbuf![
+            "manifests",
+            "gitoid_blob_sha256",
+            "9d",
+            "09789f20162dca6d80d2d884f46af22c824f6409d4f447332d079a2d1e364f"
+        ];
+
+        assert_eq!(path, expected);
+    }
+
+    #[test]
+    fn xdg_cache_dir_storage_root() {
+        let cache_home = pathbuf![env!("CARGO_MANIFEST_DIR"), "test", "xdg_cache"];
+
+        let storage = FileSystemStorage::in_cache_dir(Some(cache_home.clone())).unwrap();
+        assert_eq!(storage.root, pathbuf![&cache_home, "omnibor"]);
+        assert!(storage.root.is_dir());
+
+        assert!(matches!(
+            FileSystemStorage::in_cache_dir(None),
+            Err(Error::NoStorageRoot)
+        ));
+
+        std::fs::remove_dir_all(&cache_home).unwrap();
+    }
+
+    #[test]
+    fn get_target_for_manifest_reads_index() {
+        let root = pathbuf![
+            env!("CARGO_MANIFEST_DIR"),
+            "test",
+            "fs_storage_target_lookup"
+        ];
+        let mut storage = FileSystemStorage::new(&root).unwrap();
+
+        let untargeted = manifest_for("untargeted", None);
+        let untargeted_aid = ArtifactId::<Sha256>::id_manifest(&untargeted).unwrap();
+        assert_eq!(
+            storage.get_target_for_manifest(untargeted_aid).unwrap(),
+            None
+        );
+
+        let manifest_aids = storage
+            .batch_write_manifests(&[untargeted, manifest_for("input", Some("target"))])
+            .unwrap();
+        assert_eq!(
+            storage.get_target_for_manifest(manifest_aids[1]).unwrap(),
+            Some(ArtifactId::id_str("target"))
+        );
+        assert_eq!(
+            storage.get_target_for_manifest(manifest_aids[0]).unwrap(),
+            None
+        );
+
+        storage.cleanup().unwrap();
+    }
+
+    #[test]
+    fn batch_write_records_targets() {
+        let root = pathbuf![env!("CARGO_MANIFEST_DIR"), "test", "fs_storage_batch"];
+        let mut storage = FileSystemStorage::new(&root).unwrap();
+
+        let manifests = (0..3)
+            .map(|n| manifest_for(&format!("input_{}", n), Some(&format!("target_{}", n))))
+            .collect::<Vec<_>>();
+
+        let manifest_aids = storage.batch_write_manifests(&manifests).unwrap();
+        assert_eq!(manifest_aids.len(), manifests.len());
+
+        for (manifest, manifest_aid) in manifests.iter().zip(manifest_aids) {
+            let target = manifest.target().unwrap();
+            let stored = storage.get_manifest_for_artifact(target).unwrap().unwrap();
+            assert_eq!(stored.relations(), manifest.relations());
+            assert_eq!(
+                storage.get_manifest_id_for_artifact(target).unwrap(),
+                Some(manifest_aid)
+            );
+        }
+
+        storage.cleanup().unwrap();
+    }
+
+    #[test]
+    fn rebuild_target_index_drops_bad_entries() {
+        let root = pathbuf![env!("CARGO_MANIFEST_DIR"), "test", "fs_storage_rebuild"];
+        let _ = std::fs::remove_dir_all(&root);
+        let mut storage = FileSystemStorage::new(&root).unwrap();
+
+        let manifests = (0..2)
+            .map(|n| manifest_for(&format!("input_{}", n), Some(&format!("target_{}", n))))
+            .collect::<Vec<_>>();
+        storage.batch_write_manifests(&manifests).unwrap();
+
+        // Add a garbled line and an entry for a manifest not in the store.
+        let target_file = storage.target_file_path();
+        let mut index = std::fs::read(&target_file).unwrap();
+        index.extend(b"not an entry\xff\n");
+        index.extend(
+            format!(
+                "{} {}\n",
+                ArtifactId::<Sha256>::id_str("missing"),
+                ArtifactId::<Sha256>::id_str("target_missing")
+            )
+            .as_bytes(),
+        );
+        std::fs::write(&target_file, &index).unwrap();
+
+        assert_eq!(storage.rebuild_target_index::<Sha256>().unwrap(), 2);
+        assert_eq!(std::fs::read(storage.target_backup_path()).unwrap(), index);
+
+        for manifest in &manifests {
+            assert!(storage.has_manifest_for_artifact(manifest.target().unwrap()));
+        }
+        assert_eq!(
+            std::fs::read_to_string(&target_file)
+                .unwrap()
+                .lines()
+                .count(),
+            2
+        );
+
+        storage.cleanup().unwrap();
+    }
+
+    #[test]
+    fn rebuild_target_index_in_transaction() {
+        let root = pathbuf![env!("CARGO_MANIFEST_DIR"), "test", "fs_storage_rebuild_txn"];
+        let _ = std::fs::remove_dir_all(&root);
+        let mut storage = FileSystemStorage::new(&root).unwrap();
+
+        // The rebuild, and a nested transaction, reuse the outer lock rather
+        // than waiting on it.
+        let kept = Storage::<Sha256>::transaction(&mut storage, |storage| {
+            storage.batch_write_manifests(&[manifest_for("input", Some("target"))])?;
+            Storage::<Sha256>::transaction(storage, |storage| {
+                storage.rebuild_target_index::<Sha256>()
+            })
+        })
+        .unwrap();
+        assert_eq!(kept, 1);
+        assert!(storage.has_manifest_for_artifact(ArtifactId::<Sha256>::id_str("target")));
+
+        // Outside the transaction, the rebuild takes its own lock again.
+        assert_eq!(storage.rebuild_target_index::<Sha256>().unwrap(), 1);
+
+        storage.cleanup().unwrap();
+    }
+
+    #[test]
+    fn gc_removes_unreachable_manifests() {
+        let root = pathbuf![env!("CARGO_MANIFEST_DIR"), "test", "fs_storage_gc"];
+        let mut storage = FileSystemStorage::new(&root).unwrap();
+
+        // The app's manifest reaches the library's manifest through its target.
+        let manifest_aids = storage
+            .batch_write_manifests(&[
+                manifest_for("lib.c", Some("lib")),
+                manifest_for("lib", Some("app")),
+                manifest_for("orphan", None),
+            ])
+            .unwrap();
+
+        let reachable = storage
+            .reachable_manifests(&[ArtifactId::<Sha256>::id_str("app")])
+            .unwrap();
+        assert_eq!(reachable.len(), 2);
+        assert!(reachable.contains(&manifest_aids[0]));
+        assert!(reachable.contains(&manifest_aids[1]));
+
+        let stats = storage.gc(&reachable).unwrap();
+        assert_eq!(stats.freed_manifests, 1);
+        assert!(stats.freed_bytes > 0);
+        assert_eq!(Storage::<Sha256>::get_manifests(&storage).unwrap().len(), 2);
+        assert!(storage.has_manifest_for_artifact(ArtifactId::<Sha256>::id_str("lib")));
+
+        storage.cleanup().unwrap();
+    }
+
+    /// Check that renaming a target moves the manifest to the new target.
+    fn rename_target_test(mut storage: impl Storage<Sha256>) {
+        let old_target = ArtifactId::<Sha256>::id_str("old_target");
+        let new_target = ArtifactId::<Sha256>::id_str("new_target");
+
+        let mut manifest = InputManifest::with_relations(
+            [Relation::from_artifact(ArtifactId::id_str("input"))].into_iter(),
+        );
+        manifest.set_target(Some(old_target));
+        storage.batch_write_manifests(&[manifest]).unwrap();
+
+        storage.rename_target(old_target, new_target).unwrap();
+
+        assert!(!storage.has_manifest_for_artifact(old_target));
+        assert!(storage.has_manifest_for_artifact(new_target));
+        assert!(matches!(
+            storage.rename_target(old_target, new_target),
+            Err(Error::TargetNotFound(_))
+        ));
+    }
+
+    fn contains_manifest_test(mut storage: impl Storage<Sha256>) {
+        let manifest = InputManifest::with_relations(
+            [Relation::from_artifact(ArtifactId::id_str("input"))].into_iter(),
+        );
+        let manifest_aid = storage.write_manifest(&manifest).unwrap();
+        storage
+            .update_target_for_manifest(manifest_aid, ArtifactId::id_str("target"))
+            .unwrap();
+
+        assert!(storage.contains_manifest(manifest_aid));
+        assert!(!storage.contains_manifest(ArtifactId::id_str("target")));
+        assert!(!storage.contains_manifest(ArtifactId::id_str("missing")));
+    }
+
+    #[test]
+    fn contains_manifest_in_memory() {
+        contains_manifest_test(InMemoryStorage::new());
+    }
+
+    #[test]
+    fn contains_manifest_fs() {
+        let root = pathbuf![env!("CARGO_MANIFEST_DIR"), "test", "fs_storage_contains"];
+        let mut storage = FileSystemStorage::new(&root).unwrap();
+        contains_manifest_test(&mut storage);
+        storage.cleanup().unwrap();
+    }
+
+    fn list_targets_test(mut storage: impl Storage<Sha256>) {
+        assert!(storage.list_targets().unwrap().is_empty());
+
+        storage
+            .batch_write_manifests(&[
+                manifest_for("a.c", Some("a.o")),
+                manifest_for("b.c", Some("b.o")),
+                manifest_for("orphan", None),
+            ])
+            .unwrap();
+
+        let mut targets = storage.list_targets().unwrap();
+        targets.sort();
+        let mut expected = vec![ArtifactId::id_str("a.o"), ArtifactId::id_str("b.o")];
+        expected.sort();
+        assert_eq!(targets, expected);
+    }
+
+    #[test]
+    fn list_targets_in_memory() {
+        list_targets_test(InMemoryStorage::new());
+    }
+
+    #[test]
+    fn list_targets_fs() {
+        let root = pathbuf![
+            env!("CARGO_MANIFEST_DIR"),
+            "test",
+            "fs_storage_list_targets"
+        ];
+        let _ = std::fs::remove_dir_all(&root);
+        let mut storage = FileSystemStorage::new(&root).unwrap();
+        list_targets_test(&mut storage);
+        storage.cleanup().unwrap();
+    }
+
+    #[test]
+    fn rename_target_in_memory() {
+        rename_target_test(InMemoryStorage::new());
+    }
+
+    #[test]
+    fn rename_target_fs() {
+        let root = pathbuf![
+            env!("CARGO_MANIFEST_DIR"),
+            "test",
+            "fs_storage_rename_target"
+        ];
+        let mut storage = FileSystemStorage::new(&root).unwrap();
+        rename_target_test(&mut storage);
+        storage.cleanup().unwrap();
+    }
+
+    #[test]
+    fn restore_checkpoint_removes_newer_manifests() {
+        let root = pathbuf![env!("CARGO_MANIFEST_DIR"), "test", "fs_storage_checkpoint"];
+        let mut storage = FileSystemStorage::new(&root).unwrap();
+
+        storage
+            .batch_write_manifests(&[manifest_for("old", Some("old_target"))])
+            .unwrap();
+        let checkpoint = storage.checkpoint("before-new").unwrap();
+        assert!(checkpoint.starts_with(pathbuf![&root, "checkpoints"]));
+
+        storage
+            .batch_write_manifests(&[manifest_for("new", Some("new_target"))])
+            .unwrap();
+        assert_eq!(Storage::<Sha256>::get_manifests(&storage).unwrap().len(), 2);
+
+        storage.restore_checkpoint("before-new").unwrap();
+
+        let manifests = Storage::<Sha256>::get_manifests(&storage).unwrap();
+        assert_eq!(manifests.len(), 1);
+        assert_eq!(
+            manifests[0].target(),
+            Some(ArtifactId::id_str("old_target"))
+        );
+
+        let first = storage.checkpoint("twice").unwrap();
+        storage
+            .batch_write_manifests(&[manifest_for("newer", Some("newer_target"))])
+            .unwrap();
+        let second = storage.checkpoint("twice").unwrap();
+        assert_ne!(first, second);
+        assert!(first.is_dir() && second.is_dir());
+
+        storage.restore_checkpoint("twice").unwrap();
+        assert_eq!(Storage::<Sha256>::get_manifests(&storage).unwrap().len(), 2);
+
+        assert!(matches!(
+            storage.restore_checkpoint("missing"),
+            Err(Error::CheckpointNotFound(_))
+        ));
+        assert!(matches!(
+            storage.checkpoint("../escape"),
+            Err(Error::InvalidCheckpointLabel(_))
+        ));
+
+        storage.cleanup().unwrap();
+    }
+
+    #[test]
+    fn backup_and_restore_fs() {
+        let test_dir = pathbuf![env!("CARGO_MANIFEST_DIR"), "test", "fs_storage_backup"];
+        let _ = std::fs::remove_dir_all(&test_dir);
+        let mut storage = FileSystemStorage::new(pathbuf![&test_dir, "store"]).unwrap();
+
+        let mut manifest = InputManifest::with_relations(
+            [Relation::from_artifact(ArtifactId::<Sha256>::id_str(
+                "input",
+            ))]
+            .into_iter(),
+        );
+        manifest.set_target(Some(ArtifactId::id_str("target")));
+        storage.batch_write_manifests(&[manifest]).unwrap();
+
+        let backup = pathbuf![&test_dir, "backup"];
+        let stats = Storage::<Sha256>::backup_to(&storage, &backup).unwrap();
+        assert_eq!(stats.manifest_count, 1);
+        assert!(stats.bytes_copied > 0);
+
+        // Backups never overwrite an existing store.
+        assert!(matches!(
+            Storage::<Sha256>::backup_to(&storage, &backup),
+            Err(Error::BackupDestinationNotEmpty(_))
+        ));
+
+        let restored =
+            FileSystemStorage::restore_from_backup(&backup, pathbuf![&test_dir, "restored"])
+                .unwrap();
+        let manifest_aids = |storage: &FileSystemStorage| {
+            Storage::<Sha256>::get_manifests(storage)
+                .unwrap()
+                .iter()
+                .map(|manifest| ArtifactId::id_manifest(manifest).unwrap())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(manifest_aids(&restored), manifest_aids(&storage));
+        assert!(Storage::<Sha256>::has_manifest_for_artifact(
+            &restored,
+            ArtifactId::id_str("target")
+        ));
+
+        assert!(matches!(
+            FileSystemStorage::restore_from_backup(
+                pathbuf![&test_dir, "missing"],
+                pathbuf![&test_dir, "other"]
+            ),
+            Err(Error::InvalidBackup(_))
+        ));
+
+        std::fs::remove_dir_all(&test_dir).unwrap();
+    }
+
+    #[test]
+    fn backup_fs_in_transaction() {
+        let test_dir = pathbuf![env!("CARGO_MANIFEST_DIR"), "test", "fs_storage_backup_txn"];
+        let _ = std::fs::remove_dir_all(&test_dir);
+        let mut storage = FileSystemStorage::new(pathbuf![&test_dir, "store"]).unwrap();
+
+        // The backup reuses the transaction's lock rather than waiting on it.
+        let backup = pathbuf![&test_dir, "backup"];
+        let stats = Storage::<Sha256>::transaction(&mut storage, |storage| {
+            storage.batch_write_manifests(&[manifest_for("input", Some("target"))])?;
+            Storage::<Sha256>::backup_to(storage, &backup)
+        })
+        .unwrap();
+        assert_eq!(stats.manifest_count, 1);
+
+        // Outside the transaction, the backup takes its own lock again.
+        let stats =
+            Storage::<Sha256>::backup_to(&storage, &pathbuf![&test_dir, "backup_after"]).unwrap();
+        assert_eq!(stats.manifest_count, 1);
+
+        std::fs::remove_dir_all(&test_dir).unwrap();
+    }
+
+    #[test]
+    fn backup_in_memory_to_fs() {
+        let test_dir = pathbuf![env!("CARGO_MANIFEST_DIR"), "test", "mem_storage_backup"];
+        let _ = std::fs::remove_dir_all(&test_dir);
+        let mut storage = InMemoryStorage::new();
+
+        let manifest = InputManifest::with_relations(
+            [Relation::from_artifact(ArtifactId::<Sha256>::id_str(
+                "input",
+            ))]
+            .into_iter(),
+        );
+        let manifest_aid = storage.write_manifest(&manifest).unwrap();
+
+        let stats = storage.backup_to(&test_dir).unwrap();
+        assert_eq!(stats.manifest_count, 1);
+
+        let backup = FileSystemStorage::new(&test_dir).unwrap();
+        assert!(backup.contains_manifest(manifest_aid));
+
+        std::fs::remove_dir_all(&test_dir).unwrap();
+    }
+
+    #[test]
+    fn find_manifests_by_input() {
+        let root = pathbuf![env!("CARGO_MANIFEST_DIR"), "test", "fs_storage_by_input"];
+        let mut fs_storage = FileSystemStorage::new(&root).unwrap();
+        let mut mem_storage = InMemoryStorage::new();
+
+        let shared = ArtifactId::<Sha256>::id_str("shared");
+        let other = ArtifactId::<Sha256>::id_str("other");
+        let uses_shared = InputManifest::with_relations(
+            [
+                Relation::from_artifact(shared),
+                Relation::from_artifact(other),
+            ]
+            .into_iter(),
+        );
+        let no_shared = InputManifest::with_relations([Relation::from_artifact(other)].into_iter());
+        let expected = ArtifactId::id_manifest(&uses_shared).unwrap();
+
+        for storage in [
+            &mut fs_storage as &mut dyn Storage<Sha256>,
+            &mut mem_storage,
+        ] {
+            let _ = storage.write_manifest(&uses_shared).unwrap();
+            let _ = storage.write_manifest(&no_shared).unwrap();
+
+            let found = storage.find_manifests_by_input(shared).unwrap();
+            assert_eq!(found.len(), 1);
+            assert_eq!(found[0].relations(), uses_shared.relations());
+
+            let ids = storage.find_manifest_ids_by_input(shared).unwrap();
+            assert_eq!(ids, vec![expected]);
+
+            assert_eq!(storage.find_manifest_ids_by_input(other).unwrap().len(), 2);
+        }
+
+        fs_storage.cleanup().unwrap();
+    }
+
+    #[test]
+    fn query_filters_and_paginates() {
+        let mut storage = InMemoryStorage::new();
+
+        let manifests = (0..4)
+            .map(|n| manifest_for(&format!("input_{}", n), Some(&format!("target_{}", n))))
+            .collect::<Vec<_>>();
+        storage.batch_write_manifests(&manifests).unwrap();
+
+        let all = storage.query(ManifestQuery::new()).unwrap();
+        assert_eq!(all.len(), 4);
+
+        let target = manifests[1].target().unwrap();
+        let by_target = storage
+            .query(ManifestQuery::new().with_target(target).build())
+            .unwrap();
+        assert_eq!(by_target.len(), 1);
+        assert_eq!(by_target[0].relations(), manifests[1].relations());
+
+        let manifest_aid = ArtifactId::id_manifest(&manifests[2]).unwrap();
+        let by_id = storage
+            .query(ManifestQuery::new().with_manifest_id(manifest_aid).build())
+            .unwrap();
+        assert_eq!(by_id.len(), 1);
+        assert_eq!(by_id[0].relations(), manifests[2].relations());
+
+        let input = ArtifactId::<Sha256>::id_str("input_2");
+        let by_input = storage
+            .query(ManifestQuery::new().with_input(input).build())
+            .unwrap();
+        assert_eq!(by_input.len(), 1);
+        assert_eq!(by_input[0].relations(), manifests[2].relations());
+
+        let paged = storage
+            .query(ManifestQuery::new().offset(1).limit(2).build())
+            .unwrap();
+        assert_eq!(paged.len(), 2);
+        assert_eq!(paged[0].relations(), manifests[1].relations());
+        assert_eq!(paged[1].relations(), manifests[2].relations());
+
+        let none = storage
+            .query(
+                ManifestQuery::new()
+                    .with_input(input)
+                    .with_target(target)
+                    .build(),
+            )
+            .unwrap();
+        assert!(none.is_empty());
+    }
+
+    #[test]
+    fn in_memory_storage_to_and_into_vec() {
+        let mut storage = InMemoryStorage::new();
+
+        let manifests = (0..3)
+            .map(|n| {
+                let input = ArtifactId::<Sha256>::id_str(format!("input_{}", n));
+                InputManifest::with_relations([Relation::from_artifact(input)].into_iter())
+            })
+            .collect::<Vec<_>>();
+        storage.batch_write_manifests(&manifests).unwrap();
+
+        let copied = storage.to_vec();
+        let owned = storage.into_vec();
+
+        for found in [copied, owned] {
+            assert_eq!(found.len(), manifests.len());
+            for (found, expected) in found.iter().zip(&manifests) {
+                assert_eq!(found.relations(), expected.relations());
+            }
+        }
+    }
+
+    /// Write manifests from two threads at once through a shared handle.
+    fn write_concurrently<S>(storage: S) -> S
+    where
+        S: Storage<Sha256> + Clone + Send + 'static,
+    {
+        let handles = (0..2)
+            .map(|thread_n| {
+                let mut storage = storage.clone();
+
+                thread::spawn(move || {
+                    for n in 0..10 {
+                        let input = ArtifactId::<Sha256>::id_str(format!("input_{thread_n}_{n}"));
+                        let manifest = InputManifest::with_relations(
+                            [Relation::from_artifact(input)].into_iter(),
+                        );
+                        let _ = storage.write_manifest(&manifest).unwrap();
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        storage
+    }
+
+    #[test]
+    fn mutex_storage_concurrent_writes() {
+        let storage = write_concurrently(Arc::new(Mutex::new(InMemoryStorage::new())));
+        assert_eq!(storage.get_manifests().unwrap().len(), 20);
+    }
+
+    #[test]
+    fn rwlock_storage_concurrent_writes() {
+        let storage = write_concurrently(Arc::new(RwLock::new(InMemoryStorage::new())));
+        assert_eq!(storage.get_manifests().unwrap().len(), 20);
+    }
+
+    #[test]
+    fn set_root_moves_storage() {
+        let first = pathbuf![env!("CARGO_MANIFEST_DIR"), "test", "fs_storage_set_root_1"];
+        let second = pathbuf![env!("CARGO_MANIFEST_DIR"), "test", "fs_storage_set_root_2"];
+        let mut storage = FileSystemStorage::with_root(&first).unwrap();
+
+        storage.set_root(&second).unwrap();
+        assert_eq!(storage.manifests_path(), pathbuf![&second, "manifests"]);
+
+        std::fs::remove_dir_all(&first).unwrap();
+        std::fs::remove_dir_all(&second).unwrap();
+    }
+
+    #[test]
+    fn store_validity_checks() {
+        let root = pathbuf![env!("CARGO_MANIFEST_DIR"), "test", "fs_storage_validity"];
+        let _ = std::fs::remove_dir_all(&root);
+
+        let missing = FileSystemStorage::new_unchecked(&root);
+        assert!(!missing.root_exists());
+        assert!(!missing.is_valid_store().unwrap());
+
+        let mut storage = FileSystemStorage::new(&root).unwrap();
+        assert!(storage.root_exists());
+        assert!(!storage.is_valid_store().unwrap());
+
+        let manifest = InputManifest::<Sha256>::with_relations([].into_iter());
+        let manifest_aid = storage.write_manifest(&manifest).unwrap();
+        assert!(storage.manifest_path(manifest_aid).exists());
+        assert!(storage.is_valid_store().unwrap());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn remove_all_manifests_clears_storage() {
+        let root = pathbuf![env!("CARGO_MANIFEST_DIR"), "test", "fs_storage_remove_all"];
+        let mut fs_storage = FileSystemStorage::new(&root).unwrap();
+        let mut mem_storage = InMemoryStorage::new();
+
+        let manifests = (0..3)
+            .map(|n| {
+                let input = ArtifactId::<Sha256>::id_str(format!("input_{}", n));
+                let target = ArtifactId::<Sha256>::id_str(format!("target_{}", n));
+                let mut manifest =
+                    InputManifest::with_relations([Relation::from_artifact(input)].into_iter());
+                manifest.set_target(Some(target));
+                manifest
+            })
+            .collect::<Vec<_>>();
+
+        for storage in [
+            &mut fs_storage as &mut dyn Storage<Sha256>,
+            &mut mem_storage,
+        ] {
+            storage.batch_write_manifests(&manifests).unwrap();
+            assert_eq!(storage.remove_all_manifests().unwrap(), 3);
+            assert!(storage.get_manifests().unwrap().is_empty());
+            assert!(!storage.has_manifest_for_artifact(manifests[0].target().unwrap()));
+            assert_eq!(storage.remove_all_manifests().unwrap(), 0);
+        }
+
+        fs_storage.cleanup().unwrap();
+    }
+
+    #[test]
+    fn transaction_rolls_back_on_error() {
+        let root = pathbuf![env!("CARGO_MANIFEST_DIR"), "test", "fs_storage_transaction"];
+        let mut fs_storage = FileSystemStorage::new(&root).unwrap();
+        let mut mem_storage = InMemoryStorage::new();
+
+        fn check<S: Storage<Sha256>>(
+            storage: &mut S,
+            first: &InputManifest<Sha256>,
+            second: &InputManifest<Sha256>,
+        ) {
+            storage
+                .batch_write_manifests(std::slice::from_ref(first))
+                .unwrap();
+
+            // A failed transaction leaves no trace of its writes or removals.
+            let result: crate::Result<()> = storage.transaction(|storage| {
+                storage.batch_write_manifests(std::slice::from_ref(second))?;
+                storage.remove_all_manifests()?;
+                Err(Error::TransactionClosed)
+            });
+            assert!(matches!(result, Err(Error::TransactionClosed)));
+            assert_eq!(storage.get_manifests().unwrap().len(), 1);
+            assert!(storage.has_manifest_for_artifact(first.target().unwrap()));
+            assert!(!storage.has_manifest_for_artifact(second.target().unwrap()));
+
+            // A successful transaction keeps its changes.
+            let written = storage
+                .transaction(|storage| storage.batch_write_manifests(std::slice::from_ref(second)))
+                .unwrap();
+            assert_eq!(written.len(), 1);
+            assert_eq!(storage.get_manifests().unwrap().len(), 2);
+            assert!(storage.has_manifest_for_artifact(second.target().unwrap()));
+        }
+
+        let first = manifest_for("input_0", Some("target_0"));
+        let second = manifest_for("input_1", Some("target_1"));
+        check(&mut fs_storage, &first, &second);
+        check(&mut mem_storage, &first, &second);
+
+        fs_storage.cleanup().unwrap();
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn prefetch_matches_sequential_reads() {
+        let root = pathbuf![env!("CARGO_MANIFEST_DIR"), "test", "fs_storage_prefetch"];
+        let mut storage = FileSystemStorage::new(&root)
+            .unwrap()
+            .with_prefetch_factor(4);
+
+        let manifests = (0..10)
+            .map(|n| {
+                let input = ArtifactId::<Sha256>::id_str(format!("input_{}", n));
+                let mut manifest =
+                    InputManifest::with_relations([Relation::from_artifact(input)].into_iter());
+
+                if n % 2 == 0 {
+                    let target = ArtifactId::<Sha256>::id_str(format!("target_{}", n));
+                    manifest.set_target(Some(target));
+                }
+
+                manifest
+            })
+            .collect::<Vec<_>>();
+
+        storage.batch_write_manifests(&manifests).unwrap();
+
+        let prefetched: Vec<InputManifest<Sha256>> = storage.get_manifests().unwrap();
+        let sequential: Vec<InputManifest<Sha256>> =
+            storage.with_prefetch_factor(1).get_manifests().unwrap();
+
+        let summarize = |manifests: &[InputManifest<Sha256>]| {
+            manifests
+                .iter()
+                .map(|m| (ArtifactId::id_manifest(m).unwrap(), m.target()))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(prefetched.len(), manifests.len());
+        assert_eq!(summarize(&prefetched), summarize(&sequential));
+        assert_eq!(
+            prefetched.iter().filter(|m| m.target().is_some()).count(),
+            5
+        );
+
+        FileSystemStorage::new(&root).unwrap().cleanup().unwrap();
+    }
+}
@@ -0,0 +1,184 @@
+//! Build queries for manifests held in a [`Storage`].
+
+use crate::hashes::SupportedHash;
+#[cfg(doc)]
+use crate::storage::Storage;
+use crate::ArtifactId;
+use crate::InputManifest;
+use crate::Result;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+
+/// A query for manifests, run with [`Storage::query`].
+///
+/// Every criteria set on the query must match for a manifest to be
+/// included. A query with no criteria matches every manifest.
+///
+/// # Example
+///
+/// ```rust
+/// # use omnibor::ArtifactId;
+/// # use omnibor::hashes::Sha256;
+/// # use omnibor::storage::query::ManifestQuery;
+/// let input = ArtifactId::<Sha256>::id_str("input");
+/// let query = ManifestQuery::new().with_input(input).limit(10).build();
+/// assert_eq!(query.input(), Some(input));
+/// ```
+pub struct ManifestQuery<H: SupportedHash> {
+    target: Option<ArtifactId<H>>,
+    manifest_id: Option<ArtifactId<H>>,
+    input: Option<ArtifactId<H>>,
+    limit: Option<usize>,
+    offset: usize,
+}
+
+impl<H: SupportedHash> ManifestQuery<H> {
+    /// Start a query which matches every manifest.
+    pub fn new() -> Self {
+        ManifestQuery {
+            target: None,
+            manifest_id: None,
+            input: None,
+            limit: None,
+            offset: 0,
+        }
+    }
+
+    /// Only match the manifest for the given target artifact.
+    pub fn with_target(mut self, target_aid: ArtifactId<H>) -> Self {
+        self.target = Some(target_aid);
+        self
+    }
+
+    /// Only match the manifest with the given ID.
+    pub fn with_manifest_id(mut self, manifest_aid: ArtifactId<H>) -> Self {
+        self.manifest_id = Some(manifest_aid);
+        self
+    }
+
+    /// Only match manifests which include the given artifact as an input.
+    pub fn with_input(mut self, input_aid: ArtifactId<H>) -> Self {
+        self.input = Some(input_aid);
+        self
+    }
+
+    /// Return at most `limit` manifests.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Skip the first `offset` matching manifests.
+    pub fn offset(mut self, offset: usize) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Finish building the query.
+    pub fn build(self) -> ManifestQuery<H> {
+        self
+    }
+
+    /// Get the target artifact the query matches, if set.
+    pub fn target(&self) -> Option<ArtifactId<H>> {
+        self.target
+    }
+
+    /// Get the manifest ID the query matches, if set.
+    pub fn manifest_id(&self) -> Option<ArtifactId<H>> {
+        self.manifest_id
+    }
+
+    /// Get the input artifact the query matches, if set.
+    pub fn input(&self) -> Option<ArtifactId<H>> {
+        self.input
+    }
+
+    /// Get the maximum number of manifests to return, if set.
+    pub fn max_results(&self) -> Option<usize> {
+        self.limit
+    }
+
+    /// Get the number of matching manifests to skip.
+    pub fn skip(&self) -> usize {
+        self.offset
+    }
+
+    /// Check if a manifest matches the query's criteria.
+    ///
+    /// This ignores the limit and offset.
+    pub fn matches(&self, manifest: &InputManifest<H>) -> Result<bool> {
+        if let Some(target_aid) = self.target {
+            if manifest.target() != Some(target_aid) {
+                return Ok(false);
+            }
+        }
+
+        if let Some(input_aid) = self.input {
+            let has_input = manifest
+                .relations()
+                .iter()
+                .any(|relation| relation.artifact() == input_aid);
+
+            if !has_input {
+                return Ok(false);
+            }
+        }
+
+        if let Some(manifest_aid) = self.manifest_id {
+            if ArtifactId::id_manifest(manifest)? != manifest_aid {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Apply the query to a list of manifests.
+    pub(crate) fn apply(&self, manifests: Vec<InputManifest<H>>) -> Result<Vec<InputManifest<H>>> {
+        let mut matched = Vec::new();
+
+        for manifest in manifests {
+            if self.matches(&manifest)? {
+                matched.push(manifest);
+            }
+        }
+
+        Ok(matched
+            .into_iter()
+            .skip(self.offset)
+            .take(self.limit.unwrap_or(usize::MAX))
+            .collect())
+    }
+}
+
+impl<H: SupportedHash> Default for ManifestQuery<H> {
+    fn default() -> Self {
+        ManifestQuery::new()
+    }
+}
+
+impl<H: SupportedHash> Clone for ManifestQuery<H> {
+    fn clone(&self) -> Self {
+        ManifestQuery {
+            target: self.target,
+            manifest_id: self.manifest_id,
+            input: self.input,
+            limit: self.limit,
+            offset: self.offset,
+        }
+    }
+}
+
+impl<H: SupportedHash> Debug for ManifestQuery<H> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.debug_struct("ManifestQuery")
+            .field("target", &self.target)
+            .field("manifest_id", &self.manifest_id)
+            .field("input", &self.input)
+            .field("limit", &self.limit)
+            .field("offset", &self.offset)
+            .finish()
+    }
+}
@@ -6,6 +6,9 @@ use gitoid::HashAlgorithm;
 /// Marker trait for hash algorithms supported for constructing [`ArtifactId`]s.
 pub trait SupportedHash: Sealed {
     type HashAlgorithm: HashAlgorithm;
+
+    /// The raw bytes of a hash made with the algorithm, like `[u8; 32]`.
+    type RawHash: AsRef<[u8]>;
 }
 
 /// The SHA-256 hashing algorithm.
@@ -18,4 +21,40 @@ impl Sealed for Sha256 {}
 
 impl SupportedHash for Sha256 {
     type HashAlgorithm = gitoid::rustcrypto::Sha256;
+    type RawHash = [u8; 32];
+}
+
+/// The BLAKE3 hashing algorithm, with 256-bit output.
+///
+/// BLAKE3 is much faster than SHA-256, but [`ArtifactId`]s made with it
+/// won't match those made by tools which only support SHA-256.
+#[cfg(feature = "hash-blake3")]
+pub struct Blake3 {
+    #[doc(hidden)]
+    _private: (),
+}
+
+#[cfg(feature = "hash-blake3")]
+impl Sealed for Blake3 {}
+
+#[cfg(feature = "hash-blake3")]
+impl SupportedHash for Blake3 {
+    type HashAlgorithm = gitoid::rustcrypto::Blake3;
+    type RawHash = [u8; 32];
+}
+
+/// The SHA3-256 hashing algorithm.
+#[cfg(feature = "hash-sha3-256")]
+pub struct Sha3_256 {
+    #[doc(hidden)]
+    _private: (),
+}
+
+#[cfg(feature = "hash-sha3-256")]
+impl Sealed for Sha3_256 {}
+
+#[cfg(feature = "hash-sha3-256")]
+impl SupportedHash for Sha3_256 {
+    type HashAlgorithm = gitoid::rustcrypto::Sha3_256;
+    type RawHash = [u8; 32];
 }
@@ -16,6 +16,446 @@ fn artifact_id_sha256_size() {
     assert_eq!(size_of::<ArtifactId<Sha256>>(), Sha256Alg::output_size());
 }
 
+mod newline_test {
+    use crate::hashes::Sha256;
+    use crate::ArtifactId;
+    use crate::IntoArtifactId;
+    use std::io::Cursor;
+
+    /// Check a CRLF input read through a `Cursor` matches the LF bytes.
+    fn assert_cursor_normalized(crlf: &[u8], lf: &[u8]) {
+        let expected = ArtifactId::<Sha256>::id_bytes(lf);
+
+        let by_reader = ArtifactId::<Sha256>::id_reader(Cursor::new(crlf.to_vec())).unwrap();
+        let by_trait: ArtifactId<Sha256> = Cursor::new(crlf.to_vec()).into_artifact_id().unwrap();
+        let by_bytes = ArtifactId::<Sha256>::id_bytes(crlf);
+
+        assert_eq!(by_reader, expected);
+        assert_eq!(by_trait, expected);
+        assert_eq!(by_bytes, expected);
+    }
+
+    #[test]
+    fn cursor_single_crlf() {
+        assert_cursor_normalized(b"hello\r\n", b"hello\n");
+    }
+
+    #[test]
+    fn cursor_multiple_crlf() {
+        assert_cursor_normalized(b"a\r\nb\r\nc\r\n", b"a\nb\nc\n");
+    }
+
+    #[test]
+    fn cursor_only_crlf() {
+        assert_cursor_normalized(b"\r\n\r\n", b"\n\n");
+    }
+
+    #[test]
+    fn lone_cr_is_preserved() {
+        assert_ne!(
+            ArtifactId::<Sha256>::id_bytes(b"a\rb"),
+            ArtifactId::<Sha256>::id_bytes(b"ab")
+        );
+    }
+
+    #[test]
+    fn reader_with_length_uses_raw_length() {
+        let id = ArtifactId::<Sha256>::id_reader_with_length(Cursor::new(b"hello\r\n"), 7).unwrap();
+        assert_eq!(id, ArtifactId::id_bytes(b"hello\n"));
+        assert!(ArtifactId::<Sha256>::id_reader_with_length(Cursor::new(b"hello\r\n"), 6).is_err());
+    }
+
+    #[test]
+    fn unseekable_reader_normalizes() {
+        let stream: &[u8] = b"a\r\nb\r\n";
+        let id = ArtifactId::<Sha256>::id_unseekable_reader(stream).unwrap();
+        assert_eq!(id, ArtifactId::id_bytes(b"a\nb\n"));
+    }
+
+    #[test]
+    fn async_reader_normalizes() {
+        tokio_test::block_on(async {
+            let id = ArtifactId::<Sha256>::id_async_reader(Cursor::new(b"a\r\nb\r\n"))
+                .await
+                .unwrap();
+            assert_eq!(id, ArtifactId::id_bytes(b"a\nb\n"));
+        });
+    }
+
+    #[test]
+    fn async_reader_with_crlf_across_chunks() {
+        let mut content = vec![b'x'; 8 * 1024 - 1];
+        content.extend_from_slice(b"\r\nend\r\n");
+
+        tokio_test::block_on(async {
+            let id = ArtifactId::<Sha256>::id_async_reader(Cursor::new(&content))
+                .await
+                .unwrap();
+            assert_eq!(id, ArtifactId::id_bytes(&content));
+        });
+    }
+
+    #[test]
+    fn reader_with_length_is_bounded() {
+        // A huge expected length mustn't be allocated up front.
+        let result = ArtifactId::<Sha256>::id_reader_with_length(Cursor::new(b"a\r\n"), usize::MAX);
+        assert!(result.is_err());
+
+        // Reading stops one byte past the expected length.
+        let mut reader = Cursor::new(b"hello\r\nworld");
+        assert!(ArtifactId::<Sha256>::id_reader_with_length(&mut reader, 4).is_err());
+        assert_eq!(reader.position(), 5);
+
+        tokio_test::block_on(async {
+            let result = ArtifactId::<Sha256>::id_async_reader_with_length(
+                Cursor::new(b"a\r\n"),
+                usize::MAX,
+            )
+            .await;
+            assert!(result.is_err());
+
+            let id = ArtifactId::<Sha256>::id_async_reader_with_length(Cursor::new(b"a\r\n"), 3)
+                .await
+                .unwrap();
+            assert_eq!(id, ArtifactId::id_bytes(b"a\n"));
+        });
+    }
+}
+
+mod compare_files_test {
+    use crate::hashes::Sha256;
+    use crate::ArtifactId;
+    use pathbuf::pathbuf;
+    use std::fs::remove_file;
+    use std::fs::write;
+    use std::path::PathBuf;
+
+    /// Write two files under `test/` for a comparison test.
+    fn write_pair(name: &str, a: &[u8], b: &[u8]) -> (PathBuf, PathBuf) {
+        let dir = pathbuf![env!("CARGO_MANIFEST_DIR"), "test"];
+        let a_path = dir.join(format!("compare_{name}_a"));
+        let b_path = dir.join(format!("compare_{name}_b"));
+        write(&a_path, a).unwrap();
+        write(&b_path, b).unwrap();
+        (a_path, b_path)
+    }
+
+    fn compare(name: &str, a: &[u8], b: &[u8]) -> (bool, bool) {
+        let (a, b) = write_pair(name, a, b);
+        let sync = ArtifactId::<Sha256>::compare_files(&a, &b).unwrap();
+        let async_ =
+            tokio_test::block_on(ArtifactId::<Sha256>::compare_files_async(&a, &b)).unwrap();
+        remove_file(a).unwrap();
+        remove_file(b).unwrap();
+        (sync, async_)
+    }
+
+    #[test]
+    fn identical_files_match() {
+        assert_eq!(compare("identical", b"hello", b"hello"), (true, true));
+    }
+
+    #[test]
+    fn different_files_differ() {
+        assert_eq!(compare("different", b"hello", b"world"), (false, false));
+    }
+
+    #[test]
+    fn crlf_and_lf_files_match() {
+        assert_eq!(compare("crlf", b"a\r\nb\r\n", b"a\nb\n"), (true, true));
+    }
+
+    #[test]
+    fn missing_file_fails() {
+        let missing = pathbuf![env!("CARGO_MANIFEST_DIR"), "test", "compare_missing"];
+        let present = pathbuf![
+            env!("CARGO_MANIFEST_DIR"),
+            "test",
+            "data",
+            "hello_world.txt"
+        ];
+        assert!(ArtifactId::<Sha256>::compare_files(&present, &missing).is_err());
+    }
+}
+
+mod dir_entry_test {
+    use crate::hashes::Sha256;
+    use crate::ArtifactId;
+    use crate::Error;
+    use crate::IntoArtifactId;
+    use pathbuf::pathbuf;
+    use std::fs::read_dir;
+    use walkdir::WalkDir;
+
+    #[test]
+    fn dir_entry_matches_path() {
+        let dir = pathbuf![env!("CARGO_MANIFEST_DIR"), "test", "data"];
+        let entry = read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap())
+            .find(|entry| entry.file_name() == "hello_world.txt")
+            .unwrap();
+
+        let by_ref: ArtifactId<Sha256> = (&entry).into_artifact_id().unwrap();
+        let by_path: ArtifactId<Sha256> = entry.path().as_path().into_artifact_id().unwrap();
+        let by_value: ArtifactId<Sha256> = entry.into_artifact_id().unwrap();
+        assert_eq!(by_ref, by_path);
+        assert_eq!(by_value, by_path);
+    }
+
+    #[test]
+    fn dir_entry_for_directory_fails() {
+        let dir = pathbuf![env!("CARGO_MANIFEST_DIR"), "test"];
+        let entry = read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap())
+            .find(|entry| entry.file_name() == "data")
+            .unwrap();
+
+        let result: Result<ArtifactId<Sha256>, _> = entry.into_artifact_id();
+        assert!(matches!(result, Err(Error::CannotIdentifyDirectory(_))));
+    }
+
+    #[test]
+    fn walkdir_entry_matches_path() {
+        let dir = pathbuf![env!("CARGO_MANIFEST_DIR"), "test", "data"];
+        let mut entries = WalkDir::new(&dir).into_iter().map(|entry| entry.unwrap());
+
+        // The first entry is the root directory itself.
+        let root = entries.next().unwrap();
+        let result: Result<ArtifactId<Sha256>, _> = root.into_artifact_id();
+        assert!(matches!(result, Err(Error::CannotIdentifyDirectory(_))));
+
+        let entry = entries
+            .find(|entry| entry.file_name() == "hello_world.txt")
+            .unwrap();
+        let by_ref: ArtifactId<Sha256> = (&entry).into_artifact_id().unwrap();
+        let by_path: ArtifactId<Sha256> = entry.path().into_artifact_id().unwrap();
+        let by_value: ArtifactId<Sha256> = entry.into_artifact_id().unwrap();
+        assert_eq!(by_ref, by_path);
+        assert_eq!(by_value, by_path);
+    }
+}
+
+#[cfg(unix)]
+mod symlink_test {
+    use crate::hashes::Sha256;
+    use crate::ArtifactId;
+    use pathbuf::pathbuf;
+    use std::fs::remove_file;
+    use std::os::unix::fs::symlink;
+    use std::path::Path;
+
+    #[test]
+    fn symlink_target_and_content() {
+        let target = pathbuf![
+            env!("CARGO_MANIFEST_DIR"),
+            "test",
+            "data",
+            "hello_world.txt"
+        ];
+        let link = pathbuf![env!("CARGO_MANIFEST_DIR"), "test", "symlink_test_link"];
+        let _ = remove_file(&link);
+        symlink(&target, &link).unwrap();
+
+        let by_target = ArtifactId::<Sha256>::compute_for_symlink_target_bytes(&link).unwrap();
+        let by_content = ArtifactId::<Sha256>::compute_for_symlink_content(&link).unwrap();
+        remove_file(&link).unwrap();
+
+        assert_eq!(by_target, ArtifactId::id_str(target.to_str().unwrap()));
+        assert_eq!(
+            by_content.as_hex(),
+            "fee53a18d32820613c0527aa79be5cb30173c823a9b448fa4817767cc84c6f03"
+        );
+    }
+
+    #[test]
+    fn symlink_target_requires_symlink() {
+        let not_link = Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml");
+        assert!(ArtifactId::<Sha256>::compute_for_symlink_target_bytes(&not_link).is_err());
+    }
+}
+
+mod str_eq_test {
+    use crate::hashes::Sha256;
+    use crate::ArtifactId;
+
+    const HELLO_WORLD: &str =
+        "gitoid:blob:sha256:fee53a18d32820613c0527aa79be5cb30173c823a9b448fa4817767cc84c6f03";
+
+    #[test]
+    fn artifact_id_eq_str() {
+        let id = ArtifactId::<Sha256>::id_str("hello world");
+
+        assert_eq!(id, HELLO_WORLD);
+        assert_eq!(HELLO_WORLD, id);
+        assert_eq!(id, *HELLO_WORLD);
+        assert_eq!(*HELLO_WORLD, id);
+    }
+
+    #[test]
+    fn artifact_id_ne_other_str() {
+        let id = ArtifactId::<Sha256>::id_str("hello, world");
+
+        assert_ne!(id, HELLO_WORLD);
+        assert_ne!(id, "not a gitoid");
+    }
+}
+
+mod try_collect_test {
+    use crate::hashes::Sha256;
+    use crate::ArtifactId;
+    use crate::Error;
+
+    #[test]
+    fn collects_exact_bytes() {
+        let id = ArtifactId::<Sha256>::id_str("hello, world");
+        let collected = ArtifactId::try_collect(id.as_bytes().iter().copied()).unwrap();
+        assert_eq!(collected, id);
+    }
+
+    #[test]
+    fn wrong_byte_count_fails() {
+        for count in [0, 31, 33] {
+            let result = ArtifactId::<Sha256>::try_collect(std::iter::repeat_n(0, count));
+            assert!(matches!(
+                result,
+                Err(Error::WrongByteSliceLength { expected: 32, got }) if got == count
+            ));
+        }
+    }
+}
+
+mod index_test {
+    use crate::hashes::Sha256;
+    use crate::ArtifactId;
+    use crate::ArtifactIdIndex;
+    use crate::IndexEntry;
+    use std::path::Path;
+    use std::path::PathBuf;
+    use std::time::Duration;
+    use std::time::SystemTime;
+    use std::time::UNIX_EPOCH;
+
+    /// An entry for an artifact at `path`, built `secs` after the epoch.
+    fn entry(path: &str, secs: u64) -> IndexEntry {
+        IndexEntry {
+            path: Some(PathBuf::from(path)),
+            build_time: Some(time(secs)),
+            ..IndexEntry::default()
+        }
+    }
+
+    /// The time `secs` after the epoch.
+    fn time(secs: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(secs)
+    }
+
+    fn sample_index() -> (ArtifactIdIndex<Sha256>, [ArtifactId<Sha256>; 3]) {
+        let aids = [
+            ArtifactId::id_str("first build"),
+            ArtifactId::id_str("second build"),
+            ArtifactId::id_str("library"),
+        ];
+
+        let mut index = ArtifactIdIndex::new();
+        index.insert(aids[0], entry("target/app", 100));
+        index.insert(aids[1], entry("target/app", 200));
+        index.insert(aids[2], entry("target/lib.so", 150));
+        (index, aids)
+    }
+
+    #[test]
+    fn get_and_replace_entries() {
+        let (mut index, aids) = sample_index();
+        assert_eq!(index.len(), 3);
+        assert_eq!(index.get(aids[2]), Some(&entry("target/lib.so", 150)));
+        assert_eq!(index.get(ArtifactId::id_str("missing")), None);
+
+        let old = index.insert(aids[2], entry("target/lib.a", 150));
+        assert_eq!(old, Some(entry("target/lib.so", 150)));
+        assert_eq!(index.len(), 3);
+    }
+
+    #[test]
+    fn find_by_path() {
+        let (index, aids) = sample_index();
+        let mut found = index.find_by_path(Path::new("target/app"));
+        found.sort();
+
+        let mut expected = vec![aids[0], aids[1]];
+        expected.sort();
+        assert_eq!(found, expected);
+        assert!(index.find_by_path(Path::new("target/other")).is_empty());
+    }
+
+    #[test]
+    fn find_by_time_range_is_half_open() {
+        let (mut index, aids) = sample_index();
+        index.insert(ArtifactId::id_str("untimed"), IndexEntry::default());
+
+        let mut found = index.find_by_time_range(time(100), time(200));
+        found.sort();
+
+        let mut expected = vec![aids[0], aids[2]];
+        expected.sort();
+        assert_eq!(found, expected);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let (mut index, aids) = sample_index();
+        index
+            .entry(aids[0])
+            .or_default()
+            .metadata
+            .insert(String::from("profile"), String::from("release"));
+
+        let json = serde_json::to_string(&index).unwrap();
+        assert!(json.contains(&aids[0].to_string()));
+
+        let round_tripped: ArtifactIdIndex<Sha256> = serde_json::from_str(&json).unwrap();
+        assert_eq!(round_tripped, index);
+    }
+}
+
+#[cfg(feature = "futures")]
+mod stream_test {
+    use crate::hashes::Sha256;
+    use crate::ArtifactId;
+    use futures_util::stream;
+    use futures_util::StreamExt as _;
+    use pathbuf::pathbuf;
+    use std::fs::File;
+
+    #[test]
+    fn identify_stream_keeps_order_and_errors() {
+        let present = pathbuf![
+            env!("CARGO_MANIFEST_DIR"),
+            "test",
+            "data",
+            "hello_world.txt"
+        ];
+        let missing = pathbuf![env!("CARGO_MANIFEST_DIR"), "test", "data", "missing.txt"];
+
+        let results = tokio_test::block_on(
+            ArtifactId::<Sha256>::identify_stream(stream::iter([
+                present.clone(),
+                missing,
+                present.clone(),
+            ]))
+            .collect::<Vec<_>>(),
+        );
+
+        let expected = ArtifactId::<Sha256>::id_reader(File::open(&present).unwrap()).unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].as_ref().unwrap(), &expected);
+        assert!(results[1].is_err());
+        assert_eq!(results[2].as_ref().unwrap(), &expected);
+    }
+}
+
 #[cfg(feature = "serde")]
 mod serde_test {
     use crate::hashes::Sha256;
@@ -31,3 +471,1091 @@ mod serde_test {
         assert_tokens(&id, &[Token::Str("gitoid:blob:sha256:7d0be525d6521168c74051e5ab1b99e3b6d1c962fba763818f1954ab9e1c821a")]);
     }
 }
+
+#[cfg(feature = "serde")]
+mod serde_bytes_test {
+    use crate::hashes::Sha256;
+    use crate::ArtifactId;
+    use crate::ArtifactIdBytes;
+
+    #[test]
+    fn json_serializes_as_string() {
+        let id = ArtifactId::<Sha256>::id_str("hello, world");
+        let json = serde_json::to_string(&id).unwrap();
+        assert_eq!(json, format!("\"{}\"", id));
+    }
+
+    #[test]
+    fn cbor_serializes_as_bytes() {
+        let id = ArtifactId::<Sha256>::id_str("hello, world");
+
+        let mut cbor = Vec::new();
+        ciborium::into_writer(&ArtifactIdBytes(id), &mut cbor).unwrap();
+
+        // A CBOR byte string header with a one-byte length, then the hash.
+        assert_eq!(&cbor[..2], &[0x58, 32]);
+        assert_eq!(&cbor[2..], id.as_bytes());
+
+        let round_trip: ArtifactIdBytes<Sha256> = ciborium::from_reader(&cbor[..]).unwrap();
+        assert_eq!(round_trip.artifact_id(), id);
+    }
+
+    #[test]
+    fn deserialize_from_byte_sequence() {
+        let id = ArtifactId::<Sha256>::id_str("hello, world");
+        let json = serde_json::to_string(id.as_bytes()).unwrap();
+        let mut deserializer = serde_json::Deserializer::from_str(&json);
+        assert_eq!(
+            ArtifactId::<Sha256>::deserialize_from_bytes(&mut deserializer).unwrap(),
+            id
+        );
+    }
+
+    #[test]
+    fn deserialize_from_wrong_length_fails() {
+        let mut deserializer = serde_json::Deserializer::from_str("[1, 2, 3]");
+        assert!(ArtifactId::<Sha256>::deserialize_from_bytes(&mut deserializer).is_err());
+    }
+}
+
+#[cfg(feature = "postgres")]
+mod postgres_test {
+    use crate::hashes::Sha256;
+    use crate::ArtifactId;
+    use crate::ArtifactIdPgBinary;
+    use bytes::BytesMut;
+    use postgres_types::FromSql;
+    use postgres_types::ToSql;
+    use postgres_types::Type;
+
+    #[test]
+    fn text_round_trip() {
+        let id = ArtifactId::<Sha256>::id_str("hello, world");
+
+        let mut raw = BytesMut::new();
+        id.to_sql_checked(&Type::TEXT, &mut raw).unwrap();
+        assert_eq!(&raw[..], id.to_string().as_bytes());
+
+        let round_trip = ArtifactId::<Sha256>::from_sql(&Type::TEXT, &raw).unwrap();
+        assert_eq!(round_trip, id);
+    }
+
+    #[test]
+    fn bytea_round_trip() {
+        let id = ArtifactId::<Sha256>::id_str("hello, world");
+
+        let mut raw = BytesMut::new();
+        ArtifactIdPgBinary(id)
+            .to_sql_checked(&Type::BYTEA, &mut raw)
+            .unwrap();
+        assert_eq!(&raw[..], id.as_bytes());
+
+        let round_trip = ArtifactIdPgBinary::<Sha256>::from_sql(&Type::BYTEA, &raw).unwrap();
+        assert_eq!(round_trip.artifact_id(), id);
+    }
+
+    #[test]
+    fn wrong_types_are_rejected() {
+        let id = ArtifactId::<Sha256>::id_str("hello, world");
+        let mut raw = BytesMut::new();
+
+        assert!(id.to_sql_checked(&Type::BYTEA, &mut raw).is_err());
+        assert!(ArtifactIdPgBinary(id)
+            .to_sql_checked(&Type::TEXT, &mut raw)
+            .is_err());
+    }
+}
+
+#[cfg(feature = "rusqlite")]
+mod rusqlite_test {
+    use crate::hashes::Sha256;
+    use crate::ArtifactId;
+    use crate::ArtifactIdSqliteBlob;
+    use rusqlite::types::FromSqlError;
+    use rusqlite::Connection;
+    use rusqlite::Error as SqlError;
+
+    fn connection() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("CREATE TABLE ids (text_id TEXT, blob_id BLOB);")
+            .unwrap();
+        conn
+    }
+
+    #[test]
+    fn text_round_trip() {
+        let conn = connection();
+        let id = ArtifactId::<Sha256>::id_str("hello, world");
+
+        conn.execute("INSERT INTO ids (text_id) VALUES (?1)", [id])
+            .unwrap();
+
+        let (stored, round_trip): (String, ArtifactId<Sha256>) = conn
+            .query_row("SELECT text_id, text_id FROM ids", [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap();
+
+        assert_eq!(stored, id.to_string());
+        assert_eq!(round_trip, id);
+    }
+
+    #[test]
+    fn blob_round_trip() {
+        let conn = connection();
+        let id = ArtifactId::<Sha256>::id_str("hello, world");
+
+        conn.execute(
+            "INSERT INTO ids (blob_id) VALUES (?1)",
+            [ArtifactIdSqliteBlob(id)],
+        )
+        .unwrap();
+
+        let (stored, round_trip): (Vec<u8>, ArtifactIdSqliteBlob<Sha256>) = conn
+            .query_row("SELECT blob_id, blob_id FROM ids", [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap();
+
+        assert_eq!(stored, id.as_bytes());
+        assert_eq!(round_trip.artifact_id(), id);
+    }
+
+    #[test]
+    fn wrong_blob_size_fails() {
+        let conn = connection();
+
+        conn.execute("INSERT INTO ids (blob_id) VALUES (?1)", [vec![0u8; 20]])
+            .unwrap();
+
+        let result = conn.query_row("SELECT blob_id FROM ids", [], |row| {
+            row.get::<_, ArtifactIdSqliteBlob<Sha256>>(0)
+        });
+
+        assert!(matches!(
+            result,
+            Err(SqlError::FromSqlConversionFailure(
+                0,
+                _,
+                e
+            )) if matches!(
+                e.downcast_ref::<FromSqlError>(),
+                Some(FromSqlError::InvalidBlobSize { expected_size: 32, blob_size: 20 })
+            )
+        ));
+    }
+}
+
+#[cfg(feature = "bytes")]
+mod bytes_test {
+    use crate::hashes::Sha256;
+    use crate::ArtifactId;
+    use crate::IntoArtifactId;
+    use bytes::Bytes;
+
+    #[test]
+    fn bytes_match_slice_artifact_id() {
+        let content = Bytes::from_static(b"hello, world");
+        let expected = ArtifactId::<Sha256>::id_str("hello, world");
+
+        let by_ref: ArtifactId<Sha256> = (&content).into_artifact_id().unwrap();
+        let by_value: ArtifactId<Sha256> = content.into_artifact_id().unwrap();
+
+        assert_eq!(by_ref, expected);
+        assert_eq!(by_value, expected);
+    }
+}
+
+#[cfg(feature = "tempfile")]
+mod tempfile_test {
+    use crate::hashes::Sha256;
+    use crate::ArtifactId;
+    use crate::IntoArtifactId;
+    use std::io::Write as _;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn named_temp_file_artifact_id() {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(b"hello, world").unwrap();
+        let expected = ArtifactId::<Sha256>::id_str("hello, world");
+
+        let by_ref: ArtifactId<Sha256> = (&file).into_artifact_id().unwrap();
+        let by_value: ArtifactId<Sha256> = file.into_artifact_id().unwrap();
+
+        assert_eq!(by_ref, expected);
+        assert_eq!(by_value, expected);
+    }
+}
+
+#[cfg(feature = "gzip")]
+mod gzip_test {
+    use crate::hashes::Sha256;
+    use crate::ArtifactId;
+    use crate::IntoArtifactId;
+    use flate2::read::GzDecoder;
+    use flate2::read::ZlibDecoder;
+    use flate2::write::GzEncoder;
+    use flate2::write::ZlibEncoder;
+    use flate2::Compression;
+    use std::io::Write as _;
+
+    #[test]
+    fn gzip_identifies_decompressed_content() {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(b"hello, world").unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        let aid: ArtifactId<Sha256> = GzDecoder::new(&compressed[..]).into_artifact_id().unwrap();
+        assert_eq!(aid, ArtifactId::id_str("hello, world"));
+    }
+
+    #[test]
+    fn zlib_identifies_decompressed_content() {
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(b"hello, world").unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        let aid: ArtifactId<Sha256> = ZlibDecoder::new(&compressed[..])
+            .into_artifact_id()
+            .unwrap();
+        assert_eq!(aid, ArtifactId::id_str("hello, world"));
+    }
+
+    #[test]
+    fn gzip_encoder_identifies_compressed_content() {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(b"hello, world").unwrap();
+        let aid: ArtifactId<Sha256> = encoder.into_artifact_id().unwrap();
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(b"hello, world").unwrap();
+        let compressed = encoder.finish().unwrap();
+        assert_eq!(aid, ArtifactId::id_bytes(&compressed));
+
+        // The level changes the compressed bytes, and so the ID.
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::none());
+        encoder.write_all(b"hello, world").unwrap();
+        let uncompressed: ArtifactId<Sha256> = encoder.into_artifact_id().unwrap();
+        assert_ne!(aid, uncompressed);
+    }
+
+    #[test]
+    fn truncated_gzip_fails() {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(b"hello, world").unwrap();
+        let compressed = encoder.finish().unwrap();
+        let truncated = &compressed[..compressed.len() / 2];
+
+        let result: crate::Result<ArtifactId<Sha256>> =
+            GzDecoder::new(truncated).into_artifact_id();
+        assert!(result.is_err());
+    }
+}
+
+#[cfg(feature = "bzip2")]
+mod bzip2_test {
+    use crate::hashes::Sha256;
+    use crate::ArtifactId;
+    use crate::IntoArtifactId;
+    use bzip2::read::BzDecoder;
+    use bzip2::write::BzEncoder;
+    use bzip2::Compression;
+    use std::io::Write as _;
+
+    #[test]
+    fn bzip2_identifies_decompressed_content() {
+        let mut encoder = BzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(b"hello, world").unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        let aid: ArtifactId<Sha256> = BzDecoder::new(&compressed[..]).into_artifact_id().unwrap();
+        assert_eq!(aid, ArtifactId::id_str("hello, world"));
+    }
+}
+
+#[cfg(feature = "http")]
+mod http_test {
+    use crate::hashes::Sha256;
+    use crate::ArtifactId;
+    use crate::IntoArtifactId;
+    use bytes::Bytes;
+    use http::Response;
+
+    #[test]
+    fn response_identifies_body_only() {
+        let response = Response::builder()
+            .header("content-type", "text/plain")
+            .body(Bytes::from_static(b"hello\r\n"))
+            .unwrap();
+
+        let by_ref: ArtifactId<Sha256> = (&response).into_artifact_id().unwrap();
+        let by_value: ArtifactId<Sha256> = response.into_artifact_id().unwrap();
+
+        assert_eq!(by_ref, ArtifactId::id_bytes(b"hello\n"));
+        assert_eq!(by_value, by_ref);
+    }
+}
+
+#[cfg(feature = "reqwest")]
+mod reqwest_test {
+    use crate::hashes::Sha256;
+    use crate::ArtifactId;
+    use crate::IntoArtifactId;
+    use std::io::Read as _;
+    use std::io::Write as _;
+    use std::net::TcpListener;
+    use std::thread;
+
+    #[test]
+    fn blocking_response_identifies_body() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 1024];
+            let _ = stream.read(&mut request).unwrap();
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 12\r\nConnection: close\r\n\r\nhello, world")
+                .unwrap();
+        });
+
+        let response = reqwest::blocking::get(format!("http://{}/", addr)).unwrap();
+        let aid: ArtifactId<Sha256> = response.into_artifact_id().unwrap();
+        server.join().unwrap();
+
+        assert_eq!(aid, ArtifactId::id_str("hello, world"));
+    }
+
+    #[test]
+    fn async_response_identifies_body() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        // Send the body in two writes, so it can arrive in more than one chunk.
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 1024];
+            let _ = stream.read(&mut request).unwrap();
+            stream
+                .write_all(
+                    b"HTTP/1.1 200 OK\r\nContent-Length: 13\r\nConnection: close\r\n\r\nhello,\r\n",
+                )
+                .unwrap();
+            stream.flush().unwrap();
+            stream.write_all(b"world").unwrap();
+        });
+
+        let aid: ArtifactId<Sha256> = tokio_test::block_on(async {
+            let response = reqwest::get(format!("http://{}/", addr)).await.unwrap();
+            ArtifactId::id_async_response(response).await.unwrap()
+        });
+        server.join().unwrap();
+
+        assert_eq!(aid, ArtifactId::id_str("hello,\nworld"));
+    }
+}
+
+#[cfg(feature = "uuid")]
+mod uuid_test {
+    use crate::hashes::Sha256;
+    use crate::ArtifactId;
+    use uuid::Variant;
+
+    #[test]
+    fn lossy_uuid_is_hash_prefix() {
+        let id = ArtifactId::<Sha256>::id_str("hello, world");
+        assert_eq!(id.to_uuid_lossy().as_bytes(), &id.as_bytes()[..16]);
+    }
+
+    #[test]
+    fn v8_uuid_uses_full_hash() {
+        let id = ArtifactId::<Sha256>::id_str("hello, world");
+        let uuid = id.to_uuid_v8();
+
+        assert_eq!(uuid.get_version_num(), 8);
+        assert_eq!(uuid.get_variant(), Variant::RFC4122);
+        assert_ne!(uuid.as_bytes(), &id.as_bytes()[..16]);
+    }
+}
+
+#[cfg(feature = "semver")]
+mod semver_test {
+    use crate::hashes::Sha256;
+    use crate::ArtifactId;
+    use crate::IntoArtifactId;
+    use semver::Version;
+    use semver::VersionReq;
+
+    #[test]
+    fn version_identifies_canonical_string() {
+        let version = Version::parse("1.2.3-beta.1+build.5").unwrap();
+        let by_ref: ArtifactId<Sha256> = (&version).into_artifact_id().unwrap();
+        let by_value: ArtifactId<Sha256> = version.into_artifact_id().unwrap();
+
+        assert_eq!(by_ref, ArtifactId::id_str("1.2.3-beta.1+build.5"));
+        assert_eq!(by_value, by_ref);
+    }
+
+    #[test]
+    fn version_req_is_normalized() {
+        let spaced: ArtifactId<Sha256> = VersionReq::parse(">= 1.2, < 2")
+            .unwrap()
+            .into_artifact_id()
+            .unwrap();
+        let compact: ArtifactId<Sha256> = VersionReq::parse(">=1.2,<2")
+            .unwrap()
+            .into_artifact_id()
+            .unwrap();
+
+        assert_eq!(spaced, compact);
+        assert_eq!(spaced, ArtifactId::id_str(">=1.2, <2"));
+    }
+}
+
+#[cfg(feature = "parallel-hashing")]
+mod parallel_test {
+    use crate::hashes::Sha256;
+    use crate::ArtifactId;
+    use pathbuf::pathbuf;
+    use std::fs::remove_file;
+    use std::fs::write;
+
+    #[test]
+    fn parallel_matches_sequential() {
+        let path = pathbuf![env!("CARGO_MANIFEST_DIR"), "test", "parallel_test_file"];
+
+        // Chunks of 3 bytes split several of these CRLF pairs across chunks.
+        let content = b"ab\r\ncd\r\n\r\nefg\rhi\r\n".repeat(50);
+        write(&path, &content).unwrap();
+
+        for chunk_size in [0, 1, 3, 7, 4096] {
+            let id = ArtifactId::<Sha256>::compute_parallel(&path, chunk_size).unwrap();
+            assert_eq!(id, ArtifactId::id_bytes(&content));
+        }
+
+        remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn parallel_hello_world() {
+        let path = pathbuf![
+            env!("CARGO_MANIFEST_DIR"),
+            "test",
+            "data",
+            "hello_world.txt"
+        ];
+        let id = ArtifactId::<Sha256>::compute_parallel(&path, 4).unwrap();
+        assert_eq!(
+            id.as_hex(),
+            "fee53a18d32820613c0527aa79be5cb30173c823a9b448fa4817767cc84c6f03"
+        );
+    }
+}
+
+#[cfg(feature = "map-identify")]
+mod map_test {
+    use crate::hashes::Sha256;
+    use crate::ArtifactId;
+    use crate::IntoArtifactId;
+    use std::collections::BTreeMap;
+    use std::collections::HashMap;
+
+    fn entries() -> [(String, Vec<u8>); 3] {
+        [
+            ("lib.a".to_owned(), vec![1, 2, 3]),
+            ("app".to_owned(), b"binary".to_vec()),
+            ("config".to_owned(), Vec::new()),
+        ]
+    }
+
+    #[test]
+    fn map_id_ignores_insertion_order() {
+        let forward = entries().into_iter().collect::<BTreeMap<_, _>>();
+        let reverse = entries().into_iter().rev().collect::<BTreeMap<_, _>>();
+        let hashed = entries().into_iter().rev().collect::<HashMap<_, _>>();
+
+        let forward: ArtifactId<Sha256> = forward.into_artifact_id().unwrap();
+        let reverse: ArtifactId<Sha256> = reverse.into_artifact_id().unwrap();
+        let hashed: ArtifactId<Sha256> = hashed.into_artifact_id().unwrap();
+
+        assert_eq!(forward, reverse);
+        assert_eq!(forward, hashed);
+    }
+
+    #[test]
+    fn map_id_uses_documented_format() {
+        let map = entries().into_iter().collect::<BTreeMap<_, _>>();
+        let aid: ArtifactId<Sha256> = map.into_artifact_id().unwrap();
+        assert_eq!(
+            aid,
+            ArtifactId::id_bytes(b"app\0binary\0config\0\0lib.a\0\x01\x02\x03\0")
+        );
+    }
+}
+
+mod similarity_test {
+    use crate::hashes::Sha256;
+    use crate::ArtifactId;
+    use std::str::FromStr;
+
+    #[test]
+    fn identical_ids_have_no_distance() {
+        let id = ArtifactId::<Sha256>::id_str("hello, world");
+        assert_eq!(id.hamming_distance(&id), 0);
+        assert_eq!(id.bytes_differ(&id), 0);
+    }
+
+    #[test]
+    fn distance_counts_bits_and_bytes() {
+        let zeros = format!("gitoid:blob:sha256:{}", "00".repeat(32));
+        let mixed = format!("gitoid:blob:sha256:{}ff03", "00".repeat(30));
+        let a = ArtifactId::<Sha256>::from_str(&zeros).unwrap();
+        let b = ArtifactId::<Sha256>::from_str(&mixed).unwrap();
+
+        assert_eq!(a.hamming_distance(&b), 10);
+        assert_eq!(a.bytes_differ(&b), 2);
+        assert_eq!(b.hamming_distance(&a), 10);
+    }
+}
+
+mod raw_bytes_test {
+    use crate::hashes::Sha256;
+    use crate::ArtifactId;
+    use crate::Error;
+
+    #[test]
+    fn round_trips_hash_bytes() {
+        let id = ArtifactId::<Sha256>::id_str("hello, world");
+        let from_slice = ArtifactId::<Sha256>::try_from(id.as_bytes()).unwrap();
+        assert_eq!(from_slice, id);
+
+        let array: [u8; 32] = id.as_bytes().try_into().unwrap();
+        assert_eq!(ArtifactId::from(&array), id);
+        assert_eq!(ArtifactId::from(array), id);
+        assert_eq!(ArtifactId::<Sha256>::from_raw_hash(array), id);
+    }
+
+    #[test]
+    fn borrows_hash_bytes() {
+        use std::collections::BTreeSet;
+        use std::collections::HashSet;
+
+        let id = ArtifactId::<Sha256>::id_str("hello, world");
+        let other = ArtifactId::<Sha256>::id_str("goodbye, world");
+        assert_eq!(AsRef::<[u8]>::as_ref(&id), id.as_bytes());
+
+        let hashed = HashSet::from([id, other]);
+        assert!(hashed.contains(id.as_bytes()));
+
+        let ordered = BTreeSet::from([id, other]);
+        assert!(ordered.contains(other.as_bytes()));
+        assert!(!ordered.contains(&[0u8; 32][..]));
+    }
+
+    #[test]
+    fn rejects_wrong_length() {
+        let result = ArtifactId::<Sha256>::try_from(&[0u8; 20][..]);
+        assert!(matches!(
+            result,
+            Err(Error::WrongByteSliceLength {
+                expected: 32,
+                got: 20
+            })
+        ));
+    }
+}
+
+mod hash_test {
+    use crate::hashes::Sha256;
+    use crate::ArtifactId;
+    use pathbuf::pathbuf;
+    use std::collections::HashMap;
+    use std::fs::File;
+    use std::hash::BuildHasher as _;
+    use std::hash::RandomState;
+    use std::str::FromStr;
+
+    #[test]
+    fn equal_ids_hash_equally() {
+        let state = RandomState::new();
+        let id = ArtifactId::<Sha256>::id_str("hello, world");
+        let from_url = ArtifactId::<Sha256>::from_str(&id.to_string()).unwrap();
+        let from_bytes = ArtifactId::<Sha256>::try_from(id.as_bytes()).unwrap();
+
+        for equal in [from_url, from_bytes] {
+            assert_eq!(equal, id);
+            assert_eq!(state.hash_one(equal), state.hash_one(id));
+        }
+    }
+
+    #[test]
+    fn ids_differing_in_last_byte_are_distinct_keys() {
+        let ids = (0..=u8::MAX)
+            .map(|last| {
+                let mut bytes = [0xAB; 32];
+                bytes[31] = last;
+                ArtifactId::<Sha256>::from(bytes)
+            })
+            .collect::<Vec<_>>();
+
+        let map = ids
+            .iter()
+            .enumerate()
+            .map(|(index, id)| (*id, index))
+            .collect::<HashMap<_, _>>();
+
+        assert_eq!(map.len(), ids.len());
+
+        for (index, id) in ids.iter().enumerate() {
+            assert_eq!(map[id], index);
+        }
+    }
+
+    #[test]
+    fn same_file_read_twice_is_one_key() {
+        let path = pathbuf![
+            env!("CARGO_MANIFEST_DIR"),
+            "test",
+            "data",
+            "hello_world.txt"
+        ];
+        let first = ArtifactId::<Sha256>::id_reader(File::open(&path).unwrap()).unwrap();
+        let second = ArtifactId::<Sha256>::id_reader(File::open(&path).unwrap()).unwrap();
+
+        let mut map = HashMap::new();
+        map.insert(first, "first");
+        assert_eq!(map.insert(second, "second"), Some("first"));
+        assert_eq!(map.len(), 1);
+        assert_eq!(map.get(&first), Some(&"second"));
+    }
+}
+
+mod gitoid_test {
+    use crate::hashes::Sha256;
+    use crate::hashes::SupportedHash;
+    use crate::ArtifactId;
+    use gitoid::Blob;
+    use gitoid::GitOid;
+
+    type Sha256GitOid = GitOid<<Sha256 as SupportedHash>::HashAlgorithm, Blob>;
+
+    #[test]
+    fn converts_to_and_from_gitoid() {
+        let id = ArtifactId::<Sha256>::id_str("hello, world");
+        let gitoid = Sha256GitOid::from(id);
+        assert_eq!(gitoid.as_bytes(), id.as_bytes());
+        assert_eq!(ArtifactId::from(gitoid), id);
+
+        let gitoid = Sha256GitOid::id_str("hello, world");
+        assert_eq!(ArtifactId::<Sha256>::from(gitoid), id);
+    }
+}
+
+#[cfg(feature = "nix-compat")]
+mod nix_test {
+    use crate::hashes::Sha256;
+    use crate::ArtifactId;
+    use crate::Error;
+    use std::str::FromStr;
+
+    // The SHA-256 hash of the empty string, as printed by `nix hash convert`.
+    const EMPTY_HEX: &str = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+    const EMPTY_NIX: &str = "0mdqa9w1p6cmli6976v4wi0sw9r4p5prkj7lzfd1877wk11c9c73";
+
+    #[test]
+    fn encodes_known_hash() {
+        let url = format!("gitoid:blob:sha256:{}", EMPTY_HEX);
+        let id = ArtifactId::<Sha256>::from_str(&url).unwrap();
+        assert_eq!(id.to_nix_hash(), EMPTY_NIX);
+        assert_eq!(ArtifactId::from_nix_hash(EMPTY_NIX).unwrap(), id);
+    }
+
+    #[test]
+    fn round_trips() {
+        let id = ArtifactId::<Sha256>::id_str("hello, world");
+        assert_eq!(ArtifactId::from_nix_hash(&id.to_nix_hash()).unwrap(), id);
+    }
+
+    #[test]
+    fn rejects_invalid_hashes() {
+        // 'e' isn't in the Nix base32 alphabet.
+        let bad_char = EMPTY_NIX.replacen('0', "e", 1);
+        assert!(matches!(
+            ArtifactId::<Sha256>::from_nix_hash(&bad_char),
+            Err(Error::InvalidNixHash(_))
+        ));
+
+        // The first character holds the last bits, which must not overflow.
+        let overflow = EMPTY_NIX.replacen('0', "z", 1);
+        assert!(ArtifactId::<Sha256>::from_nix_hash(&overflow).is_err());
+
+        // A valid encoding of a 20-byte hash.
+        assert!(matches!(
+            ArtifactId::<Sha256>::from_nix_hash(&"0".repeat(32)),
+            Err(Error::WrongByteSliceLength {
+                expected: 32,
+                got: 20
+            })
+        ));
+    }
+}
+
+mod sha256sum_test {
+    use crate::hashes::Sha256;
+    use crate::ArtifactId;
+    use crate::Error;
+    use std::path::Path;
+    use std::str::FromStr;
+
+    const EMPTY_HEX: &str = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+
+    fn empty_aid() -> ArtifactId<Sha256> {
+        ArtifactId::from_str(&format!("gitoid:blob:sha256:{}", EMPTY_HEX)).unwrap()
+    }
+
+    #[test]
+    fn parses_text_and_binary_lines() {
+        for line in [
+            format!("{}  dir/empty file.txt\n", EMPTY_HEX),
+            format!("{} *dir/empty file.txt", EMPTY_HEX),
+        ] {
+            let (aid, path) = ArtifactId::from_sha256sum_line(&line).unwrap();
+            assert_eq!(aid, empty_aid());
+            assert_eq!(path, Path::new("dir/empty file.txt"));
+        }
+    }
+
+    #[test]
+    fn unescapes_paths() {
+        let line = format!("\\{}  a\\\\b\\nc", EMPTY_HEX);
+        let (_, path) = ArtifactId::from_sha256sum_line(&line).unwrap();
+        assert_eq!(path, Path::new("a\\b\nc"));
+    }
+
+    #[test]
+    fn rejects_invalid_lines() {
+        for line in [
+            String::from("not a checksum line"),
+            format!("{} file.txt", EMPTY_HEX),
+            format!("{}  ", EMPTY_HEX),
+            format!("{}  file.txt", &EMPTY_HEX[2..]),
+            format!("{}  file.txt", EMPTY_HEX.replace('e', "g")),
+            format!("\\{}  bad\\escape", EMPTY_HEX),
+        ] {
+            assert!(matches!(
+                ArtifactId::from_sha256sum_line(&line),
+                Err(Error::InvalidSha256SumLine(_))
+            ));
+        }
+    }
+}
+
+#[cfg(feature = "cmd-identify")]
+mod cmd_test {
+    use crate::hashes::Sha256;
+    use crate::ArtifactId;
+    use crate::IntoArtifactId;
+    use std::process::Command;
+
+    #[test]
+    fn cmd_id_uses_documented_format() {
+        let mut cmd = Command::new("cc");
+        cmd.args(["-O2", "-c", "main.c"]);
+
+        let aid: ArtifactId<Sha256> = (&cmd).into_artifact_id().unwrap();
+        assert_eq!(aid, ArtifactId::id_bytes(b"cc\0-O2\0-c\0main.c\0"));
+    }
+
+    #[test]
+    fn cmd_id_changes_with_args() {
+        let mut first = Command::new("cc");
+        first.args(["-O2", "main.c"]);
+
+        let mut second = Command::new("cc");
+        second.args(["-O3", "main.c"]);
+
+        let first: ArtifactId<Sha256> = (&first).into_artifact_id().unwrap();
+        let second: ArtifactId<Sha256> = (&second).into_artifact_id().unwrap();
+        assert_ne!(first, second);
+    }
+}
+
+#[cfg(all(unix, feature = "process-identify"))]
+mod process_test {
+    use crate::hashes::Sha256;
+    use crate::ArtifactId;
+    use crate::Error;
+    use crate::IntoArtifactId;
+    use std::os::unix::process::ExitStatusExt as _;
+    use std::process::ExitStatus;
+    use std::process::Output;
+
+    fn output(code: i32) -> Output {
+        Output {
+            status: ExitStatus::from_raw(code << 8),
+            stdout: b"generated code".to_vec(),
+            stderr: b"warning: unused".to_vec(),
+        }
+    }
+
+    #[test]
+    fn output_id_is_stdout_only() {
+        let aid: ArtifactId<Sha256> = (&output(0)).into_artifact_id().unwrap();
+        assert_eq!(aid, ArtifactId::id_str("generated code"));
+    }
+
+    #[test]
+    fn failed_output_is_an_error() {
+        let result: Result<ArtifactId<Sha256>, _> = output(1).into_artifact_id();
+        assert!(
+            matches!(result, Err(Error::NonZeroExitStatus(status)) if status.code() == Some(1))
+        );
+    }
+}
+
+#[cfg(feature = "subtle")]
+mod subtle_test {
+    use crate::hashes::Sha256;
+    use crate::ArtifactId;
+
+    #[test]
+    fn constant_time_eq_agrees_with_eq() {
+        let first = ArtifactId::<Sha256>::id_str("hello, world");
+        let second = ArtifactId::<Sha256>::id_str("hello, world");
+        let other = ArtifactId::<Sha256>::id_str("hello world");
+
+        assert_eq!(first.eq_constant_time(&second), first == second);
+        assert_eq!(first.eq_constant_time(&other), first == other);
+        assert!(first.eq_constant_time(&second));
+        assert!(!first.eq_constant_time(&other));
+    }
+}
+
+#[cfg(feature = "ndarray")]
+mod ndarray_test {
+    use crate::hashes::Sha256;
+    use crate::ArtifactId;
+    use crate::IntoArtifactId;
+    use ndarray::ArrayD;
+    use ndarray::IxDyn;
+
+    fn array(shape: &[usize]) -> ArrayD<u8> {
+        ArrayD::from_shape_vec(IxDyn(shape), (0..6).collect()).unwrap()
+    }
+
+    #[test]
+    fn ndarray_id_uses_documented_format() {
+        let aid: ArtifactId<Sha256> = array(&[2, 3]).into_artifact_id().unwrap();
+
+        let mut expected = Vec::new();
+        expected.extend_from_slice(&2u64.to_le_bytes());
+        expected.extend_from_slice(&2u64.to_le_bytes());
+        expected.extend_from_slice(&3u64.to_le_bytes());
+        expected.extend_from_slice(&[0, 1, 2, 3, 4, 5]);
+
+        assert_eq!(aid, ArtifactId::id_bytes(expected));
+    }
+
+    #[test]
+    fn ndarray_id_depends_on_shape() {
+        let wide: ArtifactId<Sha256> = array(&[2, 3]).into_artifact_id().unwrap();
+        let tall: ArtifactId<Sha256> = array(&[3, 2]).into_artifact_id().unwrap();
+        assert_ne!(wide, tall);
+    }
+
+    #[test]
+    fn ndarray_id_ignores_memory_layout() {
+        // Transposing twice gives the same array, laid out column-major.
+        let row_major = array(&[2, 3]);
+        let col_major = row_major.t().as_standard_layout().t().to_owned();
+        assert!(!col_major.is_standard_layout());
+
+        let row_major: ArtifactId<Sha256> = row_major.view().into_artifact_id().unwrap();
+        let col_major: ArtifactId<Sha256> = col_major.view().into_artifact_id().unwrap();
+        assert_eq!(row_major, col_major);
+    }
+}
+
+#[cfg(feature = "opentelemetry")]
+mod otel_test {
+    use crate::add_artifact_id_attribute;
+    use crate::hashes::Sha256;
+    use crate::ArtifactId;
+    use opentelemetry::trace::Span;
+    use opentelemetry::trace::SpanContext;
+    use opentelemetry::trace::Status;
+    use opentelemetry::KeyValue;
+    use opentelemetry::Value;
+    use std::borrow::Cow;
+    use std::time::SystemTime;
+
+    /// A span which just records its attributes.
+    struct RecordingSpan {
+        context: SpanContext,
+        attributes: Vec<KeyValue>,
+    }
+
+    impl Span for RecordingSpan {
+        fn add_event_with_timestamp<T>(&mut self, _: T, _: SystemTime, _: Vec<KeyValue>)
+        where
+            T: Into<Cow<'static, str>>,
+        {
+        }
+
+        fn span_context(&self) -> &SpanContext {
+            &self.context
+        }
+
+        fn is_recording(&self) -> bool {
+            true
+        }
+
+        fn set_attribute(&mut self, attribute: KeyValue) {
+            self.attributes.push(attribute);
+        }
+
+        fn set_status(&mut self, _: Status) {}
+
+        fn update_name<T>(&mut self, _: T)
+        where
+            T: Into<Cow<'static, str>>,
+        {
+        }
+
+        fn add_link(&mut self, _: SpanContext, _: Vec<KeyValue>) {}
+
+        fn end_with_timestamp(&mut self, _: SystemTime) {}
+    }
+
+    #[test]
+    fn artifact_id_converts_to_gitoid_value() {
+        let aid = ArtifactId::<Sha256>::id_str("hello, world");
+        assert_eq!(Value::from(aid).as_str(), aid.to_string());
+    }
+
+    #[test]
+    fn artifact_id_attribute_is_set() {
+        let aid = ArtifactId::<Sha256>::id_str("hello, world");
+        let mut span = RecordingSpan {
+            context: SpanContext::empty_context(),
+            attributes: Vec::new(),
+        };
+
+        add_artifact_id_attribute(&mut span, "omnibor.artifact_id", aid);
+
+        assert_eq!(
+            span.attributes,
+            vec![KeyValue::new("omnibor.artifact_id", aid.to_string())]
+        );
+    }
+}
+
+#[cfg(feature = "zeroize")]
+mod zeroize_test {
+    use crate::hashes::Sha256;
+    use crate::ArtifactId;
+    use zeroize::Zeroize as _;
+
+    #[test]
+    fn zeroizes_hash_bytes() {
+        let mut id = ArtifactId::<Sha256>::id_str("hello, world");
+        id.zeroize();
+        assert_eq!(id.as_bytes(), &[0; 32]);
+        assert_eq!(id.hash_algorithm(), "sha256");
+    }
+}
+
+#[cfg(feature = "hash-blake3")]
+mod blake3_test {
+    use crate::hashes::Blake3;
+    use crate::storage::FileSystemStorage;
+    use crate::storage::Storage;
+    use crate::ArtifactId;
+    use crate::InputManifest;
+    use crate::Relation;
+    use pathbuf::pathbuf;
+    use std::str::FromStr;
+
+    #[test]
+    fn id_bytes_round_trips() {
+        let id = ArtifactId::<Blake3>::id_bytes(b"hello world");
+        let expected = format!(
+            "gitoid:blob:blake3:{}",
+            blake3::hash(b"blob 11\0hello world").to_hex()
+        );
+
+        assert_eq!(id.hash_algorithm(), "blake3");
+        assert_eq!(id.to_string(), expected);
+        assert_eq!(ArtifactId::<Blake3>::from_str(&expected).unwrap(), id);
+    }
+
+    #[test]
+    fn stores_manifest_by_algorithm() {
+        let root = pathbuf![env!("CARGO_MANIFEST_DIR"), "test", "fs_storage_blake3"];
+        let mut storage = FileSystemStorage::new(&root).unwrap();
+
+        let relation = Relation::new_without_manifest(ArtifactId::<Blake3>::id_str("input"));
+        let manifest = InputManifest::with_relations([relation].into_iter());
+        let manifest_aid = storage.write_manifest(&manifest).unwrap();
+
+        let hex = manifest_aid.as_hex();
+        let path = pathbuf![
+            &root,
+            "manifests",
+            "gitoid_blob_blake3",
+            &hex[..2],
+            &hex[2..]
+        ];
+        assert!(path.is_file());
+        let stored = storage.get_manifests().unwrap();
+        assert_eq!(stored.len(), 1);
+        assert_eq!(stored[0].relations(), manifest.relations());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
+
+#[cfg(feature = "hash-sha3-256")]
+mod sha3_256_test {
+    use crate::hashes::Sha3_256;
+    use crate::ArtifactId;
+    use crate::InputManifest;
+    use crate::IntoArtifactId;
+    use crate::Relation;
+    use pathbuf::pathbuf;
+    use std::str::FromStr;
+
+    const HELLO_WORLD_URL: &str =
+        "gitoid:blob:sha3-256:254d5b29c6d7dc776a6281cc639d94f8762b02c47786ebd59fa2421a2a9599ce";
+
+    #[test]
+    fn identifies_file_and_round_trips() {
+        let path = pathbuf![
+            env!("CARGO_MANIFEST_DIR"),
+            "test",
+            "data",
+            "hello_world.txt"
+        ];
+        let id: ArtifactId<Sha3_256> = path.as_path().into_artifact_id().unwrap();
+
+        assert_eq!(id.hash_algorithm(), "sha3-256");
+        assert_eq!(id.to_string(), HELLO_WORLD_URL);
+        assert_eq!(
+            ArtifactId::<Sha3_256>::from_str(HELLO_WORLD_URL).unwrap(),
+            id
+        );
+    }
+
+    #[test]
+    fn manifest_round_trips() {
+        let relation = Relation::new_without_manifest(ArtifactId::<Sha3_256>::id_str("input"));
+        let manifest = InputManifest::with_relations([relation].into_iter());
+        let bytes = manifest.as_bytes().unwrap();
+        assert!(bytes.starts_with(b"gitoid:blob:sha3-256\n"));
+
+        let parsed = InputManifest::<Sha3_256>::from_reader(&bytes[..]).unwrap();
+        assert_eq!(parsed.relations(), manifest.relations());
+    }
+}
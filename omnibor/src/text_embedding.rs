@@ -0,0 +1,234 @@
+//! Read embedded manifest [`ArtifactId`]s from text files.
+//!
+//! OmniBOR embeds the [`ArtifactId`] of a text file's input manifest in a
+//! comment on its own line, written as:
+//!
+//! ```text
+//! <comment prefix> OmniBOR-Input-Manifest: <AID> [comment suffix]
+//! ```
+//!
+//! The comment suffix is only present for languages whose comments are
+//! wrapped, like `/* ... */` in C.
+//!
+//! XML files instead hold the [`ArtifactId`] in a processing instruction,
+//! which may share a line with other markup:
+//!
+//! ```text
+//! <?omnibor-input-manifest aid="<AID>"?>
+//! ```
+
+use crate::hashes::SupportedHash;
+use crate::ArtifactId;
+use crate::Result;
+use gitoid::HashAlgorithm;
+use std::fs;
+use std::ops::Not as _;
+use std::path::Path;
+use std::str::FromStr;
+
+/// The marker which precedes an embedded [`ArtifactId`].
+const MARKER: &str = "OmniBOR-Input-Manifest:";
+
+/// The start of the XML processing instruction holding an [`ArtifactId`].
+const XML_INSTRUCTION_START: &str = "<?omnibor-input-manifest aid=\"";
+
+/// The end of the XML processing instruction holding an [`ArtifactId`].
+const XML_INSTRUCTION_END: &str = "\"?>";
+
+/// The comment prefixes an embedded [`ArtifactId`] may follow.
+///
+/// Longer prefixes come before shorter ones which start the same way.
+const COMMENT_PREFIXES: &[&str] = &["<!--", "/*", "(*", "{-", "//", "--", "#", ";", "%", "'"];
+
+/// Read the manifest [`ArtifactId`] embedded in a text file, if any.
+///
+/// Returns the first embedded ID for the hash algorithm `H`, or `Ok(None)`
+/// if there isn't one. IDs for other hash algorithms are skipped. Returns
+/// an [`Error`](crate::Error) if the file can't be read, or if an embedded
+/// ID is malformed.
+pub fn read_embedded_aid_from_text<H: SupportedHash>(path: &Path) -> Result<Option<ArtifactId<H>>> {
+    let content = fs::read_to_string(path)?;
+    find_embedded_aid(&content)
+}
+
+/// Find the first embedded [`ArtifactId`] for `H` in some text.
+pub(crate) fn find_embedded_aid<H: SupportedHash>(content: &str) -> Result<Option<ArtifactId<H>>> {
+    find_embedded_aid_with(content, |line| {
+        COMMENT_PREFIXES
+            .iter()
+            .find_map(|prefix| embedded_aid_text(line, prefix))
+            .or_else(|| xml_instruction_aid_text(line))
+    })
+}
+
+/// Get the XML processing instruction which embeds an [`ArtifactId`].
+pub(crate) fn xml_processing_instruction<H: SupportedHash>(aid: ArtifactId<H>) -> String {
+    format!("{}{}{}", XML_INSTRUCTION_START, aid, XML_INSTRUCTION_END)
+}
+
+/// Find the first [`ArtifactId`] for `H` embedded after a specific comment
+/// prefix in some text.
+pub(crate) fn find_embedded_aid_after_prefix<H: SupportedHash>(
+    content: &str,
+    prefix: &str,
+) -> Result<Option<ArtifactId<H>>> {
+    find_embedded_aid_with(content, |line| embedded_aid_text(line, prefix))
+}
+
+/// Embed an [`ArtifactId`] in some text, in a comment on its own line.
+///
+/// The comment is added at the end, so it never displaces lines which must
+/// come first, like shebang lines. Any ID for `H` already embedded after the
+/// same prefix is removed, so there's only ever one. The `suffix` is for
+/// languages whose comments are wrapped, and is empty otherwise.
+pub(crate) fn insert_comment<H: SupportedHash>(
+    content: &str,
+    prefix: &str,
+    suffix: &str,
+    aid: ArtifactId<H>,
+) -> String {
+    let scheme = scheme::<H>();
+    let newline = if content.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+
+    let mut embedded = content
+        .split_inclusive('\n')
+        .filter(|line| {
+            embedded_aid_text(line, prefix).is_none_or(|text| text.starts_with(&scheme).not())
+        })
+        .collect::<String>();
+
+    if embedded.is_empty().not() && embedded.ends_with('\n').not() {
+        embedded.push_str(newline);
+    }
+
+    embedded.push_str(&format!("{} {} {}", prefix, MARKER, aid));
+    if suffix.is_empty().not() {
+        embedded.push_str(&format!(" {}", suffix));
+    }
+    embedded.push_str(newline);
+    embedded
+}
+
+/// Find the first embedded [`ArtifactId`] for `H`, using `aid_text` to get
+/// the text of the ID on each line.
+fn find_embedded_aid_with<'c, H: SupportedHash>(
+    content: &'c str,
+    aid_text: impl Fn(&'c str) -> Option<&'c str>,
+) -> Result<Option<ArtifactId<H>>> {
+    let scheme = scheme::<H>();
+
+    for line in content.lines() {
+        let Some(aid) = aid_text(line) else {
+            continue;
+        };
+
+        if aid.starts_with(&scheme) {
+            return ArtifactId::from_str(aid).map(Some);
+        }
+    }
+
+    Ok(None)
+}
+
+/// Get the start of every [`ArtifactId`] URL for `H`.
+fn scheme<H: SupportedHash>() -> String {
+    format!("gitoid:blob:{}:", H::HashAlgorithm::NAME)
+}
+
+/// Get the text of the [`ArtifactId`] in an XML processing instruction on a
+/// line, if it has one.
+fn xml_instruction_aid_text(line: &str) -> Option<&str> {
+    let (_, rest) = line.split_once(XML_INSTRUCTION_START)?;
+    let (aid, _) = rest.split_once(XML_INSTRUCTION_END)?;
+    Some(aid)
+}
+
+/// Get the text of the [`ArtifactId`] embedded after `prefix` on a line,
+/// if it has one.
+fn embedded_aid_text<'l>(line: &'l str, prefix: &str) -> Option<&'l str> {
+    line.trim_start()
+        .strip_prefix(prefix)?
+        .trim_start()
+        .strip_prefix(MARKER)?
+        .split_whitespace()
+        .next()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hashes::Sha256;
+
+    #[test]
+    fn finds_aid_after_any_prefix() {
+        let aid = ArtifactId::<Sha256>::id_str("manifest");
+
+        for (prefix, suffix) in [("#", ""), ("//", ""), ("/*", " */"), ("<!--", " -->")] {
+            let content = format!(
+                "first line\n  {} {} {}{}\nlast line\n",
+                prefix, MARKER, aid, suffix
+            );
+            assert_eq!(find_embedded_aid(&content).unwrap(), Some(aid));
+        }
+    }
+
+    #[test]
+    fn inserted_comment_replaces_existing_one() {
+        let old = ArtifactId::<Sha256>::id_str("old");
+        let new = ArtifactId::<Sha256>::id_str("new");
+
+        let content = "#!/bin/sh\necho hi";
+        let embedded = insert_comment(content, "#", "", old);
+        assert_eq!(
+            embedded,
+            format!("#!/bin/sh\necho hi\n# {} {}\n", MARKER, old)
+        );
+
+        let embedded = insert_comment(&embedded, "#", "", new);
+        assert_eq!(
+            embedded,
+            format!("#!/bin/sh\necho hi\n# {} {}\n", MARKER, new)
+        );
+        assert_eq!(find_embedded_aid(&embedded).unwrap(), Some(new));
+
+        let embedded = insert_comment("int x;\r\n", "/*", "*/", new);
+        assert_eq!(embedded, format!("int x;\r\n/* {} {} */\r\n", MARKER, new));
+        assert_eq!(find_embedded_aid(&embedded).unwrap(), Some(new));
+    }
+
+    #[test]
+    fn finds_aid_in_xml_processing_instruction() {
+        let aid = ArtifactId::<Sha256>::id_str("manifest");
+        let content = format!(
+            "<project>\n  <a/>{}</project>\n",
+            xml_processing_instruction(aid)
+        );
+        assert_eq!(find_embedded_aid(&content).unwrap(), Some(aid));
+    }
+
+    #[test]
+    fn missing_marker_is_none() {
+        let content = "# a comment\n// OmniBOR is mentioned, but not embedded\n";
+        assert_eq!(find_embedded_aid::<Sha256>(content).unwrap(), None);
+    }
+
+    #[test]
+    fn skips_other_hash_algorithms() {
+        let aid = ArtifactId::<Sha256>::id_str("manifest");
+        let content = format!(
+            "# {} gitoid:blob:sha1:95d09f2b10159347eece71399a7e2e907ea3df4f\n# {} {}\n",
+            MARKER, MARKER, aid
+        );
+        assert_eq!(find_embedded_aid(&content).unwrap(), Some(aid));
+    }
+
+    #[test]
+    fn malformed_aid_fails() {
+        let content = format!("# {} gitoid:blob:sha256:not-hex\n", MARKER);
+        assert!(find_embedded_aid::<Sha256>(&content).is_err());
+    }
+}
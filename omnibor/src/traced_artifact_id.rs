@@ -0,0 +1,58 @@
+//! Emit `tracing` events when computing [`ArtifactId`]s.
+//!
+//! To record an [`ArtifactId`] as a field on your own events or spans, use
+//! its [`Display`](std::fmt::Display) form, which is the `gitoid` URL:
+//!
+//! ```rust
+//! # use omnibor::ArtifactId;
+//! # use omnibor::hashes::Sha256;
+//! let artifact_id = ArtifactId::<Sha256>::id_str("hello, world");
+//! tracing::info!(artifact_id = %artifact_id, "built artifact");
+//! ```
+
+use crate::hashes::SupportedHash;
+use crate::ArtifactId;
+use crate::IntoArtifactId;
+use crate::Result;
+use std::time::Instant;
+use tracing::debug;
+
+impl<H: SupportedHash> ArtifactId<H> {
+    /// Construct an [`ArtifactId`], emitting a `DEBUG` event with the result.
+    ///
+    /// On success the event has an `artifact_id` field holding the `gitoid`
+    /// URL and an `elapsed` field with how long the computation took. On
+    /// failure it has an `error` field instead. Events are emitted in the
+    /// current span, so calls inside a `#[tracing::instrument]` function are
+    /// attributed to it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use omnibor::ArtifactId;
+    /// # use omnibor::hashes::Sha256;
+    /// # use std::path::Path;
+    /// #[tracing::instrument]
+    /// fn identify(path: &Path) -> ArtifactId<Sha256> {
+    ///     ArtifactId::traced_new(path).unwrap()
+    /// }
+    ///
+    /// println!("Artifact ID: {}", identify(Path::new("test/data/hello_world.txt")));
+    /// ```
+    pub fn traced_new<I: IntoArtifactId<H>>(target: I) -> Result<ArtifactId<H>> {
+        let start = Instant::now();
+        let result = target.into_artifact_id();
+        let elapsed = start.elapsed();
+
+        match &result {
+            Ok(artifact_id) => {
+                debug!(artifact_id = %artifact_id, elapsed = ?elapsed, "computed Artifact ID")
+            }
+            Err(error) => {
+                debug!(error = %error, elapsed = ?elapsed, "failed to compute Artifact ID")
+            }
+        }
+
+        result
+    }
+}
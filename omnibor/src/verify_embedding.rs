@@ -0,0 +1,181 @@
+//! Check which manifest [`ArtifactId`], if any, is embedded in a file.
+
+use crate::elf;
+use crate::hashes::SupportedHash;
+#[cfg(feature = "jupyter-embed")]
+use crate::jupyter;
+use crate::pe;
+use crate::text_embedding;
+use crate::ArtifactId;
+#[cfg(feature = "jupyter-embed")]
+use crate::Error;
+use crate::Result;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+use std::fs;
+use std::path::Path;
+use std::str;
+
+/// The result of checking a file for an embedded manifest [`ArtifactId`].
+pub enum EmbedVerifyResult<H: SupportedHash> {
+    /// The file has an embedded manifest ID.
+    Embedded(ArtifactId<H>),
+
+    /// The file's format supports embedding, but it has no manifest ID.
+    NotEmbedded,
+
+    /// The file's format doesn't support embedding.
+    FormatUnsupported,
+}
+
+impl<H: SupportedHash> Clone for EmbedVerifyResult<H> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<H: SupportedHash> Copy for EmbedVerifyResult<H> {}
+
+impl<H: SupportedHash> PartialEq for EmbedVerifyResult<H> {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (EmbedVerifyResult::Embedded(a), EmbedVerifyResult::Embedded(b)) => a == b,
+            (EmbedVerifyResult::NotEmbedded, EmbedVerifyResult::NotEmbedded) => true,
+            (EmbedVerifyResult::FormatUnsupported, EmbedVerifyResult::FormatUnsupported) => true,
+            _ => false,
+        }
+    }
+}
+
+impl<H: SupportedHash> Eq for EmbedVerifyResult<H> {}
+
+impl<H: SupportedHash> Debug for EmbedVerifyResult<H> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            EmbedVerifyResult::Embedded(aid) => f.debug_tuple("Embedded").field(aid).finish(),
+            EmbedVerifyResult::NotEmbedded => write!(f, "NotEmbedded"),
+            EmbedVerifyResult::FormatUnsupported => write!(f, "FormatUnsupported"),
+        }
+    }
+}
+
+/// Check which manifest [`ArtifactId`], if any, is embedded in a file.
+///
+/// The file's format is detected from its contents: ELF and PE files are
+/// read with [`read_embedded_aid_from_elf`](crate::embedding::read_embedded_aid_from_elf)
+/// and [`read_embedded_aid_from_pe`](crate::embedding::read_embedded_aid_from_pe),
+/// and other UTF-8 files are read as text, like with
+/// [`read_embedded_aid_from_text`](crate::embedding::read_embedded_aid_from_text).
+/// With the `jupyter-embed` feature, `.ipynb` files are instead read from the
+/// notebook's metadata.
+/// Any other file gives [`EmbedVerifyResult::FormatUnsupported`].
+///
+/// Returns an [`Error`](crate::Error) if the file can't be read, or if it
+/// has a malformed embedded ID.
+pub fn verify_target<H: SupportedHash>(path: &Path) -> Result<EmbedVerifyResult<H>> {
+    let data = fs::read(path)?;
+
+    let aid = if elf::is_elf(&data) {
+        elf::read_embedded_aid_from_elf(path)?
+    } else if pe::is_pe(&data) {
+        pe::read_embedded_aid_from_pe(path)?
+    } else if let Ok(content) = str::from_utf8(&data) {
+        find_text_embedded_aid(path, content)?
+    } else {
+        return Ok(EmbedVerifyResult::FormatUnsupported);
+    };
+
+    Ok(match aid {
+        Some(aid) => EmbedVerifyResult::Embedded(aid),
+        None => EmbedVerifyResult::NotEmbedded,
+    })
+}
+
+/// Find the embedded ID in a text file.
+///
+/// Jupyter notebooks hold the ID in their metadata rather than a comment.
+#[cfg_attr(not(feature = "jupyter-embed"), allow(unused_variables))]
+fn find_text_embedded_aid<H: SupportedHash>(
+    path: &Path,
+    content: &str,
+) -> Result<Option<ArtifactId<H>>> {
+    #[cfg(feature = "jupyter-embed")]
+    if jupyter::is_notebook_path(path) {
+        return jupyter::find_notebook_metadata(content)
+            .map_err(|reason| Error::InvalidJupyterNotebook(path.display().to_string(), reason));
+    }
+
+    text_embedding::find_embedded_aid(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hashes::Sha256;
+    use pathbuf::pathbuf;
+
+    /// Write a test file and check it for an embedded ID.
+    fn verify(name: &str, content: &[u8]) -> EmbedVerifyResult<Sha256> {
+        let path = pathbuf![env!("CARGO_MANIFEST_DIR"), "test", name];
+        fs::write(&path, content).unwrap();
+        let result = verify_target(&path);
+        fs::remove_file(&path).unwrap();
+        result.unwrap()
+    }
+
+    #[test]
+    fn text_file_with_embedded_id() {
+        let aid = ArtifactId::<Sha256>::id_str("manifest");
+        let content = format!("# OmniBOR-Input-Manifest: {}\n", aid);
+        assert_eq!(
+            verify("verify_embedded.sh", content.as_bytes()),
+            EmbedVerifyResult::Embedded(aid)
+        );
+    }
+
+    #[test]
+    fn text_file_without_embedded_id() {
+        assert_eq!(
+            verify("verify_not_embedded.sh", b"echo hello\n"),
+            EmbedVerifyResult::NotEmbedded
+        );
+    }
+
+    #[cfg(feature = "jupyter-embed")]
+    #[test]
+    fn embedded_jupyter_notebook() {
+        use crate::embedding::Embed;
+        use crate::storage::InMemoryStorage;
+        use crate::InputManifestBuilder;
+        use crate::ShouldStore;
+
+        let path = pathbuf![env!("CARGO_MANIFEST_DIR"), "test", "verify_embedded.ipynb"];
+        fs::write(&path, r#"{"cells": [], "nbformat": 4}"#).unwrap();
+
+        let ids = InputManifestBuilder::<Sha256, Embed, _>::with_storage(InMemoryStorage::new())
+            .add_relation(ArtifactId::id_str("input"))
+            .unwrap()
+            .finish(&path, ShouldStore::Yes)
+            .unwrap();
+        let result = verify_target(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            result.unwrap(),
+            EmbedVerifyResult::Embedded(ids.manifest_aid())
+        );
+        assert_eq!(
+            verify("verify_not_embedded.ipynb", br#"{"nbformat": 4}"#),
+            EmbedVerifyResult::NotEmbedded
+        );
+    }
+
+    #[test]
+    fn unknown_binary_is_unsupported() {
+        assert_eq!(
+            verify("verify_unsupported.bin", &[0xFF, 0xFE, 0x00, 0x80]),
+            EmbedVerifyResult::FormatUnsupported
+        );
+    }
+}